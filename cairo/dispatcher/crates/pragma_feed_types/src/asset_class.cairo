@@ -1,6 +1,9 @@
 #[derive(Debug, Drop, Copy, Serde, PartialEq, Hash, starknet::Store)]
 pub enum AssetClass {
     Crypto,
+    Forex,
+    Equities,
+    Commodities,
 }
 
 pub type AssetClassId = u16;
@@ -9,15 +12,17 @@ impl AssetClassIntoAssetClassId of Into<AssetClass, AssetClassId> {
     fn into(self: AssetClass) -> AssetClassId {
         match self {
             AssetClass::Crypto => 0,
+            AssetClass::Forex => 1,
+            AssetClass::Equities => 2,
+            AssetClass::Commodities => 3,
         }
     }
 }
 
 impl AssetClassIntoAssetfelt252 of Into<AssetClass, felt252> {
     fn into(self: AssetClass) -> felt252 {
-        match self {
-            AssetClass::Crypto => 0,
-        }
+        let id: AssetClassId = self.into();
+        id.into()
     }
 }
 
@@ -25,6 +30,9 @@ impl AssetClassIntoString of Into<AssetClass, ByteArray> {
     fn into(self: AssetClass) -> ByteArray {
         match self {
             AssetClass::Crypto => "Crypto",
+            AssetClass::Forex => "Forex",
+            AssetClass::Equities => "Equities",
+            AssetClass::Commodities => "Commodities",
         }
     }
 }
@@ -33,6 +41,9 @@ impl AssetClassIdTryIntoAssetClass of TryInto<AssetClassId, AssetClass> {
     fn try_into(self: u16) -> Option<AssetClass> {
         match self {
             0 => Option::Some(AssetClass::Crypto),
+            1 => Option::Some(AssetClass::Forex),
+            2 => Option::Some(AssetClass::Equities),
+            3 => Option::Some(AssetClass::Commodities),
             _ => Option::None(())
         }
     }
@@ -4,7 +4,25 @@ use pragma_feed_types::{AssetClass, AssetClassId};
 fn test_asset_class_into_asset_class_id() {
     let crypto = AssetClass::Crypto;
     let id: AssetClassId = crypto.into();
-    assert(id == 0, 'Crypto should convert to 1');
+    assert(id == 0, 'Crypto should convert to 0');
+
+    let id: AssetClassId = AssetClass::Forex.into();
+    assert(id == 1, 'Forex should convert to 1');
+
+    let id: AssetClassId = AssetClass::Equities.into();
+    assert(id == 2, 'Equities should convert to 2');
+
+    let id: AssetClassId = AssetClass::Commodities.into();
+    assert(id == 3, 'Commodities should convert to 3');
+}
+
+#[test]
+fn test_asset_class_into_felt() {
+    let id: felt252 = AssetClass::Crypto.into();
+    assert(id == 0, 'Crypto should convert to 0');
+
+    let id: felt252 = AssetClass::Commodities.into();
+    assert(id == 3, 'Commodities should convert to 3');
 }
 
 #[test]
@@ -14,9 +32,21 @@ fn test_asset_class_id_try_into_asset_class() {
     assert(result.is_some(), 'Should convert 0 to Some');
     assert(result.unwrap() == AssetClass::Crypto, 'Should be Crypto');
 
-    let invalid_id: AssetClassId = 1;
+    let forex_id: AssetClassId = 1;
+    let result: Option<AssetClass> = forex_id.try_into();
+    assert(result.unwrap() == AssetClass::Forex, 'Should be Forex');
+
+    let equities_id: AssetClassId = 2;
+    let result: Option<AssetClass> = equities_id.try_into();
+    assert(result.unwrap() == AssetClass::Equities, 'Should be Equities');
+
+    let commodities_id: AssetClassId = 3;
+    let result: Option<AssetClass> = commodities_id.try_into();
+    assert(result.unwrap() == AssetClass::Commodities, 'Should be Commodities');
+
+    let invalid_id: AssetClassId = 4;
     let result: Option<AssetClass> = invalid_id.try_into();
-    assert(result.is_none(), 'Should not convert 1');
+    assert(result.is_none(), 'Should not convert 4');
 }
 
 #[test]
@@ -26,7 +56,20 @@ fn test_felt_try_into_asset_class() {
     assert(result.is_some(), 'Should convert 0 to Some');
     assert(result.unwrap() == AssetClass::Crypto, 'Should be Crypto');
 
-    let invalid_felt: felt252 = 1.into();
+    let equities_felt: felt252 = 2.into();
+    let result: Option<AssetClass> = equities_felt.try_into();
+    assert(result.unwrap() == AssetClass::Equities, 'Should be Equities');
+
+    let invalid_felt: felt252 = 4.into();
     let result: Option<AssetClass> = invalid_felt.try_into();
-    assert(result.is_none(), 'Should not convert 1');
+    assert(result.is_none(), 'Should not convert 4');
+}
+
+#[test]
+fn test_asset_class_into_string() {
+    let name: ByteArray = AssetClass::Forex.into();
+    assert(name == "Forex", 'Should be Forex');
+
+    let name: ByteArray = AssetClass::Commodities.into();
+    assert(name == "Commodities", 'Should be Commodities');
 }
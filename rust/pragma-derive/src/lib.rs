@@ -0,0 +1,114 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, Path, Type};
+
+/// Derives `pragma_utils::event_data::FromStarknetEventData` for a struct with named fields.
+///
+/// The fields are read from the event data in their declaration order. By default, a field
+/// is stored in a single felt & decoded with `FromFieldBytes`. Field attributes:
+///
+/// - `#[event_data(u256)]`: a Cairo u256, stored as its low & then its high part,
+/// - `#[event_data(byte_array)]`: a Cairo `ByteArray`, decoded as a `String`,
+/// - `#[event_data(span)]`: a Cairo `Span`, i.e its length followed by its elements,
+/// - `#[event_data(nested)]`: a type that implements `FromStarknetEventData` itself,
+/// - `#[event_data(with = path::to::fn)]`: a `fn(&mut FeltReader) -> Result<T, Error>`.
+///
+/// The error returned is `EventDataError`, unless set with `#[event_data(error = Type)]` on the struct.
+#[proc_macro_derive(FromStarknetEventData, attributes(event_data))]
+pub fn derive_from_starknet_event_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+enum FieldKind {
+    Felt,
+    U256,
+    ByteArray,
+    Span,
+    Nested(Type),
+    With(Path),
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut error: Type = syn::parse_quote!(::pragma_utils::event_data::EventDataError);
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("event_data")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("error") {
+                error = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown event_data attribute, expected `error`"))
+            }
+        })?;
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(input.span(), "FromStarknetEventData can only be derived for structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(data.fields.span(), "FromStarknetEventData requires named fields"));
+    };
+
+    let mut reads = Vec::with_capacity(fields.named.len());
+    let mut names = Vec::with_capacity(fields.named.len());
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named field");
+        let field_name = ident.to_string();
+        let read = match field_kind(field)? {
+            FieldKind::Felt => quote!(reader.read(#field_name)?),
+            FieldKind::U256 => quote!(reader.read_u256(#field_name)?),
+            FieldKind::ByteArray => quote!(reader.read_byte_array(#field_name)?),
+            FieldKind::Span => quote!(reader.read_span(#field_name)?),
+            FieldKind::Nested(ty) => {
+                quote!(<#ty as ::pragma_utils::event_data::FromStarknetEventData>::read_event_data(reader)?)
+            }
+            FieldKind::With(path) => quote!(#path(reader)?),
+        };
+        reads.push(quote!(let #ident = #read;));
+        names.push(ident);
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::pragma_utils::event_data::FromStarknetEventData for #name #ty_generics #where_clause {
+            type Error = #error;
+
+            fn read_event_data(
+                reader: &mut ::pragma_utils::event_data::FeltReader<'_>,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                #(#reads)*
+                ::core::result::Result::Ok(Self { #(#names),* })
+            }
+        }
+    })
+}
+
+fn field_kind(field: &syn::Field) -> syn::Result<FieldKind> {
+    let mut kind = None;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("event_data")) {
+        attr.parse_nested_meta(|meta| {
+            if kind.is_some() {
+                return Err(meta.error("only one event_data attribute is allowed per field"));
+            }
+            kind = Some(if meta.path.is_ident("u256") {
+                FieldKind::U256
+            } else if meta.path.is_ident("byte_array") {
+                FieldKind::ByteArray
+            } else if meta.path.is_ident("span") {
+                FieldKind::Span
+            } else if meta.path.is_ident("nested") {
+                FieldKind::Nested(field.ty.clone())
+            } else if meta.path.is_ident("with") {
+                FieldKind::With(meta.value()?.parse()?)
+            } else {
+                return Err(meta
+                    .error("unknown event_data attribute, expected `u256`, `byte_array`, `span`, `nested` or `with`"));
+            });
+            Ok(())
+        })?;
+    }
+    Ok(kind.unwrap_or(FieldKind::Felt))
+}
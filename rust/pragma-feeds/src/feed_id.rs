@@ -0,0 +1,342 @@
+use std::fmt;
+use std::str::FromStr;
+
+use alloy::primitives::U256;
+use anyhow::{bail, Context};
+use starknet::core::types::Felt;
+
+use crate::{
+    pair_id::{split_pair_id, validate_pair_id},
+    AssetClass, FeedType,
+};
+
+/// Size in bytes of an encoded [FeedId].
+pub const FEED_ID_SIZE: usize = 32;
+/// Maximum size in bytes of a pair id.
+pub const PAIR_ID_SIZE: usize = 27;
+
+// Positions of each field in the big-endian encoded [FeedId].
+// The first byte is always empty since a feed id must fit in a felt252.
+const ASSET_CLASS_OFFSET: usize = 1;
+const FEED_TYPE_OFFSET: usize = 3;
+const PAIR_ID_OFFSET: usize = 5;
+
+/// Identifier of a Pragma feed, as defined in the `feed.cairo` contract:
+///
+/// ```text
+/// [ASSET_CLASS: 2 bytes] [FEED_TYPE: 2 bytes] [PAIR_ID: 27 bytes]
+/// ```
+///
+/// The asset class is shifted by 29 bytes & the feed type by 27 bytes, so the encoded
+/// feed id always fits in a felt252.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FeedId {
+    pub asset_class: AssetClass,
+    pub feed_type: FeedType,
+    pub pair_id: String,
+}
+
+impl FeedId {
+    pub fn new(asset_class: AssetClass, feed_type: FeedType, pair_id: &str) -> anyhow::Result<Self> {
+        if pair_id.is_empty() {
+            bail!("Empty pair ID");
+        }
+        if pair_id.len() > PAIR_ID_SIZE {
+            bail!("Pair ID \"{}\" is longer than {} bytes", pair_id, PAIR_ID_SIZE);
+        }
+        validate_pair_id(asset_class, pair_id)?;
+        Ok(Self { asset_class, feed_type, pair_id: pair_id.to_owned() })
+    }
+
+    /// Encodes the feed id as 32 big-endian bytes.
+    pub fn encode(&self) -> [u8; FEED_ID_SIZE] {
+        let mut bytes = [0_u8; FEED_ID_SIZE];
+        bytes[ASSET_CLASS_OFFSET..FEED_TYPE_OFFSET].copy_from_slice(&self.asset_class.id().to_be_bytes());
+        bytes[FEED_TYPE_OFFSET..PAIR_ID_OFFSET].copy_from_slice(&self.feed_type.id().to_be_bytes());
+        // The pair id is right-aligned, like a Cairo short string.
+        let pair_id = self.pair_id.as_bytes();
+        bytes[FEED_ID_SIZE - pair_id.len()..].copy_from_slice(pair_id);
+        bytes
+    }
+
+    /// Decodes a feed id from its 32 big-endian bytes.
+    /// The pair id is not checked against the conventions of the asset class, see [FeedId::validate].
+    pub fn decode(bytes: &[u8; FEED_ID_SIZE]) -> anyhow::Result<Self> {
+        if bytes[0] != 0 {
+            bail!("Feed ID does not fit in a felt252");
+        }
+
+        let asset_class =
+            AssetClass::try_from(u16::from_be_bytes([bytes[ASSET_CLASS_OFFSET], bytes[ASSET_CLASS_OFFSET + 1]]))?;
+        let feed_type = FeedType::try_from(u16::from_be_bytes([bytes[FEED_TYPE_OFFSET], bytes[FEED_TYPE_OFFSET + 1]]))?;
+
+        let raw_pair_id = &bytes[PAIR_ID_OFFSET..];
+        let start = raw_pair_id.iter().position(|b| *b != 0).unwrap_or(raw_pair_id.len());
+        let pair_id =
+            std::str::from_utf8(&raw_pair_id[start..]).context("Invalid UTF-8 sequence for pair_id")?.to_owned();
+        if pair_id.is_empty() {
+            bail!("Empty pair ID");
+        }
+
+        Ok(Self { asset_class, feed_type, pair_id })
+    }
+
+    /// Checks that the pair id follows the conventions of its asset class, see [validate_pair_id].
+    /// Decoding doesn't check it, so feeds registered on-chain are never rejected: it must be
+    /// checked where feed ids are provided by users.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_pair_id(self.asset_class, &self.pair_id)
+    }
+
+    /// Returns the pair id as 32 big-endian bytes, i.e the encoded feed id without
+    /// the asset class & the feed type.
+    pub fn pair_id_bytes(&self) -> [u8; FEED_ID_SIZE] {
+        let mut bytes = self.encode();
+        bytes[..PAIR_ID_OFFSET].fill(0);
+        bytes
+    }
+
+    /// Returns the base & the quote of the pair id.
+    pub fn base_and_quote(&self) -> Option<(&str, &str)> {
+        split_pair_id(&self.pair_id)
+    }
+}
+
+/// Displays the feed id as a lowercase hexadecimal string without leading zeros,
+/// i.e the same representation as [Felt::to_hex_string].
+impl fmt::Display for FeedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encoded = hex::encode(self.encode());
+        write!(f, "0x{}", encoded.trim_start_matches('0'))
+    }
+}
+
+/// Parses a feed id from a hexadecimal string, with or without the "0x" prefix.
+impl FromStr for FeedId {
+    type Err = anyhow::Error;
+
+    fn from_str(feed_id: &str) -> anyhow::Result<Self> {
+        let stripped_id = feed_id.strip_prefix("0x").unwrap_or(feed_id);
+        if stripped_id.len() > FEED_ID_SIZE * 2 {
+            bail!("Feed ID is too long");
+        }
+        // Left pad with zeros so the id can be decoded as 32 big-endian bytes.
+        let padded_id = format!("{:0>width$}", stripped_id, width = FEED_ID_SIZE * 2);
+
+        let mut bytes = [0_u8; FEED_ID_SIZE];
+        hex::decode_to_slice(padded_id, &mut bytes).context("Invalid hexadecimal feed ID")?;
+        Self::decode(&bytes)
+    }
+}
+
+impl From<&FeedId> for [u8; FEED_ID_SIZE] {
+    fn from(feed_id: &FeedId) -> Self {
+        feed_id.encode()
+    }
+}
+
+impl TryFrom<[u8; FEED_ID_SIZE]> for FeedId {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: [u8; FEED_ID_SIZE]) -> anyhow::Result<Self> {
+        Self::decode(&bytes)
+    }
+}
+
+impl From<&FeedId> for U256 {
+    fn from(feed_id: &FeedId) -> Self {
+        U256::from_be_bytes(feed_id.encode())
+    }
+}
+
+impl TryFrom<U256> for FeedId {
+    type Error = anyhow::Error;
+
+    fn try_from(value: U256) -> anyhow::Result<Self> {
+        Self::decode(&value.to_be_bytes::<FEED_ID_SIZE>())
+    }
+}
+
+impl From<&FeedId> for Felt {
+    fn from(feed_id: &FeedId) -> Self {
+        Felt::from_bytes_be(&feed_id.encode())
+    }
+}
+
+impl TryFrom<Felt> for FeedId {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Felt) -> anyhow::Result<Self> {
+        Self::decode(&value.to_bytes_be())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn btc_usd() -> FeedId {
+        FeedId::new(AssetClass::Crypto, FeedType::UniqueSpotMedian, "BTC/USD").unwrap()
+    }
+
+    #[test]
+    fn test_encode_matches_cairo_layout() {
+        let feed_id = FeedId::new(AssetClass::Crypto, FeedType::TwapSpotMedianOneDay, "ETH/USD").unwrap();
+        let bytes = feed_id.encode();
+
+        assert_eq!(bytes[0], 0);
+        // asset class: shifted by 29 bytes
+        assert_eq!(&bytes[1..3], &[0x00, 0x00]);
+        // feed type: shifted by 27 bytes, main type then variant
+        assert_eq!(&bytes[3..5], &[0x01, 0x00]);
+        // pair id: 27 bytes, right-aligned
+        assert_eq!(&bytes[5..25], &[0_u8; 20]);
+        assert_eq!(&bytes[25..], b"ETH/USD");
+    }
+
+    #[test]
+    fn test_encode_decode_all_feed_types() {
+        let feed_types = [
+            (FeedType::UniqueSpotMedian, 0x0000),
+            (FeedType::UniquePerpMedian, 0x0001),
+            (FeedType::UniqueSpotMean, 0x0002),
+            (FeedType::TwapSpotMedianOneDay, 0x0100),
+            (FeedType::RealizedVolatilityOneWeek, 0x0200),
+        ];
+        for (feed_type, expected_id) in feed_types {
+            let feed_id = FeedId::new(AssetClass::Crypto, feed_type, "BTC/USD").unwrap();
+            let bytes = feed_id.encode();
+
+            assert_eq!(u16::from_be_bytes([bytes[3], bytes[4]]), expected_id);
+            assert_eq!(FeedId::decode(&bytes).unwrap(), feed_id);
+            assert_eq!(feed_id.to_string().parse::<FeedId>().unwrap(), feed_id);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(btc_usd().to_string(), "0x4254432f555344");
+
+        let feed_id = FeedId::new(AssetClass::Crypto, FeedType::UniquePerpMedian, "BTC/USD").unwrap();
+        assert_eq!(feed_id.to_string(), "0x100000000000000000000000000000000000000004254432f555344");
+
+        let feed_id = FeedId::new(AssetClass::Crypto, FeedType::RealizedVolatilityOneWeek, "BTC/USD").unwrap();
+        assert_eq!(feed_id.to_string(), "0x20000000000000000000000000000000000000000004254432f555344");
+    }
+
+    #[test]
+    fn test_display_matches_felt_hex_string() {
+        for feed_type in [FeedType::UniqueSpotMedian, FeedType::UniqueSpotMean, FeedType::TwapSpotMedianOneDay] {
+            let feed_id = FeedId::new(AssetClass::Crypto, feed_type, "SOL/USDC").unwrap();
+            assert_eq!(feed_id.to_string(), Felt::from(&feed_id).to_hex_string());
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("0x4254432f555344".parse::<FeedId>().unwrap(), btc_usd());
+        assert_eq!("4254432f555344".parse::<FeedId>().unwrap(), btc_usd());
+        assert_eq!("0x000000000000004254432F555344".parse::<FeedId>().unwrap(), btc_usd());
+
+        assert!("0x".parse::<FeedId>().is_err());
+        assert!("0xzz".parse::<FeedId>().is_err());
+        assert!(format!("0x1{}", "0".repeat(63)).parse::<FeedId>().is_err());
+        assert!(format!("0x{}", "0".repeat(65)).parse::<FeedId>().is_err());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut bytes = btc_usd().encode();
+        bytes[0] = 1;
+        assert!(FeedId::decode(&bytes).is_err());
+
+        let mut bytes = btc_usd().encode();
+        bytes[2] = 0xff;
+        assert!(FeedId::decode(&bytes).is_err(), "unknown asset class");
+
+        let mut bytes = btc_usd().encode();
+        bytes[4] = 0x03;
+        assert!(FeedId::decode(&bytes).is_err(), "unknown feed type variant");
+
+        let mut bytes = btc_usd().encode();
+        bytes[3] = 0x03;
+        assert!(FeedId::decode(&bytes).is_err(), "unknown feed type");
+
+        let mut bytes = [0_u8; FEED_ID_SIZE];
+        assert!(FeedId::decode(&bytes).is_err(), "empty pair id");
+
+        bytes[31] = 0xff;
+        assert!(FeedId::decode(&bytes).is_err(), "invalid utf-8 pair id");
+    }
+
+    #[test]
+    fn test_new_errors() {
+        assert!(FeedId::new(AssetClass::Crypto, FeedType::UniqueSpotMedian, "").is_err());
+        assert!(FeedId::new(AssetClass::Crypto, FeedType::UniqueSpotMedian, "BTCUSD").is_err());
+        assert!(FeedId::new(AssetClass::Forex, FeedType::UniqueSpotMedian, "BTC/USDC").is_err());
+
+        let max_size_pair_id = format!("{}/USD", "A".repeat(PAIR_ID_SIZE - 4));
+        assert!(FeedId::new(AssetClass::Crypto, FeedType::UniqueSpotMedian, &max_size_pair_id).is_ok());
+        let too_long_pair_id = format!("{}/USD", "A".repeat(PAIR_ID_SIZE - 3));
+        assert!(FeedId::new(AssetClass::Crypto, FeedType::UniqueSpotMedian, &too_long_pair_id).is_err());
+    }
+
+    #[test]
+    fn test_max_size_pair_id_roundtrip() {
+        let pair_id = "ABCDEFGHIJKLMNOPQRSTUVWXY/Z";
+        let feed_id = FeedId::new(AssetClass::Crypto, FeedType::RealizedVolatilityOneWeek, pair_id).unwrap();
+
+        let felt = Felt::from(&feed_id);
+        assert_eq!(FeedId::try_from(felt).unwrap(), feed_id);
+        assert_eq!(&feed_id.encode()[PAIR_ID_OFFSET..], pair_id.as_bytes());
+    }
+
+    #[test]
+    fn test_encode_decode_all_asset_classes() {
+        let feeds = [
+            (AssetClass::Crypto, "BTC/USD"),
+            (AssetClass::Forex, "EUR/USD"),
+            (AssetClass::Equities, "US0378331005/USD"),
+            (AssetClass::Commodities, "XAU/USD"),
+        ];
+        for (asset_class, pair_id) in feeds {
+            let feed_id = FeedId::new(asset_class, FeedType::UniqueSpotMedian, pair_id).unwrap();
+            let bytes = feed_id.encode();
+
+            assert_eq!(u16::from_be_bytes([bytes[1], bytes[2]]), asset_class.id());
+            assert_eq!(FeedId::decode(&bytes).unwrap(), feed_id);
+            assert_eq!(feed_id.to_string().parse::<FeedId>().unwrap(), feed_id);
+        }
+    }
+
+    #[test]
+    fn test_decode_does_not_validate_pair_id_convention() {
+        // A crypto pair id encoded with the equities asset class.
+        let mut bytes = btc_usd().encode();
+        bytes[2] = AssetClass::Equities.id() as u8;
+        let feed_id = FeedId::decode(&bytes).unwrap();
+
+        assert_eq!(feed_id.asset_class, AssetClass::Equities);
+        assert_eq!(feed_id.pair_id, "BTC/USD");
+        assert!(feed_id.validate().is_err());
+        assert!(btc_usd().validate().is_ok());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let feed_id = btc_usd();
+
+        let as_u256: U256 = (&feed_id).into();
+        assert_eq!(as_u256, U256::from(0x4254432f555344_u64));
+        assert_eq!(FeedId::try_from(as_u256).unwrap(), feed_id);
+
+        let as_felt = Felt::from(&feed_id);
+        assert_eq!(as_felt, Felt::from(0x4254432f555344_u64));
+        assert_eq!(FeedId::try_from(as_felt).unwrap(), feed_id);
+
+        let as_bytes: [u8; FEED_ID_SIZE] = (&feed_id).into();
+        assert_eq!(FeedId::try_from(as_bytes).unwrap(), feed_id);
+
+        assert_eq!(feed_id.pair_id_bytes(), U256::from(0x4254432f555344_u64).to_be_bytes::<FEED_ID_SIZE>());
+    }
+}
@@ -2,20 +2,22 @@
 //!
 //! # Feed Encoding
 //!
-//! Feeds are encoded as hexadecimal strings with the following structure:
+//! Feed ids are felt252 encoded with the following structure, see [FeedId]:
 //!
 //! ```text
 //! [ASSET_CLASS] [FEED_TYPE] [PAIR_ID]
 //! ```
 //!
-//! - `ASSET_CLASS`: 1 byte representing the asset class (e.g., 1 for Crypto)
-//! - `FEED_TYPE`: 2 bytes representing the type of feed (e.g., 534d for Spot Median)
-//! - `PAIR_ID`: 32 bytes representing the trading pair (e.g., "BTC/USD")
-//!   If the provided pair ID is shorter than 32 bytes, it will be right-padded with zeros.
+//! - `ASSET_CLASS`: 2 bytes representing the asset class (e.g., 0 for Crypto)
+//! - `FEED_TYPE`: 2 bytes representing the type of feed: the first byte is the main
+//!   type & the second one its variant (e.g., 0x0000 for Unique Spot Median)
+//! - `PAIR_ID`: 27 bytes representing the trading pair as a short string (e.g., "BTC/USD")
+//!   If the provided pair ID is shorter than 27 bytes, it is left-padded with zeros.
 //!
-//! Total length: Always 35 bytes (70 hexadecimal characters)
+//! Total length: 31 bytes, i.e. 32 bytes once encoded as big-endian bytes with the
+//! first byte always empty.
 //!
-//! Example feed ID: `0x01534d4254432f555344` (will be padded to 35 bytes internally)
+//! Example feed ID: `0x4254432f555344` (Crypto, Unique Spot Median, "BTC/USD")
 //!
 //! # Parsing
 //!
@@ -24,23 +26,35 @@
 //!
 //! # Asset Classes
 //!
-//! Currently, only the Crypto asset class is supported (represented by the value 1).
+//! Supported asset classes are:
+//! - Crypto (0)
+//! - Forex (1)
+//! - Equities (2)
+//! - Commodities (3)
+//!
+//! Each asset class has its own pair ID conventions, e.g. the base of an equity is its ISIN,
+//! see the [pair_id] module.
 //!
 //! # Feed Types
 //!
 //! Supported feed types include:
-//! - Spot Median (21325)
-//! - Twap (21591)
-//! - Realized Volatility (21078)
-//! - Options (20304)
-//! - Perp (20560)
+//! - Unique Spot Median (0x0000)
+//! - Unique Perp Median (0x0001)
+//! - Unique Spot Mean (0x0002)
+//! - Twap Spot Median One Day (0x0100)
+//! - Realized Volatility One Week (0x0200)
+pub mod feed_id;
+pub mod pair_id;
+
 use std::convert::TryFrom;
 use std::str::FromStr;
 
-use anyhow::{anyhow, bail, Context};
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
+pub use feed_id::FeedId;
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Feed {
     pub feed_id: String,
@@ -49,9 +63,20 @@ pub struct Feed {
     pub pair_id: String,
 }
 
-#[derive(Debug, PartialEq, Display, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize)]
+#[strum(ascii_case_insensitive)]
 pub enum AssetClass {
     Crypto = 0,
+    Forex = 1,
+    Equities = 2,
+    Commodities = 3,
+}
+
+impl AssetClass {
+    /// Returns the 2 bytes identifier of the asset class.
+    pub fn id(&self) -> u16 {
+        *self as u16
+    }
 }
 
 impl TryFrom<u16> for AssetClass {
@@ -60,19 +85,36 @@ impl TryFrom<u16> for AssetClass {
     fn try_from(value: u16) -> anyhow::Result<Self> {
         match value {
             0 => Ok(AssetClass::Crypto),
+            1 => Ok(AssetClass::Forex),
+            2 => Ok(AssetClass::Equities),
+            3 => Ok(AssetClass::Commodities),
             _ => Err(anyhow!("Unknown asset class: {}", value)),
         }
     }
 }
 
-// TODO:
-// This configuration is wrong at the moment. We should include:
-// FeedType(FeedVariant).
-// For now it works because we only have 0 anyway.
-#[derive(Debug, PartialEq, Display, EnumString, Serialize, Deserialize)]
+/// Type of a feed, with its variant.
+/// The discriminant is the 2 bytes identifier of the feed type: the first byte is the
+/// main type (Unique, Twap, Realized Volatility) & the second one is the variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize)]
 pub enum FeedType {
     #[strum(serialize = "Unique Spot Median")]
-    UniqueSpotMedian = 0,
+    UniqueSpotMedian = 0x0000,
+    #[strum(serialize = "Unique Perp Median")]
+    UniquePerpMedian = 0x0001,
+    #[strum(serialize = "Unique Spot Mean")]
+    UniqueSpotMean = 0x0002,
+    #[strum(serialize = "Twap Spot Median One Day")]
+    TwapSpotMedianOneDay = 0x0100,
+    #[strum(serialize = "Realized Volatility One Week")]
+    RealizedVolatilityOneWeek = 0x0200,
+}
+
+impl FeedType {
+    /// Returns the 2 bytes identifier of the feed type.
+    pub fn id(&self) -> u16 {
+        *self as u16
+    }
 }
 
 impl TryFrom<u16> for FeedType {
@@ -80,45 +122,39 @@ impl TryFrom<u16> for FeedType {
 
     fn try_from(value: u16) -> anyhow::Result<Self> {
         match value {
-            0 => Ok(FeedType::UniqueSpotMedian),
-            _ => Err(anyhow!("Unknown feed type: {}", value)),
+            0x0000 => Ok(FeedType::UniqueSpotMedian),
+            0x0001 => Ok(FeedType::UniquePerpMedian),
+            0x0002 => Ok(FeedType::UniqueSpotMean),
+            0x0100 => Ok(FeedType::TwapSpotMedianOneDay),
+            0x0200 => Ok(FeedType::RealizedVolatilityOneWeek),
+            _ => Err(anyhow!("Unknown feed type: {:#06x}", value)),
         }
     }
 }
 
-impl FromStr for Feed {
-    type Err = anyhow::Error;
-
-    fn from_str(feed_id: &str) -> anyhow::Result<Self> {
-        let stripped_id = feed_id.strip_prefix("0x").unwrap_or(feed_id);
-        let mut bytes = hex::decode(stripped_id)?;
-
-        if bytes.len() < 3 {
-            bail!("Feed ID is too short");
-        }
-
-        if bytes.len() > 35 {
-            bail!("Feed ID is too long");
+impl From<FeedId> for Feed {
+    fn from(feed_id: FeedId) -> Self {
+        Feed {
+            feed_id: feed_id.to_string(),
+            asset_class: feed_id.asset_class,
+            feed_type: feed_id.feed_type,
+            pair_id: feed_id.pair_id,
         }
+    }
+}
 
-        // Pad the bytes to 35 if necessary, but at the end
-        let original_len = bytes.len();
-        bytes.resize(35, 0);
-        bytes.rotate_right(35 - original_len);
-
-        let asset_class = AssetClass::try_from(u16::from_be_bytes([bytes[0], bytes[1]]))?;
-        let feed_type = FeedType::try_from(u16::from_be_bytes([bytes[2], bytes[3]]))?;
-
-        let pair_id = String::from_utf8(bytes[3..].to_vec())
-            .context("Invalid UTF-8 sequence for pair_id")?
-            .trim_start_matches('\0')
-            .to_string();
+impl Feed {
+    /// Returns the canonical [FeedId] of the feed.
+    pub fn id(&self) -> FeedId {
+        FeedId { asset_class: self.asset_class, feed_type: self.feed_type, pair_id: self.pair_id.clone() }
+    }
+}
 
-        if pair_id.is_empty() {
-            bail!("Empty pair ID");
-        }
+impl FromStr for Feed {
+    type Err = anyhow::Error;
 
-        Ok(Feed { feed_id: feed_id.to_owned(), asset_class, feed_type, pair_id })
+    fn from_str(feed_id: &str) -> anyhow::Result<Self> {
+        Ok(Feed::from(feed_id.parse::<FeedId>()?))
     }
 }
 
@@ -136,8 +172,60 @@ mod tests {
         assert_eq!(result.pair_id, "BTC/USD");
     }
 
+    #[test]
+    fn test_feed_from_str_with_feed_type() {
+        let feed_id = "0x10000000000000000000000000000000000000000004554482f555344";
+        let result: Feed = feed_id.parse().unwrap();
+
+        assert_eq!(result.asset_class, AssetClass::Crypto);
+        assert_eq!(result.feed_type, FeedType::TwapSpotMedianOneDay);
+        assert_eq!(result.pair_id, "ETH/USD");
+        assert_eq!(result.feed_id, feed_id);
+        assert_eq!(result.id().to_string(), feed_id);
+    }
+
+    #[test]
+    fn test_feed_from_str_errors() {
+        assert!("0x".parse::<Feed>().is_err());
+        assert!("0x0300000000000000000000000000000000000000000004254432f555344".parse::<Feed>().is_err());
+        assert!("not an hex string".parse::<Feed>().is_err());
+    }
+
+    #[test]
+    fn test_feed_type_display() {
+        assert_eq!(FeedType::UniqueSpotMedian.to_string(), "Unique Spot Median");
+        assert_eq!(FeedType::RealizedVolatilityOneWeek.to_string(), "Realized Volatility One Week");
+    }
+
+    #[test]
+    fn test_feed_from_str_with_asset_class() {
+        // Equities, Unique Spot Median, "US0378331005/USD"
+        let feed_id = "0x2000000000000000000000000005553303337383333313030352f555344";
+        let result: Feed = feed_id.parse().unwrap();
+
+        assert_eq!(result.asset_class, AssetClass::Equities);
+        assert_eq!(result.feed_type, FeedType::UniqueSpotMedian);
+        assert_eq!(result.pair_id, "US0378331005/USD");
+    }
+
     #[test]
     fn test_asset_class_display() {
         assert_eq!(AssetClass::Crypto.to_string(), "Crypto");
+        assert_eq!(AssetClass::Commodities.to_string(), "Commodities");
+    }
+
+    #[test]
+    fn test_asset_class_from_str() {
+        assert_eq!(AssetClass::from_str("Forex").unwrap(), AssetClass::Forex);
+        assert_eq!(AssetClass::from_str("equities").unwrap(), AssetClass::Equities);
+        assert!(AssetClass::from_str("Bonds").is_err());
+    }
+
+    #[test]
+    fn test_asset_class_try_from() {
+        for asset_class in [AssetClass::Crypto, AssetClass::Forex, AssetClass::Equities, AssetClass::Commodities] {
+            assert_eq!(AssetClass::try_from(asset_class.id()).unwrap(), asset_class);
+        }
+        assert!(AssetClass::try_from(4).is_err());
     }
 }
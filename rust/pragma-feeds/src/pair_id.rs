@@ -0,0 +1,148 @@
+//! Pair id conventions of each asset class.
+//!
+//! Every pair id is formatted as `BASE/QUOTE`, where:
+//! - Crypto: both are tickers, e.g "BTC/USD" or "SOL/USDC",
+//! - Forex: both are ISO 4217 currency codes, e.g "EUR/USD",
+//! - Equities: the base is the ISIN of the equity & the quote a currency code, e.g "US0378331005/USD",
+//! - Commodities: the base is the commodity symbol & the quote a currency code, e.g "XAU/USD".
+use anyhow::{anyhow, bail, ensure};
+
+use crate::AssetClass;
+
+/// Separator between the base & the quote of a pair id.
+pub const PAIR_ID_SEPARATOR: char = '/';
+/// Length of an ISIN, e.g "US0378331005".
+const ISIN_LEN: usize = 12;
+/// Length of an ISO 4217 currency code, e.g "USD".
+const CURRENCY_CODE_LEN: usize = 3;
+
+/// Splits a pair id into its base & quote.
+pub fn split_pair_id(pair_id: &str) -> Option<(&str, &str)> {
+    pair_id.split_once(PAIR_ID_SEPARATOR)
+}
+
+/// Checks that the pair id follows the conventions of the asset class.
+pub fn validate_pair_id(asset_class: AssetClass, pair_id: &str) -> anyhow::Result<()> {
+    let (base, quote) =
+        split_pair_id(pair_id).ok_or_else(|| anyhow!("Pair ID \"{}\" should be formatted as BASE/QUOTE", pair_id))?;
+
+    match asset_class {
+        AssetClass::Crypto => {
+            ensure!(is_ticker(base) && is_ticker(quote), "Invalid crypto pair ID \"{}\"", pair_id);
+        }
+        AssetClass::Forex => {
+            ensure!(
+                is_currency_code(base) && is_currency_code(quote),
+                "Invalid forex pair ID \"{}\": expected ISO 4217 currency codes",
+                pair_id
+            );
+        }
+        AssetClass::Equities => {
+            validate_isin(base)?;
+            ensure!(
+                is_currency_code(quote),
+                "Invalid equity pair ID \"{}\": expected a currency code as quote",
+                pair_id
+            );
+        }
+        AssetClass::Commodities => {
+            ensure!(
+                is_ticker(base) && base.bytes().all(|b| !b.is_ascii_lowercase()) && is_currency_code(quote),
+                "Invalid commodity pair ID \"{}\"",
+                pair_id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the ISIN (ISO 6166) is well formed & that its check digit is valid.
+pub fn validate_isin(isin: &str) -> anyhow::Result<()> {
+    let bytes = isin.as_bytes();
+    if bytes.len() != ISIN_LEN {
+        bail!("ISIN \"{}\" should be {} characters long", isin, ISIN_LEN);
+    }
+    if !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..ISIN_LEN - 1].iter().all(|b| b.is_ascii_digit() || b.is_ascii_uppercase())
+        || !bytes[ISIN_LEN - 1].is_ascii_digit()
+    {
+        bail!("ISIN \"{}\" is malformed", isin);
+    }
+
+    // Letters are converted to numbers (A = 10, ..., Z = 35) & the Luhn algorithm
+    // is applied on the resulting digits, check digit included.
+    let digits: Vec<u32> = bytes
+        .iter()
+        .flat_map(|b| {
+            let value = (*b as char).to_digit(36).expect("checked above");
+            if value < 10 {
+                vec![value]
+            } else {
+                vec![value / 10, value % 10]
+            }
+        })
+        .collect();
+    let checksum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, digit)| {
+            if i % 2 == 1 {
+                let doubled = digit * 2;
+                doubled / 10 + doubled % 10
+            } else {
+                *digit
+            }
+        })
+        .sum();
+
+    let remainder = checksum % 10;
+    ensure!(remainder == 0, "ISIN \"{}\" has an invalid check digit", isin);
+    Ok(())
+}
+
+fn is_ticker(symbol: &str) -> bool {
+    !symbol.is_empty() && symbol.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.' || b == b'_')
+}
+
+fn is_currency_code(code: &str) -> bool {
+    code.len() == CURRENCY_CODE_LEN && code.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_isin() {
+        // Apple, Siemens & BAE Systems
+        assert!(validate_isin("US0378331005").is_ok());
+        assert!(validate_isin("DE0007236101").is_ok());
+        assert!(validate_isin("GB0002634946").is_ok());
+
+        assert!(validate_isin("US0378331004").is_err(), "invalid check digit");
+        assert!(validate_isin("US037833100").is_err(), "too short");
+        assert!(validate_isin("us0378331005").is_err(), "lowercase country");
+        assert!(validate_isin("US037833100A").is_err(), "letter as check digit");
+    }
+
+    #[test]
+    fn test_validate_pair_id() {
+        assert!(validate_pair_id(AssetClass::Crypto, "BTC/USD").is_ok());
+        assert!(validate_pair_id(AssetClass::Crypto, "1000PEPE/USDT").is_ok());
+        assert!(validate_pair_id(AssetClass::Crypto, "BTCUSD").is_err());
+        assert!(validate_pair_id(AssetClass::Crypto, "BTC/").is_err());
+
+        assert!(validate_pair_id(AssetClass::Forex, "EUR/USD").is_ok());
+        assert!(validate_pair_id(AssetClass::Forex, "EURO/USD").is_err());
+        assert!(validate_pair_id(AssetClass::Forex, "eur/usd").is_err());
+
+        assert!(validate_pair_id(AssetClass::Equities, "US0378331005/USD").is_ok());
+        assert!(validate_pair_id(AssetClass::Equities, "AAPL/USD").is_err());
+        assert!(validate_pair_id(AssetClass::Equities, "US0378331005/USDC").is_err());
+
+        assert!(validate_pair_id(AssetClass::Commodities, "XAU/USD").is_ok());
+        assert!(validate_pair_id(AssetClass::Commodities, "BRENT/EUR").is_ok());
+        assert!(validate_pair_id(AssetClass::Commodities, "xau/USD").is_err());
+    }
+}
@@ -12,16 +12,31 @@ pub fn apibara_field_as_felt(value: &FieldElement) -> Felt {
 }
 
 pub trait FromFieldBytes: Sized {
+    /// Number of low bytes of the field kept by [FromFieldBytes::from_field_bytes].
+    const SIZE: usize;
+
     fn from_field_bytes(bytes: [u8; 32]) -> Self;
 }
 
+impl FromFieldBytes for Felt {
+    const SIZE: usize = 32;
+
+    fn from_field_bytes(bytes: [u8; 32]) -> Self {
+        Felt::from_bytes_be(&bytes)
+    }
+}
+
 impl FromFieldBytes for u8 {
+    const SIZE: usize = 1;
+
     fn from_field_bytes(bytes: [u8; 32]) -> Self {
         bytes[31]
     }
 }
 
 impl FromFieldBytes for u16 {
+    const SIZE: usize = 2;
+
     fn from_field_bytes(bytes: [u8; 32]) -> Self {
         let last_two_bytes: [u8; 2] = bytes[30..32].try_into().expect("Slice with incorrect length");
         u16::from_be_bytes(last_two_bytes)
@@ -29,6 +44,8 @@ impl FromFieldBytes for u16 {
 }
 
 impl FromFieldBytes for u32 {
+    const SIZE: usize = 4;
+
     fn from_field_bytes(bytes: [u8; 32]) -> Self {
         let last_four_bytes: [u8; 4] = bytes[28..32].try_into().expect("Slice with incorrect length");
         u32::from_be_bytes(last_four_bytes)
@@ -36,6 +53,8 @@ impl FromFieldBytes for u32 {
 }
 
 impl FromFieldBytes for u64 {
+    const SIZE: usize = 8;
+
     fn from_field_bytes(bytes: [u8; 32]) -> Self {
         let last_eight_bytes: [u8; 8] = bytes[24..32].try_into().expect("Slice with incorrect length");
         u64::from_be_bytes(last_eight_bytes)
@@ -43,6 +62,8 @@ impl FromFieldBytes for u64 {
 }
 
 impl FromFieldBytes for u128 {
+    const SIZE: usize = 16;
+
     fn from_field_bytes(bytes: [u8; 32]) -> Self {
         let last_sixteen_bytes: [u8; 16] = bytes[16..32].try_into().expect("Slice with incorrect length");
         u128::from_be_bytes(last_sixteen_bytes)
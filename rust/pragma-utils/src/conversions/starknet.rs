@@ -1,104 +1,159 @@
-use anyhow::{bail, Result};
+use std::string::FromUtf8Error;
+
+use anyhow::{bail, Context, Result};
 use starknet::core::types::Felt;
 
+/// Maximum number of bytes stored in a Cairo short string, i.e in a single felt.
+pub const BYTES31_SIZE: usize = 31;
+
 pub trait FeltVecToString {
     fn to_string(&self) -> String;
 }
 
-impl FeltVecToString for Vec<Felt> {
+/// Decodes the felts as consecutive Cairo short strings, i.e felts holding up to 31 bytes
+/// without leading zeros. Invalid UTF-8 sequences are replaced by `U+FFFD`.
+impl FeltVecToString for [Felt] {
     fn to_string(&self) -> String {
-        self.iter()
+        let bytes: Vec<u8> = self
+            .iter()
             .flat_map(|felt| {
-                let hex = felt.to_hex_string();
-                (0..hex.len()).step_by(2).filter_map(move |i| {
-                    if i + 2 <= hex.len() {
-                        u8::from_str_radix(&hex[i..i + 2], 16).ok()
-                    } else {
-                        None
-                    }
-                })
+                let bytes = felt.to_bytes_be();
+                let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+                bytes[start..].to_vec()
             })
-            .map(|byte| byte as char)
-            .collect()
+            .collect();
+        String::from_utf8_lossy(&bytes).into_owned()
     }
 }
 
-pub fn felt_vec_to_vec_string(felts: &[Felt]) -> Result<Vec<String>> {
-    let mut result = Vec::new();
-    let mut i = 0;
+/// Encodes a string as consecutive Cairo short strings, the inverse of [FeltVecToString].
+pub fn string_to_felt_vec(value: &str) -> Vec<Felt> {
+    value.as_bytes().chunks(BYTES31_SIZE).map(Felt::from_bytes_be_slice).collect()
+}
 
-    if felts.is_empty() {
-        bail!("Empty input");
+/// Converts a felt into a usize, failing instead of truncating it when it doesn't fit.
+pub fn felt_to_usize(felt: &Felt) -> Result<usize> {
+    let bytes = felt.to_bytes_be();
+    let (high, low) = bytes.split_at(24);
+    if high.iter().any(|byte| *byte != 0) {
+        bail!("{} does not fit in a usize", felt);
     }
+    let value = u64::from_be_bytes(low.try_into().expect("Slice with incorrect length"));
+    usize::try_from(value).with_context(|| format!("{} does not fit in a usize", felt))
+}
 
-    let sub_array_count = felts[i].to_bytes_be()[31] as usize;
-    i += 1;
+/// A Cairo `ByteArray`, serialized as the span of its full words of 31 bytes, its pending word
+/// & the number of bytes in the pending word.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ByteArray(pub Vec<u8>);
+
+impl ByteArray {
+    /// Serializes the byte array like Cairo.
+    pub fn to_felts(&self) -> Vec<Felt> {
+        let words = self.0.chunks_exact(BYTES31_SIZE);
+        let pending_word = words.remainder();
+
+        let mut felts = Vec::with_capacity(words.len() + 3);
+        felts.push(Felt::from(words.len() as u64));
+        felts.extend(words.map(Felt::from_bytes_be_slice));
+        felts.push(Felt::from_bytes_be_slice(pending_word));
+        felts.push(Felt::from(pending_word.len() as u64));
+        felts
+    }
 
-    for _ in 0..sub_array_count {
-        if i >= felts.len() {
-            bail!("Unexpected end of input");
-        }
+    /// Deserializes a byte array from the first felts, returning it with the number of felts read.
+    pub fn from_felts(felts: &[Felt]) -> Result<(Self, usize)> {
+        let nb_words = felt_to_usize(felts.first().context("Missing number of words")?)?;
+        let words = felts.get(1..).and_then(|felts| felts.get(..nb_words)).context("Unexpected end of input")?;
 
-        let count = felts[i].to_bytes_be()[31] as usize;
-        i += 1;
-
-        if i + count > felts.len() {
-            bail!("Invalid input length");
+        let mut bytes = Vec::with_capacity((nb_words + 1) * BYTES31_SIZE);
+        for word in words {
+            let word = word.to_bytes_be();
+            if word[0] != 0 {
+                bail!("Word larger than 31 bytes");
+            }
+            bytes.extend_from_slice(&word[1..]);
         }
 
-        let slice = &felts[i..i + count];
-        let storage_location = slice.to_vec().to_string(); // Using the FeltVecToString impl
-
-        result.push(storage_location);
+        let position = 1 + nb_words;
+        let pending_word = felts.get(position).context("Missing pending word")?.to_bytes_be();
+        let pending_word_len = felt_to_usize(felts.get(position + 1).context("Missing pending word length")?)?;
+        if pending_word_len >= BYTES31_SIZE {
+            bail!("Pending word of {} bytes", pending_word_len);
+        }
+        let start = pending_word.len() - pending_word_len;
+        if pending_word[..start].iter().any(|byte| *byte != 0) {
+            bail!("Pending word larger than its length");
+        }
+        bytes.extend_from_slice(&pending_word[start..]);
 
-        i += count;
+        Ok((Self(bytes), position + 2))
     }
+}
 
-    Ok(result)
+impl From<&str> for ByteArray {
+    fn from(value: &str) -> Self {
+        Self(value.as_bytes().to_vec())
+    }
 }
 
-pub fn process_nested_felt_array(felts: &[Felt]) -> Result<Vec<Vec<String>>> {
-    let mut result = Vec::new();
-    let mut i = 0;
+impl TryFrom<ByteArray> for String {
+    type Error = FromUtf8Error;
 
-    if felts.is_empty() {
-        bail!("Empty input");
+    fn try_from(value: ByteArray) -> Result<Self, Self::Error> {
+        String::from_utf8(value.0)
     }
+}
 
-    let outer_array_count = felts[i].to_bytes_be()[31] as usize;
-    i += 1;
+/// Reads the length stored at the given position.
+fn read_length(felts: &[Felt], position: usize) -> Result<usize> {
+    felt_to_usize(felts.get(position).context("Unexpected end of input")?)
+}
 
-    for _ in 0..outer_array_count {
-        if i >= felts.len() {
-            bail!("Unexpected end of input");
-        }
+/// Reads an array of strings, each string being a span of short strings.
+/// Returns the strings with the number of felts read.
+fn read_string_array(felts: &[Felt]) -> Result<(Vec<String>, usize)> {
+    let count = read_length(felts, 0)?;
+    let mut i = 1;
 
-        let inner_array_count = felts[i].to_bytes_be()[31] as usize;
+    let mut result = Vec::with_capacity(count.min(felts.len()));
+    for _ in 0..count {
+        let length = read_length(felts, i)?;
         i += 1;
 
-        let mut inner_array = Vec::new();
+        if length > felts.len() - i {
+            bail!("Invalid input length");
+        }
 
-        for _ in 0..inner_array_count {
-            if i >= felts.len() {
-                bail!("Unexpected end of input");
-            }
+        result.push(felts[i..i + length].to_string());
+        i += length;
+    }
 
-            let count = felts[i].to_bytes_be()[31] as usize;
-            i += 1;
+    Ok((result, i))
+}
 
-            if i + count > felts.len() {
-                bail!("Invalid input length");
-            }
+pub fn felt_vec_to_vec_string(felts: &[Felt]) -> Result<Vec<String>> {
+    if felts.is_empty() {
+        bail!("Empty input");
+    }
 
-            let slice = &felts[i..i + count];
-            let storage_location = slice.to_vec().to_string(); // Using the FeltVecToString impl
+    let (result, _) = read_string_array(felts)?;
+    Ok(result)
+}
 
-            inner_array.push(storage_location);
+pub fn process_nested_felt_array(felts: &[Felt]) -> Result<Vec<Vec<String>>> {
+    if felts.is_empty() {
+        bail!("Empty input");
+    }
 
-            i += count;
-        }
+    let outer_array_count = read_length(felts, 0)?;
+    let mut i = 1;
 
+    let mut result = Vec::with_capacity(outer_array_count.min(felts.len()));
+    for _ in 0..outer_array_count {
+        let (inner_array, read) = read_string_array(&felts[i..])?;
         result.push(inner_array);
+        i += read;
     }
 
     Ok(result)
@@ -107,7 +162,7 @@ pub fn process_nested_felt_array(felts: &[Felt]) -> Result<Vec<Vec<String>>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use starknet::macros::felt_dec;
+    use starknet::macros::{felt, felt_dec};
 
     #[test]
     fn test_felt_vec_to_string() {
@@ -194,4 +249,125 @@ mod tests {
         assert_eq!(result[1][0], "file:///var/folders/kr/z3l_6qyn3znb6gbnddtvgsn40000gn/T/.tmpdY51LU/checkpoint");
         assert_eq!(result[1][1], "file:///var/folders/kr/z3l_6qyn3znb6gbnddtvgsn40000gn/T/.tmpdY51LU/checkpoint");
     }
+
+    const STORAGE_LOCATION: &str = "file:///var/folders/kr/z3l_6qyn3znb6gbnddtvgsn40000gn/T/.tmpdY51LU/checkpoint";
+
+    #[test]
+    fn test_string_to_felt_vec_round_trip() {
+        let felts = string_to_felt_vec(STORAGE_LOCATION);
+        assert_eq!(
+            felts,
+            vec![
+                felt_dec!("180946006308525359965345158532346553211983108462325076142963585023296502126"),
+                felt_dec!("90954189295124463684969781689350429239725285131197301894846683156275291225"),
+                felt_dec!("276191619276790668637754154763775604"),
+            ]
+        );
+        assert_eq!(felts.to_string(), STORAGE_LOCATION);
+
+        // Short strings whose hexadecimal representation has an odd length, & multi-byte characters
+        for value in ["\nbc", "s3://pragma-checkpoints/eu-west-3 ⚡", "données/évaluées/検証者", ""] {
+            assert_eq!(string_to_felt_vec(value).to_string(), value);
+        }
+    }
+
+    #[test]
+    fn test_felt_to_usize() {
+        assert_eq!(felt_to_usize(&Felt::from(257_u16)).unwrap(), 257);
+        assert_eq!(felt_to_usize(&Felt::from(u64::MAX)).unwrap() as u64, u64::MAX);
+        assert!(felt_to_usize(&(Felt::from(u64::MAX) + Felt::ONE)).is_err());
+        assert!(felt_to_usize(&Felt::MAX).is_err());
+    }
+
+    #[test]
+    fn test_lengths_are_not_truncated() {
+        // 257 elements announced while only 1 is sent: the length must not be read as 1
+        let input = vec![Felt::ONE, Felt::from(257_u16), felt_dec!("276191619276790668637754154763775604")];
+        assert!(felt_vec_to_vec_string(&input).is_err());
+
+        let input = vec![Felt::from(257_u16), Felt::ZERO];
+        assert!(felt_vec_to_vec_string(&input).is_err());
+        assert!(process_nested_felt_array(&input).is_err());
+
+        let input = vec![Felt::ONE, Felt::ONE, Felt::MAX];
+        assert!(process_nested_felt_array(&input).is_err());
+    }
+
+    #[test]
+    fn test_byte_array_cairo_fixtures() {
+        // Serializations of strings by Cairo, i.e `Serde::<ByteArray>::serialize`
+        let fixtures: [(&str, Vec<Felt>); 4] = [
+            ("", vec![Felt::ZERO, Felt::ZERO, Felt::ZERO]),
+            ("hello", vec![Felt::ZERO, felt!("0x68656c6c6f"), Felt::from(5_u8)]),
+            (
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZ12345",
+                vec![
+                    Felt::ONE,
+                    felt!("0x4142434445464748494a4b4c4d4e4f505152535455565758595a3132333435"),
+                    Felt::ZERO,
+                    Felt::ZERO,
+                ],
+            ),
+            (
+                "Long string, more than 31 characters.",
+                vec![
+                    Felt::ONE,
+                    felt!("0x4c6f6e6720737472696e672c206d6f7265207468616e203331206368617261"),
+                    felt!("0x63746572732e"),
+                    Felt::from(6_u8),
+                ],
+            ),
+        ];
+
+        for (value, felts) in fixtures {
+            assert_eq!(ByteArray::from(value).to_felts(), felts);
+
+            let (byte_array, read) = ByteArray::from_felts(&felts).unwrap();
+            assert_eq!(read, felts.len());
+            assert_eq!(String::try_from(byte_array).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_byte_array_round_trip() {
+        let value = "données/évaluées/検証者 ⚡ ".repeat(5);
+        let mut felts = ByteArray::from(value.as_str()).to_felts();
+        // Trailing felts are not read
+        felts.push(Felt::from(42_u8));
+
+        let (byte_array, read) = ByteArray::from_felts(&felts).unwrap();
+        assert_eq!(read, felts.len() - 1);
+        assert_eq!(String::try_from(byte_array).unwrap(), value);
+    }
+
+    #[test]
+    fn test_invalid_byte_arrays() {
+        let invalid_byte_arrays = [
+            // Missing words
+            vec![Felt::TWO, Felt::ZERO, Felt::ZERO, Felt::ZERO],
+            // Number of words not fitting in a usize
+            vec![Felt::MAX, Felt::ZERO, Felt::ZERO],
+            // Word of 32 bytes
+            vec![
+                Felt::ONE,
+                felt!("0x0100000000000000000000000000000000000000000000000000000000000000"),
+                Felt::ZERO,
+                Felt::ZERO,
+            ],
+            // Pending word longer than its length
+            vec![Felt::ZERO, felt!("0x68656c6c6f"), Felt::from(4_u8)],
+            // Pending word of 31 bytes
+            vec![Felt::ZERO, Felt::ZERO, Felt::from(31_u8)],
+            // Missing pending word length
+            vec![Felt::ZERO, felt!("0x68656c6c6f")],
+            vec![],
+        ];
+        for felts in invalid_byte_arrays {
+            assert!(ByteArray::from_felts(&felts).is_err(), "{:?} decoded", felts);
+        }
+
+        // Valid byte array, but not a valid UTF-8 string
+        let (byte_array, _) = ByteArray::from_felts(&[Felt::ZERO, Felt::from(0xff_u8), Felt::ONE]).unwrap();
+        assert!(String::try_from(byte_array).is_err());
+    }
 }
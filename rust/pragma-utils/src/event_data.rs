@@ -0,0 +1,236 @@
+use starknet::core::types::{Felt, U256};
+
+pub use pragma_derive::FromStarknetEventData;
+
+use crate::conversions::{apibara::FromFieldBytes, starknet::ByteArray};
+
+/// Errors happening while decoding the data of a Starknet event.
+#[derive(Debug, thiserror::Error)]
+pub enum EventDataError {
+    #[error("missing {0}")]
+    MissingFelt(&'static str),
+    #[error("invalid length of {field}: {length} while {remaining} felts remain")]
+    InvalidLength { field: &'static str, length: Felt, remaining: usize },
+    #[error("invalid {field}: {reason}")]
+    Invalid { field: &'static str, reason: String },
+}
+
+/// Decodes a type from the data of a Starknet event, i.e the felts of its Cairo serialization.
+/// Usually implemented with `#[derive(FromStarknetEventData)]`.
+pub trait FromStarknetEventData: Sized {
+    type Error: From<EventDataError> + std::error::Error + Send + Sync + 'static;
+
+    /// Reads the value from the next felts of the event data, so it can be nested in another event.
+    fn read_event_data(reader: &mut FeltReader<'_>) -> Result<Self, Self::Error>;
+
+    fn from_starknet_event_data(data: Vec<Felt>) -> anyhow::Result<Self> {
+        Ok(Self::read_event_data(&mut FeltReader::new(&data))?)
+    }
+}
+
+/// Reads the felts of an event one after the other, failing instead of panicking
+/// when a felt is missing.
+pub struct FeltReader<'a> {
+    felts: &'a [Felt],
+    position: usize,
+}
+
+impl<'a> FeltReader<'a> {
+    pub fn new(felts: &'a [Felt]) -> Self {
+        Self { felts, position: 0 }
+    }
+
+    /// Felts not read yet.
+    pub fn remaining(&self) -> &'a [Felt] {
+        &self.felts[self.position..]
+    }
+
+    pub fn read_felt(&mut self, field: &'static str) -> Result<Felt, EventDataError> {
+        let felt = self.felts.get(self.position).ok_or(EventDataError::MissingFelt(field))?;
+        self.position += 1;
+        Ok(*felt)
+    }
+
+    /// Reads an integer stored in a single felt, failing if the felt does not fit in the integer.
+    pub fn read<T: FromFieldBytes>(&mut self, field: &'static str) -> Result<T, EventDataError> {
+        let felt = self.read_felt(field)?;
+        let bytes = felt.to_bytes_be();
+        if bytes[..32 - T::SIZE].iter().any(|byte| *byte != 0) {
+            let reason = format!("{:#x} does not fit in {} bytes", felt, T::SIZE);
+            return Err(EventDataError::Invalid { field, reason });
+        }
+        Ok(T::from_field_bytes(bytes))
+    }
+
+    /// Reads a u256 serialized by Cairo, i.e its low part first & then its high part.
+    pub fn read_u256(&mut self, field: &'static str) -> Result<U256, EventDataError> {
+        let low = self.read(field)?;
+        let high = self.read(field)?;
+        Ok(U256::from_words(low, high))
+    }
+
+    /// Reads the length of an array, which can't be greater than the number of felts remaining.
+    pub fn read_length(&mut self, field: &'static str) -> Result<usize, EventDataError> {
+        let length = self.read_felt(field)?;
+        let remaining = self.remaining().len();
+        if length > Felt::from(remaining) {
+            return Err(EventDataError::InvalidLength { field, length, remaining });
+        }
+        Ok(u64::from_field_bytes(length.to_bytes_be()) as usize)
+    }
+
+    /// Reads a Cairo `Span`, i.e its length followed by its elements.
+    pub fn read_span<T: FromFieldBytes>(&mut self, field: &'static str) -> Result<Vec<T>, EventDataError> {
+        let length = self.read_length(field)?;
+        (0..length).map(|_| self.read(field)).collect()
+    }
+
+    /// Reads a Cairo `ByteArray` holding a UTF-8 string.
+    pub fn read_byte_array(&mut self, field: &'static str) -> Result<String, EventDataError> {
+        let invalid = |reason: String| EventDataError::Invalid { field, reason };
+        let (byte_array, nb_felts) = ByteArray::from_felts(self.remaining()).map_err(|e| invalid(e.to_string()))?;
+        self.position += nb_felts;
+        String::try_from(byte_array).map_err(|e| invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::{felt, short_string};
+
+    use super::*;
+
+    #[test]
+    fn test_felt_reader() {
+        let felts = [Felt::from(7_u8), Felt::from(1_u8), Felt::from(2_u8)];
+        let mut reader = FeltReader::new(&felts);
+        assert_eq!(reader.read::<u32>("a").unwrap(), 7);
+        assert_eq!(reader.read_u256("b").unwrap(), U256::from_words(1, 2));
+        assert!(matches!(reader.read_felt("c"), Err(EventDataError::MissingFelt("c"))));
+        assert!(reader.remaining().is_empty());
+    }
+
+    #[test]
+    fn test_felt_reader_out_of_range() {
+        let felts = [Felt::from(u8::MAX), Felt::from(256_u16), Felt::from(u128::MAX), Felt::MAX];
+        let mut reader = FeltReader::new(&felts);
+        assert_eq!(reader.read::<u8>("a").unwrap(), u8::MAX);
+        assert!(matches!(reader.read::<u8>("b"), Err(EventDataError::Invalid { field: "b", .. })));
+        assert_eq!(reader.read::<u128>("c").unwrap(), u128::MAX);
+        assert!(matches!(reader.read::<u128>("d"), Err(EventDataError::Invalid { field: "d", .. })));
+
+        let mut reader = FeltReader::new(&felts[3..]);
+        assert_eq!(reader.read::<Felt>("e").unwrap(), Felt::MAX);
+
+        // The words of a u256 must fit in a u128
+        let felts = [Felt::ONE, Felt::from(u128::MAX) + Felt::ONE];
+        assert!(FeltReader::new(&felts).read_u256("f").is_err());
+    }
+
+    #[test]
+    fn test_read_span() {
+        let felts = [Felt::TWO, Felt::from(5_u8), Felt::from(6_u8)];
+        assert_eq!(FeltReader::new(&felts).read_span::<u64>("span").unwrap(), vec![5, 6]);
+
+        // The length can't exceed the felts remaining, nor be truncated to fit in an integer
+        for length in [Felt::THREE, Felt::from(u64::MAX), Felt::MAX] {
+            let felts = [length, Felt::from(5_u8), Felt::from(6_u8)];
+            assert!(matches!(
+                FeltReader::new(&felts).read_span::<u64>("span"),
+                Err(EventDataError::InvalidLength { field: "span", .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_read_byte_array() {
+        let felts = [Felt::ZERO, short_string!("hello"), Felt::from(5_u8)];
+        assert_eq!(FeltReader::new(&felts).read_byte_array("string").unwrap(), "hello");
+
+        let felts = [Felt::ZERO, Felt::ZERO, Felt::ZERO];
+        assert_eq!(FeltReader::new(&felts).read_byte_array("string").unwrap(), "");
+
+        // Serialization of "Long string, more than 31 characters." by Cairo
+        let felts = [
+            Felt::ONE,
+            felt!("0x4c6f6e6720737472696e672c206d6f7265207468616e203331206368617261"),
+            felt!("0x63746572732e"),
+            Felt::from(6_u8),
+        ];
+        assert_eq!(FeltReader::new(&felts).read_byte_array("string").unwrap(), "Long string, more than 31 characters.");
+
+        let invalid_byte_arrays = [
+            // Pending word longer than its length
+            vec![Felt::ZERO, short_string!("hello"), Felt::from(4_u8)],
+            // Pending word of 31 bytes
+            vec![Felt::ZERO, Felt::ZERO, Felt::from(31_u8)],
+            // Invalid UTF-8
+            vec![Felt::ZERO, Felt::from(0xff_u8), Felt::ONE],
+            // Missing pending word length
+            vec![Felt::ZERO, short_string!("hello")],
+        ];
+        for felts in invalid_byte_arrays {
+            assert!(FeltReader::new(&felts).read_byte_array("string").is_err());
+        }
+    }
+
+    #[derive(Debug, PartialEq, FromStarknetEventData)]
+    struct Inner {
+        a: u8,
+        b: Felt,
+    }
+
+    fn read_doubled(reader: &mut FeltReader<'_>) -> Result<u64, EventDataError> {
+        Ok(reader.read::<u64>("doubled")? * 2)
+    }
+
+    #[derive(Debug, PartialEq, FromStarknetEventData)]
+    struct Event {
+        id: u32,
+        #[event_data(u256)]
+        amount: U256,
+        #[event_data(byte_array)]
+        name: String,
+        #[event_data(span)]
+        values: Vec<u16>,
+        #[event_data(nested)]
+        inner: Inner,
+        #[event_data(with = read_doubled)]
+        doubled: u64,
+    }
+
+    #[test]
+    fn test_derive_from_starknet_event_data() {
+        let data = vec![
+            Felt::from(42_u8),
+            Felt::ONE,
+            Felt::TWO,
+            Felt::ZERO,
+            short_string!("pragma"),
+            Felt::from(6_u8),
+            Felt::TWO,
+            Felt::from(300_u16),
+            Felt::from(400_u16),
+            Felt::from(9_u8),
+            felt!("0x1234"),
+            Felt::from(21_u8),
+        ];
+
+        let event = Event::from_starknet_event_data(data.clone()).unwrap();
+        assert_eq!(
+            event,
+            Event {
+                id: 42,
+                amount: U256::from_words(1, 2),
+                name: "pragma".into(),
+                values: vec![300, 400],
+                inner: Inner { a: 9, b: felt!("0x1234") },
+                doubled: 42,
+            }
+        );
+
+        for len in 0..data.len() {
+            assert!(Event::from_starknet_event_data(data[..len].to_vec()).is_err());
+        }
+    }
+}
@@ -1,5 +1,9 @@
+// Allows the derive macros to refer to `pragma_utils` from within this crate.
+extern crate self as pragma_utils;
+
 pub mod bytes;
 pub mod conversions;
+pub mod event_data;
 pub mod http;
 pub mod services;
 pub mod tracing;
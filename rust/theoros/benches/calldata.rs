@@ -0,0 +1,97 @@
+//! Latency of [Calldata::build_from] depending on the number of signed checkpoints stored.
+//!
+//! Run with `cargo bench -p theoros --bench calldata --features bench`.
+
+use std::{collections::HashMap, sync::Arc};
+
+use alloy::primitives::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use prometheus::Registry;
+use starknet::core::types::{Felt, U256};
+use url::Url;
+
+use pragma_feeds::{AssetClass, FeedId, FeedType};
+use theoros::{
+    configs::evm_config::EvmChainName,
+    rpc::{evm::HyperlaneValidatorsMapping, starknet::StarknetRpc},
+    storage::{FeedIdsStorage, TheorosStorage, ValidatorsFetchersStorage},
+    test_utils::signed_checkpoint,
+    types::{
+        calldata::Calldata,
+        feeds_metadata::FeedsMetadataRegistry,
+        hyperlane::{DispatchUpdate, DispatchUpdateInfos, MetadataUpdate, SpotMedianUpdate},
+        state::{AppState, WsState},
+    },
+};
+
+const CHAIN_NAME: EvmChainName = EvmChainName::Mainnet;
+
+/// Number of nonces stored: the default retention, a signing backlog & a large backlog.
+const NONCES: [u32; 3] = [100, 1_000, 10_000];
+/// Number of validators signing each nonce.
+const VALIDATORS: [u8; 2] = [5, 20];
+
+/// Builds a state where every validator signed every nonce & the feed was last updated
+/// at the latest nonce.
+fn app_state(feed_id: &FeedId, nb_nonces: u32, nb_validators: u8) -> AppState {
+    let validators: HashMap<Felt, u8> = (0..nb_validators).map(|index| (Felt::from(index + 1), index)).collect();
+
+    let storage = TheorosStorage::new(
+        FeedIdsStorage::from_rpc_response(vec![feed_id.to_string()]),
+        ValidatorsFetchersStorage::new(false, HashMap::new()),
+    );
+    for nonce in 0..nb_nonces {
+        for validator in validators.keys() {
+            storage.signed_checkpoints().add(*validator, nonce, signed_checkpoint(nonce));
+        }
+    }
+
+    let update = SpotMedianUpdate {
+        pair_id: U256::from_words(1, 0),
+        metadata: MetadataUpdate { timestamp: 1_700_000_000, num_sources_aggregated: 5, decimals: 8 },
+        price: U256::from_words(6_500_000_000_000, 0),
+        volume: U256::from_words(0, 0),
+    };
+    storage.latest_update_per_feed().add(
+        feed_id.into(),
+        DispatchUpdateInfos {
+            nonce: nb_nonces - 1,
+            emitter_chain_id: 1,
+            emitter_address: Felt::ONE,
+            update: DispatchUpdate::SpotMedian { update, feed_id: feed_id.clone() },
+            message: Bytes::new(),
+        },
+    );
+
+    AppState {
+        starknet_rpc: Arc::new(StarknetRpc::new(Url::parse("http://localhost:9944").unwrap())),
+        hyperlane_validators_mapping: Arc::new(HyperlaneValidatorsMapping::new(HashMap::from([(
+            CHAIN_NAME, validators,
+        )]))),
+        storage: Arc::new(storage),
+        feeds_metadata: Arc::new(FeedsMetadataRegistry::default()),
+        metrics_registry: Registry::new(),
+        ws: Arc::new(WsState::new()),
+    }
+}
+
+fn bench_build_calldata(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let feed_id = FeedId::new(AssetClass::Crypto, FeedType::UniqueSpotMedian, "BTC/USD").unwrap();
+
+    let mut group = c.benchmark_group("calldata_build_from");
+    for nb_nonces in NONCES {
+        for nb_validators in VALIDATORS {
+            let state = app_state(&feed_id, nb_nonces, nb_validators);
+            let id = BenchmarkId::new(format!("{nb_validators}_validators"), nb_nonces);
+            group.bench_with_input(id, &state, |b, state| {
+                b.to_async(&runtime)
+                    .iter(|| async { Calldata::build_from(state, CHAIN_NAME, feed_id.to_string()).await.unwrap() })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_build_calldata);
+criterion_main!(benches);
@@ -0,0 +1,5 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto");
+    tonic_build::configure().build_client(false).compile(&["proto/theoros/v1/theoros.proto"], &["proto"])?;
+    Ok(())
+}
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use starknet::core::types::Felt;
+use theoros::types::hyperlane::DispatchEvent;
+
+// The input is split in felts of 32 bytes, reduced modulo the field prime.
+fuzz_target!(|data: &[u8]| {
+    let felts: Vec<Felt> = data.chunks(32).map(Felt::from_bytes_be_slice).collect();
+    let _ = DispatchEvent::decode(&felts);
+});
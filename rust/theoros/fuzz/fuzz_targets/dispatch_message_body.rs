@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use theoros::types::hyperlane::DispatchMessageBody;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = DispatchMessageBody::from_bytes(data);
+});
@@ -5,7 +5,8 @@ use apibara_sdk::Uri;
 use starknet::core::types::Felt;
 use url::Url;
 
-use crate::configs::evm_config;
+use crate::configs::{currencies_config, evm_config, feeds_config, validators_config};
+use crate::services::{EventSourceKind, OutOfOrderPolicy};
 
 #[derive(clap::Parser, Debug)]
 pub struct TheorosCli {
@@ -18,18 +19,46 @@ pub struct TheorosCli {
     #[clap(env = "MADARA_RPC_URL", long, value_parser = parse_url, default_value = "https://madara-pragma-prod.karnot.xyz/")]
     pub madara_rpc_url: Url,
 
+    /// Where the indexed events are read from: `apibara` streams them from Apibara DNA,
+    /// `starknet-rpc` polls them from the Madara RPC with `starknet_getEvents`.
+    #[clap(env = "EVENT_SOURCE", long, default_value_t = EventSourceKind::Apibara)]
+    pub event_source: EventSourceKind,
+
+    /// Duration in milliseconds between two polls of the events when no new block was produced.
+    /// Only used by the `starknet-rpc` event source.
+    #[clap(env = "EVENTS_POLL_INTERVAL_MS", long, default_value_t = 1000)]
+    pub events_poll_interval_ms: u64,
+
     #[clap(env = "APIBARA_DNA_URL", long, value_parser = parse_uri, default_value = "https://devnet.pragma.a5a.ch")]
     pub apibara_dna_uri: Uri,
 
+    /// Key used to authenticate with Apibara DNA, sent as bearer token.
     #[clap(env = "APIBARA_API_KEY", long)]
     pub apibara_api_key: Option<String>,
 
+    /// Duration in seconds after which connecting to Apibara DNA fails.
+    #[clap(env = "APIBARA_CONNECT_TIMEOUT_SECS", long, default_value_t = 10)]
+    pub apibara_connect_timeout_secs: u64,
+
+    /// Duration in seconds without any message from the Apibara DNA stream, heartbeats included,
+    /// after which the stream fails.
+    #[clap(env = "APIBARA_STREAM_TIMEOUT_SECS", long, default_value_t = 45)]
+    pub apibara_stream_timeout_secs: u64,
+
+    /// Maximum size in bytes of a message received from the Apibara DNA stream.
+    #[clap(env = "APIBARA_MAX_MESSAGE_SIZE_BYTES", long, default_value_t = 128 * 1024 * 1024)]
+    pub apibara_max_message_size_bytes: usize,
+
     #[clap(env = "SERVER_HOST", long, default_value = "0.0.0.0")]
     pub server_host: String,
 
     #[clap(env = "SERVER_PORT", long, default_value = "3000")]
     pub server_port: u16,
 
+    /// Port of the gRPC server. The gRPC server is only started if provided.
+    #[clap(env = "GRPC_PORT", long)]
+    pub grpc_port: Option<u16>,
+
     #[clap(env = "PRAGMA_FEEDS_REGISTRY_ADDRESS", long, value_parser = parse_felt)]
     pub pragma_feeds_registry_address: Felt,
 
@@ -39,6 +68,11 @@ pub struct TheorosCli {
     #[clap(env = "HYPERLANE_MERKLE_TREE_HOOK_ADDRESS", long, value_parser = parse_felt)]
     pub hyperlane_merkle_tree_hook_address: Felt,
 
+    /// Duration in seconds between two reconciliations of the merkle tree of the indexed messages
+    /// with the latest checkpoint of the merkle tree hook.
+    #[clap(env = "MERKLE_TREE_RECONCILE_INTERVAL_SECS", long, default_value_t = 30)]
+    pub merkle_tree_reconcile_interval_secs: u64,
+
     #[clap(env = "HYPERLANE_VALIDATOR_ANNOUNCE_ADDRESS", long, value_parser = parse_felt)]
     pub hyperlane_validator_announce_address: Felt,
 
@@ -51,8 +85,47 @@ pub struct TheorosCli {
     )]
     pub evm_config: evm_config::EvmConfig,
 
+    /// Path of the feeds configuration, used to name the feeds.
+    #[clap(env = "FEEDS_CONFIG_PATH", long, default_value = feeds_config::DEFAULT_FEEDS_CONFIG_PATH)]
+    pub feeds_config_path: String,
+
+    /// Path of the currencies configuration, used to describe the feeds currencies.
+    #[clap(env = "CURRENCIES_CONFIG_PATH", long, default_value = currencies_config::DEFAULT_CURRENCIES_CONFIG_PATH)]
+    pub currencies_config_path: String,
+
+    /// Path of the validators configuration, overriding the storage locations announced on-chain.
+    #[clap(env = "VALIDATORS_CONFIG_PATH", long, value_parser = parse_validators_config)]
+    pub validators_config: Option<validators_config::ValidatorsConfig>,
+
+    /// Duration in milliseconds during which a checkpoint not signed yet is not requested again.
+    #[clap(env = "CHECKPOINTS_NOT_SIGNED_TTL_MS", long, default_value_t = 5000)]
+    pub checkpoints_not_signed_ttl_ms: u64,
+
+    /// Duration in milliseconds during which the latest index of a validator is cached.
+    #[clap(env = "CHECKPOINTS_LATEST_INDEX_TTL_MS", long, default_value_t = 1000)]
+    pub checkpoints_latest_index_ttl_ms: u64,
+
+    /// Number of nonces for which the signed checkpoints are kept, before the oldest nonce
+    /// still referenced by the latest update of a feed.
+    #[clap(env = "SIGNED_CHECKPOINTS_RETENTION", long, default_value_t = 100)]
+    pub signed_checkpoints_retention: u32,
+
+    /// Duration in seconds after which a nonce that is not signed by all validators is dropped.
+    #[clap(env = "UNSIGNED_CHECKPOINTS_TIMEOUT_SECS", long, default_value_t = 600)]
+    pub unsigned_checkpoints_timeout_secs: u64,
+
+    /// What to do with the nonces still not signed by all validators when a newer nonce is:
+    /// `skip` drops them, `wait` keeps waiting for them & `alert` also reports them as errors.
+    #[clap(env = "OUT_OF_ORDER_NONCE_POLICY", long, default_value_t = OutOfOrderPolicy::Wait)]
+    pub out_of_order_nonce_policy: OutOfOrderPolicy,
+
     #[clap(env = "PROMETHEUS_EXTERNAL", long, default_value = "false")]
     pub prometheus_external: bool,
+
+    /// Fetches the checkpoints of the validators announcing a local storage (`file://`).
+    /// Only useful when running against a local devnet.
+    #[clap(env = "ALLOW_LOCAL_STORAGE", long, default_value = "false")]
+    pub allow_local_storage: bool,
 }
 
 /// Parse a Felt.
@@ -78,3 +151,9 @@ pub fn parse_evm_config(s: &str) -> anyhow::Result<evm_config::EvmConfig> {
     }
     evm_config::EvmConfig::from_file(s).with_context(|| format!("Failed to load EVM config from path: {}", s))
 }
+
+/// Parses the validators config path & returns it as [validators_config::ValidatorsConfig]
+pub fn parse_validators_config(s: &str) -> anyhow::Result<validators_config::ValidatorsConfig> {
+    validators_config::ValidatorsConfig::from_file(s)
+        .with_context(|| format!("Failed to load validators config from path: {}", s))
+}
@@ -0,0 +1,35 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::evm_config::ConfigError;
+
+pub const DEFAULT_CURRENCIES_CONFIG_PATH: &str = "../config/currencies.yaml";
+
+/// A currency known by Pragma.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CurrencyConfig {
+    pub name: String,
+    pub decimals: u32,
+    pub ticker: String,
+    pub coingecko_id: Option<String>,
+    /// Abstract currencies (USD, EUR...) are not deployed on any chain
+    #[serde(default, rename = "abstract")]
+    pub is_abstract: bool,
+    pub starknet_address: Option<String>,
+    pub ethereum_address: Option<String>,
+}
+
+/// Contains the currencies known by Pragma, see `config/currencies.yaml` at the root of the monorepo.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CurrenciesConfig(pub Vec<CurrencyConfig>);
+
+impl CurrenciesConfig {
+    /// Load configuration from a YAML file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        let config = serde_yaml::from_str(&contents)?;
+        Ok(config)
+    }
+}
@@ -59,6 +59,8 @@ pub enum ConfigError {
     FileRead(#[from] std::io::Error),
     #[error("Failed to parse YAML: {0}")]
     YamlParse(#[from] serde_yaml::Error),
+    #[error("Invalid validator configuration: {0}")]
+    InvalidValidator(String),
 }
 
 impl EvmConfig {
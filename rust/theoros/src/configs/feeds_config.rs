@@ -0,0 +1,32 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::evm_config::ConfigError;
+
+pub const DEFAULT_FEEDS_CONFIG_PATH: &str = "../config/feeds.yaml";
+
+/// A feed supported by Pragma.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FeedConfig {
+    /// Human readable name of the feed, e.g "BTC/USD: Spot Median"
+    pub name: String,
+    /// Hex encoded id of the feed
+    pub id: String,
+}
+
+/// Contains the supported Feeds by Pragma, see `config/feeds.yaml` at the root of the monorepo.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FeedsConfig {
+    pub feeds: Vec<FeedConfig>,
+}
+
+impl FeedsConfig {
+    /// Load configuration from a YAML file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        let config = serde_yaml::from_str(&contents)?;
+        Ok(config)
+    }
+}
@@ -1 +1,4 @@
+pub mod currencies_config;
 pub mod evm_config;
+pub mod feeds_config;
+pub mod validators_config;
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+
+use super::evm_config::ConfigError;
+
+/// Storage settings of a validator, overriding its on-chain announcements.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorStorageConfig {
+    /// Storage locations by priority: the first one is the primary storage & the next ones
+    /// are mirrors, tried in order when the previous ones fail. The location announced
+    /// on-chain is used as the last fallback.
+    #[serde(default)]
+    pub locations: Vec<String>,
+    /// Only uses the configured locations, ignoring the on-chain announcements.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Never fetches the checkpoints of the validator.
+    #[serde(default)]
+    pub blacklisted: bool,
+}
+
+/// Storage overrides of the validators, indexed by their address.
+/// ```yaml
+/// validators:
+///   "0x1234...":
+///     locations: ["s3://bucket/us-east-1", "https://mirror.example.com/validator"]
+///     pinned: true
+///   "0x5678...":
+///     blacklisted: true
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ValidatorsConfig {
+    #[serde(default)]
+    validators: HashMap<String, ValidatorStorageConfig>,
+}
+
+impl ValidatorsConfig {
+    /// Load configuration from a YAML file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        let config: Self = serde_yaml::from_str(&contents)?;
+        config.overrides()?;
+        Ok(config)
+    }
+
+    /// Returns the storage overrides of each validator.
+    pub fn overrides(&self) -> Result<HashMap<Felt, ValidatorStorageConfig>, ConfigError> {
+        self.validators
+            .iter()
+            .map(|(validator, storage)| {
+                let address = Felt::from_hex(validator)
+                    .map_err(|_| ConfigError::InvalidValidator(format!("invalid address {validator}")))?;
+                if storage.pinned && storage.locations.is_empty() {
+                    return Err(ConfigError::InvalidValidator(format!(
+                        "validator {validator} is pinned but has no storage location"
+                    )));
+                }
+                Ok((address, storage.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validators_config_overrides() {
+        let config: ValidatorsConfig = serde_yaml::from_str(
+            r#"
+validators:
+  "0x1":
+    locations: ["s3://bucket/us-east-1", "https://mirror.example.com"]
+    pinned: true
+  "0x2":
+    blacklisted: true
+"#,
+        )
+        .unwrap();
+
+        let overrides = config.overrides().unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(
+            overrides[&Felt::ONE],
+            ValidatorStorageConfig {
+                locations: vec!["s3://bucket/us-east-1".into(), "https://mirror.example.com".into()],
+                pinned: true,
+                blacklisted: false,
+            }
+        );
+        assert!(overrides[&Felt::TWO].blacklisted);
+    }
+
+    #[test]
+    fn test_invalid_validators_config() {
+        let config: ValidatorsConfig = serde_yaml::from_str("validators:\n  \"0x1\":\n    pinned: true\n").unwrap();
+        assert!(config.overrides().is_err(), "pinned without locations");
+
+        let config: ValidatorsConfig =
+            serde_yaml::from_str("validators:\n  \"validator\":\n    pinned: false\n").unwrap();
+        assert!(config.overrides().is_err(), "invalid address");
+
+        assert!(serde_yaml::from_str::<ValidatorsConfig>("validators:\n  \"0x1\":\n    pin: true\n").is_err());
+    }
+}
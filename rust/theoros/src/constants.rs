@@ -9,6 +9,7 @@ pub const TRAILING_HEADER_SIZE: u8 = 0;
 pub const PING_INTERVAL_DURATION: Duration = Duration::from_secs(30);
 pub const MAX_CLIENT_MESSAGE_SIZE: usize = 100 * 1024; // 100 KiB
 pub const FEED_UPDATED_CHANNEL_CAPACITY: usize = 1024;
+pub const SSE_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
 
 // TODO: add support for this
 /// The maximum number of bytes that can be sent per second per IP address.
@@ -2,6 +2,33 @@ use axum::{http::StatusCode, response::IntoResponse, Json};
 use serde_json::json;
 use utoipa::ToSchema;
 
+use crate::configs::evm_config::EvmChainName;
+
+/// Errors happening while building or encoding the calldata of a feed.
+#[derive(Debug, thiserror::Error)]
+pub enum CalldataError {
+    #[error("invalid feed id {0}")]
+    InvalidFeedId(String),
+    #[error("no update found for feed {0}")]
+    UpdateNotFound(String),
+    #[error("no validators found for chain {0}")]
+    NoValidators(EvmChainName),
+    #[error("no signatures found for nonce #{0}")]
+    NoSignatures(u32),
+    #[error("inconsistent checkpoints signed for nonce #{0}")]
+    InconsistentCheckpoints(u32),
+    #[error("invalid checkpoint root {0}")]
+    InvalidCheckpointRoot(String),
+    #[error("message of nonce #{0} does not match the signed message id")]
+    MessageIdMismatch(u32),
+    #[error("too many signatures: {0}")]
+    TooManySignatures(usize),
+    #[error("update data of {0} bytes is too long")]
+    UpdateTooLong(usize),
+    #[error("hyperlane message of {0} bytes is too long")]
+    MessageTooLong(usize),
+}
+
 #[derive(Debug, thiserror::Error, ToSchema)]
 #[allow(unused)]
 pub enum GetCalldataError {
@@ -9,8 +36,8 @@ pub enum GetCalldataError {
     InternalServerError,
     #[error("could not establish a connection with the database")]
     DatabaseConnection,
-    #[error("invalid feed id")]
-    InvalidFeedId,
+    #[error("Invalid feed ID '{0}': {1}")]
+    InvalidFeedId(String, String),
     #[error("could not find any dispatch event")]
     DispatchNotFound,
     #[error("Feed with ID '{0}' not found")]
@@ -23,10 +50,18 @@ pub enum GetCalldataError {
     ValidatorNotFound,
     #[error("The chain '{0}' is not supported")]
     ChainNotSupported(String),
+    #[error("The calldata mode '{0}' is not supported by this endpoint")]
+    UnsupportedMode(String),
     #[error("Error while building the calldata: {0}")]
     CalldataError(String),
 }
 
+impl From<CalldataError> for GetCalldataError {
+    fn from(e: CalldataError) -> Self {
+        Self::CalldataError(e.to_string())
+    }
+}
+
 impl IntoResponse for GetCalldataError {
     fn into_response(self) -> axum::response::Response {
         let (status, err_msg) = match self {
@@ -39,6 +74,7 @@ impl IntoResponse for GetCalldataError {
             Self::DispatchNotFound => {
                 (StatusCode::NOT_FOUND, "Could not find any Dispatch event for the provided Feed ID".into())
             }
+            Self::InvalidFeedId(..) | Self::UnsupportedMode(_) => (StatusCode::BAD_REQUEST, self.to_string()),
             Self::CalldataError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, String::from("Internal server error")),
         };
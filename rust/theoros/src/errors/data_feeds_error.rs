@@ -7,6 +7,8 @@ use utoipa::ToSchema;
 pub enum GetDataFeedsError {
     #[error("could not parse feed id: {0}")]
     ParsingFeedId(String),
+    #[error("unknown asset class: {0}")]
+    UnknownAssetClass(String),
     #[error("internal server error")]
     InternalServerError,
 }
@@ -15,6 +17,9 @@ impl IntoResponse for GetDataFeedsError {
     fn into_response(self) -> axum::response::Response {
         let (status, err_msg) = match self {
             Self::ParsingFeedId(feed_id) => (StatusCode::PROCESSING, format!("Could not parse feed: {feed_id}")),
+            Self::UnknownAssetClass(asset_class) => {
+                (StatusCode::BAD_REQUEST, format!("Unknown asset class: {asset_class}"))
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, String::from("Internal server error")),
         };
         (status, Json(json!({"resource":"Calldata", "message": err_msg, "happened_at" : chrono::Utc::now() })))
@@ -0,0 +1,17 @@
+use pragma_feeds::FeedType;
+use pragma_utils::event_data::EventDataError;
+
+/// Errors happening while decoding a Dispatch event.
+#[derive(Debug, thiserror::Error)]
+pub enum DispatchEventError {
+    #[error(transparent)]
+    EventData(#[from] EventDataError),
+    #[error("not enough bytes to read the {field}: {needed} needed, {remaining} remaining")]
+    UnexpectedEnd { field: &'static str, needed: usize, remaining: usize },
+    #[error("body of {size} bytes sent in {words} words")]
+    BodySizeMismatch { size: usize, words: usize },
+    #[error("invalid feed id: {0}")]
+    InvalidFeedId(String),
+    #[error("unsupported feed type: {0}")]
+    UnsupportedFeedType(FeedType),
+}
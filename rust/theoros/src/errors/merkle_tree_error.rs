@@ -0,0 +1,16 @@
+use alloy::primitives::B256;
+
+/// Errors happening while tracking the Hyperlane merkle tree or verifying checkpoints against it.
+#[derive(Debug, thiserror::Error)]
+pub enum MerkleTreeError {
+    #[error("the merkle tree is full")]
+    TreeFull,
+    #[error("expected message #{expected} to be inserted, got #{found}")]
+    UnexpectedIndex { expected: u32, found: u32 },
+    #[error("invalid checkpoint root {0}")]
+    InvalidRoot(String),
+    #[error("message #{index} has id {found} while {expected} is in the merkle tree")]
+    MessageIdMismatch { index: u32, expected: B256, found: B256 },
+    #[error("checkpoint #{index} has root {found} while the merkle tree root is {expected}")]
+    RootMismatch { index: u32, expected: B256, found: B256 },
+}
@@ -2,8 +2,12 @@ pub mod app_error;
 pub mod calldata_error;
 pub mod chains_error;
 pub mod data_feeds_error;
+pub mod dispatch_event_error;
+pub mod merkle_tree_error;
 
 pub use app_error::AppError;
-pub use calldata_error::GetCalldataError;
+pub use calldata_error::{CalldataError, GetCalldataError};
 pub use chains_error::GetChainsError;
 pub use data_feeds_error::GetDataFeedsError;
+pub use dispatch_event_error::DispatchEventError;
+pub use merkle_tree_error::MerkleTreeError;
@@ -1,2 +1,3 @@
 pub mod rest;
+pub mod sse;
 pub mod websocket;
@@ -8,10 +8,12 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToResponse, ToSchema};
 
+use pragma_feeds::FeedId;
+
 use crate::{
     configs::evm_config::EvmChainName,
     errors::GetCalldataError,
-    types::calldata::{AsCalldata, Calldata},
+    types::calldata::{AsCalldata, Calldata, MailboxCalldata},
     AppState,
 };
 
@@ -20,16 +22,51 @@ pub struct GetCalldataQuery {
     pub chain: String,
     #[serde(deserialize_with = "deserialize_feed_ids")]
     pub feed_ids: Vec<String>,
+    /// Format of the calldata, defaults to "pragma"
+    #[serde(default)]
+    pub mode: CalldataMode,
+}
+
+/// Contracts the calldata is built for.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, strum::Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum CalldataMode {
+    /// Calldata of the Pragma contract `updateDataFeeds` function.
+    #[default]
+    Pragma,
+    /// Arguments of the Hyperlane `Mailbox.process` function, verified by a `MessageIdMultisigIsm`.
+    HyperlaneMailbox,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToResponse, ToSchema)]
 pub struct CalldataResponse {
     pub feed_id: String,
-    pub encoded_calldata: String,
+    /// Calldata of the Pragma contract, in the "pragma" mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoded_calldata: Option<String>,
+    /// Metadata of the `MessageIdMultisigIsm`, in the "hyperlane-mailbox" mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+    /// Hyperlane message to process, in the "hyperlane-mailbox" mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
 pub type GetCalldataResponse = Vec<CalldataResponse>;
 
+/// Checks that the feed ids requested by a client can be parsed & that their pair ids
+/// follow the conventions of their asset class.
+pub fn validate_feed_ids(feed_ids: &[String]) -> Result<(), GetCalldataError> {
+    for feed_id in feed_ids {
+        feed_id
+            .parse::<FeedId>()
+            .and_then(|parsed_feed_id| parsed_feed_id.validate())
+            .map_err(|e| GetCalldataError::InvalidFeedId(feed_id.clone(), e.to_string()))?;
+    }
+    Ok(())
+}
+
 #[utoipa::path(
     get,
     path = "/v1/calldata",
@@ -42,6 +79,11 @@ pub type GetCalldataResponse = Vec<CalldataResponse>;
             description = "Constructs the calldata used to update the specified feed IDs",
             body = [GetCalldataResponse]
         ),
+        (
+            status = 400,
+            description = "Invalid Feed ID",
+            body = GetCalldataError
+        ),
         (
             status = 404,
             description = "Unknown Feed ID",
@@ -58,6 +100,7 @@ pub async fn get_calldata(
     let chain_name =
         EvmChainName::from_str(&params.chain).map_err(|_| GetCalldataError::ChainNotSupported(params.chain.clone()))?;
 
+    validate_feed_ids(&params.feed_ids)?;
     let stored_feed_ids = state.storage.feed_ids();
 
     // Check if all requested feed IDs are supported.
@@ -68,12 +111,26 @@ pub async fn get_calldata(
     // Build calldata for each feed ID.
     let mut responses: GetCalldataResponse = Vec::with_capacity(params.feed_ids.len());
     for feed_id in &params.feed_ids {
-        let calldata = Calldata::build_from(&state, chain_name, feed_id.clone())
-            .await
-            .map_err(|e| GetCalldataError::CalldataError(e.to_string()))?;
-
-        let response =
-            CalldataResponse { feed_id: feed_id.clone(), encoded_calldata: hex::encode(calldata.as_bytes()) };
+        let response = match params.mode {
+            CalldataMode::Pragma => {
+                let calldata = Calldata::build_from(&state, chain_name, feed_id.clone()).await?;
+                CalldataResponse {
+                    feed_id: feed_id.clone(),
+                    encoded_calldata: Some(hex::encode(calldata.as_bytes()?)),
+                    metadata: None,
+                    message: None,
+                }
+            }
+            CalldataMode::HyperlaneMailbox => {
+                let calldata = MailboxCalldata::build_from(&state, chain_name, feed_id.clone()).await?;
+                CalldataResponse {
+                    feed_id: feed_id.clone(),
+                    encoded_calldata: None,
+                    metadata: Some(hex::encode(calldata.metadata.as_bytes())),
+                    message: Some(hex::encode(&calldata.message)),
+                }
+            }
+        };
         responses.push(response);
     }
 
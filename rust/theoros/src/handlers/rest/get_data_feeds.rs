@@ -1,32 +1,58 @@
-use axum::extract::State;
+use std::str::FromStr;
+
+use axum::extract::{Query, State};
 use axum::Json;
 use serde::{Deserialize, Serialize};
-use utoipa::{ToResponse, ToSchema};
+use utoipa::{IntoParams, ToResponse, ToSchema};
 
-use pragma_feeds::Feed;
+use pragma_feeds::{AssetClass, Feed};
 
 use crate::errors::GetDataFeedsError;
+use crate::types::feeds_metadata::FeedWithMetadata;
 use crate::AppState;
 
+#[derive(Debug, Default, Deserialize, IntoParams, ToSchema)]
+pub struct GetDataFeedsQuery {
+    /// Only returns the feeds of this asset class, e.g "Crypto", "Forex", "Equities" or "Commodities"
+    pub asset_class: Option<String>,
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, ToResponse, ToSchema)]
-pub struct GetDataFeedsResponse(pub Vec<Feed>);
+pub struct GetDataFeedsResponse(pub Vec<FeedWithMetadata>);
 
 #[utoipa::path(
     get,
     path = "/v1/data_feeds",
+    params(
+        GetDataFeedsQuery
+    ),
     responses(
-        (status = 200, description = "Get all the available feed ids", body = [GetDataFeedsResponse])
+        (status = 200, description = "Get all the available feed ids", body = [GetDataFeedsResponse]),
+        (status = 400, description = "Unknown asset class", body = GetDataFeedsError)
     ),
 )]
-pub async fn get_data_feeds(State(state): State<AppState>) -> Result<Json<GetDataFeedsResponse>, GetDataFeedsError> {
+pub async fn get_data_feeds(
+    State(state): State<AppState>,
+    Query(params): Query<GetDataFeedsQuery>,
+) -> Result<Json<GetDataFeedsResponse>, GetDataFeedsError> {
     let started_at = std::time::Instant::now();
 
+    let asset_class = params
+        .asset_class
+        .map(|asset_class| {
+            AssetClass::from_str(&asset_class).map_err(|_| GetDataFeedsError::UnknownAssetClass(asset_class))
+        })
+        .transpose()?;
+
     let feed_ids = state.storage.feed_ids();
 
     let mut feeds = Vec::with_capacity(feed_ids.len());
     for feed_id in feed_ids.iter() {
-        let feed = feed_id.parse().map_err(|_| GetDataFeedsError::ParsingFeedId(feed_id.clone()))?;
-        feeds.push(feed);
+        let feed: Feed = feed_id.parse().map_err(|_| GetDataFeedsError::ParsingFeedId(feed_id.clone()))?;
+        if asset_class.is_some_and(|asset_class| feed.asset_class != asset_class) {
+            continue;
+        }
+        feeds.push(state.feeds_metadata.enrich(feed));
     }
 
     let response = GetDataFeedsResponse(feeds);
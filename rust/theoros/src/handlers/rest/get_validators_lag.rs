@@ -0,0 +1,57 @@
+use axum::extract::State;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+use utoipa::{ToResponse, ToSchema};
+
+use crate::storage::ValidatorLag;
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ValidatorLagResponse {
+    pub validator: String,
+    /// Index of the latest checkpoint signed by the validator
+    pub latest_index: Option<u32>,
+    /// Number of dispatched messages not signed yet by the validator
+    pub lag: u32,
+}
+
+impl From<ValidatorLag> for ValidatorLagResponse {
+    fn from(lag: ValidatorLag) -> Self {
+        Self { validator: format!("{:#x}", lag.validator), latest_index: lag.latest_index, lag: lag.lag }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToResponse, ToSchema)]
+pub struct GetValidatorsLagResponse {
+    /// Nonce of the latest message dispatched by the mailbox
+    pub latest_dispatched_nonce: Option<u32>,
+    pub validators: Vec<ValidatorLagResponse>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/validators/lag",
+    responses(
+        (
+            status = 200,
+            description = "Get how far behind the mailbox each validator is",
+            body = GetValidatorsLagResponse
+        )
+    ),
+)]
+pub async fn get_validators_lag(State(state): State<AppState>) -> Json<GetValidatorsLagResponse> {
+    let started_at = std::time::Instant::now();
+
+    let mut validators: Vec<Felt> = state.storage.validators_fetchers().all().into_keys().collect();
+    validators.sort();
+
+    let validators_lag = state.storage.validators_lag();
+    let response = GetValidatorsLagResponse {
+        latest_dispatched_nonce: validators_lag.latest_dispatched_nonce(),
+        validators: validators_lag.lags(&validators).into_iter().map(Into::into).collect(),
+    };
+
+    tracing::info!("🌐 get_validators_lag - {:?}", started_at.elapsed());
+    Json(response)
+}
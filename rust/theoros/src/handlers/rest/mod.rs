@@ -1,3 +1,4 @@
 pub mod get_calldata;
 pub mod get_chains;
 pub mod get_data_feeds;
+pub mod get_validators_lag;
@@ -0,0 +1,264 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use alloy::hex;
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive},
+        Sse,
+    },
+};
+use futures::Stream;
+use serde::Serialize;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::{
+    configs::evm_config::EvmChainName,
+    constants::SSE_KEEP_ALIVE_INTERVAL,
+    errors::GetCalldataError,
+    handlers::{
+        rest::get_calldata::{validate_feed_ids, CalldataMode, GetCalldataQuery},
+        websocket::subscribe_to_calldata::RpcDataFeed,
+    },
+    types::{
+        calldata::{AsCalldata, Calldata},
+        hyperlane::NewUpdatesAvailableEvent,
+    },
+    AppState,
+};
+
+/// Name of the SSE events containing calldata updates.
+const DATA_FEED_UPDATE_EVENT: &str = "data_feed_update";
+/// Header sent by the clients when reconnecting, containing the id of the last event received.
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+#[derive(Debug, Clone, Serialize)]
+struct SseDataFeedUpdate {
+    data_feeds: Vec<RpcDataFeed>,
+}
+
+/// Server-Sent Events route handler.
+///
+/// Streams a `data_feed_update` event every time one of the requested feeds is updated.
+/// The id of each event is the last Hyperlane nonce sent for every feed, see [LastSentNonces], so
+/// a client reconnecting with a `Last-Event-ID` header only receives the updates it missed.
+pub async fn sse_route_handler(
+    State(state): State<AppState>,
+    Query(params): Query<GetCalldataQuery>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, GetCalldataError> {
+    let chain_name =
+        EvmChainName::from_str(&params.chain).map_err(|_| GetCalldataError::ChainNotSupported(params.chain.clone()))?;
+    if !state.hyperlane_validators_mapping.is_supported_chain(&chain_name) {
+        return Err(GetCalldataError::ChainNotSupported(params.chain));
+    }
+    if params.mode != CalldataMode::Pragma {
+        return Err(GetCalldataError::UnsupportedMode(params.mode.to_string()));
+    }
+
+    // Check if all requested feed IDs are valid & supported.
+    validate_feed_ids(&params.feed_ids)?;
+    if let Some(missing_id) = state.storage.feed_ids().contains_vec(&params.feed_ids) {
+        return Err(GetCalldataError::FeedNotFound(missing_id));
+    }
+
+    let last_sent_nonces = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|last_event_id| LastSentNonces::from_last_event_id(last_event_id, &params.feed_ids))
+        .unwrap_or_default();
+
+    let feeds_receiver = state.storage.feeds_updated_tx().subscribe();
+    let subscriber = SseSubscriber {
+        state,
+        chain_name,
+        feed_ids: params.feed_ids,
+        feeds_receiver,
+        last_sent_nonces,
+        sent_initial_state: false,
+    };
+
+    let stream = futures::stream::unfold(subscriber, |mut subscriber| async move {
+        let event = subscriber.next_event().await?;
+        Some((event, subscriber))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(SSE_KEEP_ALIVE_INTERVAL).text("keep-alive")))
+}
+
+/// Represents a client connected via SSE.
+struct SseSubscriber {
+    state: AppState,
+    chain_name: EvmChainName,
+    feed_ids: Vec<String>,
+    feeds_receiver: Receiver<NewUpdatesAvailableEvent>,
+    /// Last nonce sent to the client for each feed.
+    last_sent_nonces: LastSentNonces,
+    sent_initial_state: bool,
+}
+
+impl SseSubscriber {
+    /// Waits for the next update containing data that the client has not received yet.
+    /// The latest known state is sent right away when the client connects.
+    /// Returns [None] when the updates channel is closed, which ends the stream.
+    async fn next_event(&mut self) -> Option<Result<Event, axum::Error>> {
+        loop {
+            if self.sent_initial_state {
+                match self.feeds_receiver.recv().await {
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!("🕸️ [SSE] Subscriber lagged behind by {} notifications", skipped);
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+            self.sent_initial_state = true;
+
+            if let Some(event) = self.build_update_event().await {
+                return Some(event);
+            }
+        }
+    }
+
+    /// Builds the calldata of every subscribed feed updated after the last nonce sent for it.
+    async fn build_update_event(&mut self) -> Option<Result<Event, axum::Error>> {
+        let mut data_feeds = Vec::with_capacity(self.feed_ids.len());
+
+        for feed_id in &self.feed_ids {
+            let calldata = match Calldata::build_from(&self.state, self.chain_name, feed_id.clone()).await {
+                Ok(calldata) => calldata,
+                Err(e) => {
+                    tracing::debug!("🕸️ [SSE] Could not build calldata for {}: {}", feed_id, e);
+                    continue;
+                }
+            };
+            let nonce = calldata.hyperlane_msg.nonce;
+            if !self.last_sent_nonces.is_new(feed_id, nonce) {
+                continue;
+            }
+            let encoded_calldata = match calldata.as_bytes() {
+                Ok(bytes) => hex::encode(bytes),
+                Err(e) => {
+                    tracing::debug!("🕸️ [SSE] Could not encode calldata for {}: {}", feed_id, e);
+                    continue;
+                }
+            };
+            self.last_sent_nonces.record(feed_id, nonce);
+            data_feeds.push(RpcDataFeed { feed_id: feed_id.clone(), encoded_calldata });
+        }
+
+        if data_feeds.is_empty() {
+            return None;
+        }
+
+        let event = Event::default()
+            .event(DATA_FEED_UPDATE_EVENT)
+            .id(self.last_sent_nonces.to_event_id())
+            .json_data(SseDataFeedUpdate { data_feeds });
+        Some(event)
+    }
+}
+
+/// Last Hyperlane nonce sent to a SSE client for each of its feeds.
+///
+/// Nonces are tracked per feed because feeds aren't updated in nonce order: with
+/// [crate::services::OutOfOrderPolicy::Wait], a feed can be updated with an older nonce
+/// than the last one sent for another feed, and must still be sent.
+///
+/// It's also the resume cursor of the client, used as the id of the events as
+/// `<feed_id>:<nonce>` pairs separated by commas.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct LastSentNonces(BTreeMap<String, u32>);
+
+impl LastSentNonces {
+    /// Parses the `Last-Event-ID` sent by a reconnecting client, keeping only the feeds it subscribes to.
+    /// Malformed entries are ignored, so the current state of their feed is sent again.
+    fn from_last_event_id(last_event_id: &str, feed_ids: &[String]) -> Self {
+        let nonces = last_event_id
+            .split(',')
+            .filter_map(|entry| {
+                let (feed_id, nonce) = entry.trim().split_once(':')?;
+                let nonce = nonce.parse::<u32>().ok()?;
+                feed_ids.iter().any(|id| id == feed_id).then(|| (feed_id.to_owned(), nonce))
+            })
+            .collect();
+        Self(nonces)
+    }
+
+    /// Returns true if the update of the feed at this nonce was not sent yet.
+    fn is_new(&self, feed_id: &str, nonce: u32) -> bool {
+        self.0.get(feed_id).is_none_or(|last_nonce| nonce > *last_nonce)
+    }
+
+    fn record(&mut self, feed_id: &str, nonce: u32) {
+        self.0.insert(feed_id.to_owned(), nonce);
+    }
+
+    fn to_event_id(&self) -> String {
+        self.0.iter().map(|(feed_id, nonce)| format!("{feed_id}:{nonce}")).collect::<Vec<_>>().join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BTC_USD: &str = "0x4254432f555344";
+    const ETH_USD: &str = "0x4554482f555344";
+
+    fn feed_ids() -> Vec<String> {
+        vec![BTC_USD.to_owned(), ETH_USD.to_owned()]
+    }
+
+    #[test]
+    fn test_parse_last_event_id() {
+        let nonces = LastSentNonces::from_last_event_id(&format!("{BTC_USD}:12,{ETH_USD}:7"), &feed_ids());
+        assert_eq!(nonces, LastSentNonces(BTreeMap::from([(BTC_USD.to_owned(), 12), (ETH_USD.to_owned(), 7)])));
+
+        // Malformed entries & feeds not subscribed to are ignored
+        let last_event_id = format!("{BTC_USD}:abc, 0x1234:3,{ETH_USD}:7,42");
+        let nonces = LastSentNonces::from_last_event_id(&last_event_id, &feed_ids());
+        assert_eq!(nonces, LastSentNonces(BTreeMap::from([(ETH_USD.to_owned(), 7)])));
+
+        assert_eq!(LastSentNonces::from_last_event_id("", &feed_ids()), LastSentNonces::default());
+    }
+
+    #[test]
+    fn test_event_id_round_trip() {
+        let mut nonces = LastSentNonces::default();
+        assert_eq!(nonces.to_event_id(), "");
+
+        nonces.record(ETH_USD, 7);
+        nonces.record(BTC_USD, 12);
+        let event_id = nonces.to_event_id();
+        assert_eq!(event_id, format!("{BTC_USD}:12,{ETH_USD}:7"));
+        assert_eq!(LastSentNonces::from_last_event_id(&event_id, &feed_ids()), nonces);
+    }
+
+    #[test]
+    fn test_older_nonce_of_another_feed_is_not_skipped() {
+        let mut nonces = LastSentNonces::default();
+        assert!(nonces.is_new(BTC_USD, 10));
+        nonces.record(BTC_USD, 10);
+
+        // ETH/USD was waiting for an older nonce to be signed, it must still be sent
+        assert!(nonces.is_new(ETH_USD, 8));
+        nonces.record(ETH_USD, 8);
+
+        assert!(!nonces.is_new(BTC_USD, 10));
+        assert!(!nonces.is_new(ETH_USD, 8));
+        assert!(nonces.is_new(ETH_USD, 9));
+    }
+
+    #[test]
+    fn test_resume_from_last_event_id() {
+        let nonces = LastSentNonces::from_last_event_id(&format!("{BTC_USD}:10"), &feed_ids());
+        // Updates already received before reconnecting are skipped
+        assert!(!nonces.is_new(BTC_USD, 9));
+        assert!(!nonces.is_new(BTC_USD, 10));
+        assert!(nonces.is_new(BTC_USD, 11));
+        // Feeds missing from the cursor get their current state
+        assert!(nonces.is_new(ETH_USD, 1));
+    }
+}
@@ -0,0 +1,143 @@
+use std::io::Write;
+
+use alloy::primitives::U256;
+use anyhow::{Context, Result};
+use flate2::{write::DeflateEncoder, Compression};
+use serde::Deserialize;
+
+use crate::configs::evm_config::EvmChainName;
+
+/// Version of the binary framing used for [BinaryDataFeedUpdate].
+pub const BINARY_FRAME_VERSION: u8 = 1;
+
+/// Encoding of the `data_feed_update` messages sent to a subscriber.
+/// Negotiated by the client when subscribing.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateEncoding {
+    /// JSON text messages, with the calldata represented as a hex string.
+    #[default]
+    Json,
+    /// Binary messages carrying the raw calldata bytes, see [BinaryDataFeedUpdate].
+    Binary,
+}
+
+/// Compression applied to the `data_feed_update` messages sent to a subscriber.
+/// Compressed messages are always sent as binary WebSocket messages.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateCompression {
+    #[default]
+    None,
+    /// Raw deflate stream (RFC 1951) of the encoded message.
+    Deflate,
+}
+
+impl UpdateCompression {
+    /// Compresses the provided encoded message.
+    pub fn compress(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            UpdateCompression::None => Ok(bytes),
+            UpdateCompression::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::with_capacity(bytes.len()), Compression::default());
+                encoder.write_all(&bytes).context("Deflating message")?;
+                encoder.finish().context("Deflating message")
+            }
+        }
+    }
+}
+
+/// A calldata update for a single feed in binary mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryFeedCalldata {
+    pub feed_id: U256,
+    /// Nonce of the Hyperlane message containing the update.
+    pub nonce: u32,
+    /// The raw calldata bytes.
+    pub calldata: Vec<u8>,
+}
+
+/// A `data_feed_update` message in binary mode. Encoded as:
+///
+/// ```text
+/// [version: u8] [chain_len: u8] [chain: chain_len bytes] [nb_feeds: u16]
+/// then for each feed:
+/// [feed_id: 32 bytes] [nonce: u32] [calldata_len: u32] [calldata: calldata_len bytes]
+/// ```
+///
+/// All integers are big-endian & the chain is its snake_case name, e.g "zircuit_testnet".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryDataFeedUpdate {
+    pub chain: EvmChainName,
+    pub data_feeds: Vec<BinaryFeedCalldata>,
+}
+
+impl BinaryDataFeedUpdate {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let chain = self.chain.to_string();
+        let calldata_size: usize = self.data_feeds.iter().map(|f| 40 + f.calldata.len()).sum();
+
+        let mut bytes = Vec::with_capacity(4 + chain.len() + calldata_size);
+        bytes.push(BINARY_FRAME_VERSION);
+        bytes.push(u8::try_from(chain.len()).context("Chain name too long")?);
+        bytes.extend_from_slice(chain.as_bytes());
+        bytes.extend_from_slice(&u16::try_from(self.data_feeds.len()).context("Too many feeds")?.to_be_bytes());
+        for data_feed in &self.data_feeds {
+            bytes.extend_from_slice(&data_feed.feed_id.to_be_bytes::<32>());
+            bytes.extend_from_slice(&data_feed.nonce.to_be_bytes());
+            bytes.extend_from_slice(
+                &u32::try_from(data_feed.calldata.len()).context("Calldata too long")?.to_be_bytes(),
+            );
+            bytes.extend_from_slice(&data_feed.calldata);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::DeflateDecoder;
+
+    use super::*;
+
+    #[test]
+    fn test_binary_data_feed_update_layout() {
+        let update = BinaryDataFeedUpdate {
+            chain: EvmChainName::ZircuitTestnet,
+            data_feeds: vec![BinaryFeedCalldata {
+                feed_id: U256::from(0x4254432f555344_u64),
+                nonce: 7,
+                calldata: vec![1, 2, 3],
+            }],
+        };
+        let bytes = update.to_bytes().unwrap();
+
+        let chain = b"zircuit_testnet";
+        assert_eq!(bytes[0], BINARY_FRAME_VERSION);
+        assert_eq!(bytes[1] as usize, chain.len());
+        assert_eq!(&bytes[2..2 + chain.len()], chain);
+
+        let feeds = &bytes[2 + chain.len()..];
+        assert_eq!(&feeds[..2], &[0, 1]);
+        assert_eq!(&feeds[2..34], &U256::from(0x4254432f555344_u64).to_be_bytes::<32>());
+        assert_eq!(&feeds[34..38], &7_u32.to_be_bytes());
+        assert_eq!(&feeds[38..42], &3_u32.to_be_bytes());
+        assert_eq!(&feeds[42..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_deflate_compression_roundtrip() {
+        let message = br#"{"type":"data_feed_update","data_feeds":[]}"#.repeat(10);
+
+        assert_eq!(UpdateCompression::None.compress(message.clone()).unwrap(), message);
+
+        let compressed = UpdateCompression::Deflate.compress(message.clone()).unwrap();
+        assert!(compressed.len() < message.len());
+
+        let mut decompressed = Vec::new();
+        DeflateDecoder::new(compressed.as_slice()).read_to_end(&mut decompressed).unwrap();
+        assert_eq!(decompressed, message);
+    }
+}
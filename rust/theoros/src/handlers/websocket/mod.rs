@@ -1 +1,2 @@
+pub mod encoding;
 pub mod subscribe_to_calldata;
@@ -21,9 +21,15 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast::Receiver;
 use utoipa::ToSchema;
 
+use pragma_feeds::FeedId;
+
 use crate::{
     configs::evm_config::EvmChainName,
     constants::{MAX_CLIENT_MESSAGE_SIZE, PING_INTERVAL_DURATION},
+    handlers::{
+        rest::get_calldata::validate_feed_ids,
+        websocket::encoding::{BinaryDataFeedUpdate, BinaryFeedCalldata, UpdateCompression, UpdateEncoding},
+    },
     types::{
         calldata::{AsCalldata, Calldata},
         hyperlane::NewUpdatesAvailableEvent,
@@ -37,8 +43,18 @@ pub struct DataFeedClientConfig {}
 #[derive(Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 enum ClientMessage {
+    /// Subscribes to the provided feed ids.
+    /// The `encoding` & `compression` of the updates can optionally be negotiated,
+    /// defaulting to uncompressed JSON.
     #[serde(rename = "subscribe")]
-    Subscribe { feed_ids: Vec<String>, chain: EvmChainName },
+    Subscribe {
+        feed_ids: Vec<String>,
+        chain: EvmChainName,
+        #[serde(default)]
+        encoding: UpdateEncoding,
+        #[serde(default)]
+        compression: UpdateCompression,
+    },
     #[serde(rename = "unsubscribe")]
     Unsubscribe { feed_ids: Vec<String> },
 }
@@ -108,6 +124,8 @@ pub struct Subscriber {
     sender: SplitSink<WebSocket, Message>,
     data_feeds_with_config: HashMap<String, DataFeedClientConfig>,
     active_chain: Option<EvmChainName>,
+    encoding: UpdateEncoding,
+    compression: UpdateCompression,
     ping_interval: tokio::time::Interval,
     responded_to_ping: bool,
 }
@@ -130,6 +148,8 @@ impl Subscriber {
             sender,
             data_feeds_with_config: HashMap::new(),
             active_chain: None,
+            encoding: UpdateEncoding::default(),
+            compression: UpdateCompression::default(),
             ping_interval: tokio::time::interval(PING_INTERVAL_DURATION),
             responded_to_ping: true,
         }
@@ -189,10 +209,7 @@ impl Subscriber {
         for feed_id in feed_ids {
             match Calldata::build_from(self.state.as_ref(), self.active_chain.unwrap(), feed_id.clone()).await {
                 Ok(calldata) => {
-                    data_feeds.push(RpcDataFeed {
-                        feed_id: feed_id.clone(),
-                        encoded_calldata: hex::encode(calldata.as_bytes()),
-                    });
+                    data_feeds.push((feed_id, calldata));
                 }
                 Err(e) => {
                     self.send_error_to_client(format!("Error building calldata for {}: {}", feed_id, e)).await?;
@@ -201,15 +218,69 @@ impl Subscriber {
         }
 
         // Send a single update containing all data feeds.
-        if !data_feeds.is_empty() {
-            let update = ServerMessage::DataFeedUpdate { data_feeds };
-            let message = serde_json::to_string(&update)?;
-            self.sender.send(Message::Text(message)).await?;
+        if let Some(message) = self.encode_data_feeds_update(data_feeds)? {
+            self.sender.send(message).await?;
         }
 
         Ok(())
     }
 
+    /// Encodes a data feeds update using the encoding & compression negotiated by the client.
+    /// Feeds that fail to be encoded are logged & skipped, so they don't close the subscription.
+    /// Returns `None` when there is no feed left to send.
+    fn encode_data_feeds_update(&self, data_feeds: Vec<(String, Calldata)>) -> Result<Option<Message>> {
+        let encoded = match self.encoding {
+            UpdateEncoding::Json => {
+                let data_feeds: Vec<_> = data_feeds
+                    .into_iter()
+                    .filter_map(|(feed_id, calldata)| {
+                        let encoded_calldata = self.skip_on_encoding_error(&feed_id, calldata.as_bytes())?;
+                        Some(RpcDataFeed { feed_id, encoded_calldata: hex::encode(encoded_calldata) })
+                    })
+                    .collect();
+                if data_feeds.is_empty() {
+                    return Ok(None);
+                }
+                let update = ServerMessage::DataFeedUpdate { data_feeds };
+                let message = serde_json::to_string(&update)?;
+                if self.compression == UpdateCompression::None {
+                    return Ok(Some(Message::Text(message)));
+                }
+                message.into_bytes()
+            }
+            UpdateEncoding::Binary => {
+                let data_feeds: Vec<_> = data_feeds
+                    .into_iter()
+                    .filter_map(|(feed_id, calldata)| {
+                        let encoded = feed_id.parse::<FeedId>().and_then(|parsed_feed_id| {
+                            Ok(BinaryFeedCalldata {
+                                feed_id: (&parsed_feed_id).into(),
+                                nonce: calldata.hyperlane_msg.nonce,
+                                calldata: calldata.as_bytes()?,
+                            })
+                        });
+                        self.skip_on_encoding_error(&feed_id, encoded)
+                    })
+                    .collect();
+                if data_feeds.is_empty() {
+                    return Ok(None);
+                }
+                let update = BinaryDataFeedUpdate { chain: self.active_chain.unwrap(), data_feeds };
+                update.to_bytes()?
+            }
+        };
+        Ok(Some(Message::Binary(self.compression.compress(encoded)?)))
+    }
+
+    /// Logs the error of a feed that couldn't be encoded, returning `None` so it's skipped.
+    fn skip_on_encoding_error<T, E: std::fmt::Debug>(&self, feed_id: &str, encoded: Result<T, E>) -> Option<T> {
+        encoded
+            .inspect_err(|e| {
+                tracing::warn!(subscriber = self.id, feed_id, "Skipping feed that could not be encoded: {:?}", e)
+            })
+            .ok()
+    }
+
     /// Processes messages received from the client.
     #[tracing::instrument(skip(self, message))]
     async fn handle_client_message(&mut self, message: Message) -> Result<()> {
@@ -221,9 +292,10 @@ impl Subscriber {
                 Ok(())
             }
             Message::Text(text) => self.process_client_message(&text).await,
-            Message::Binary(data) => {
-                let text = String::from_utf8(data)?;
-                self.process_client_message(&text).await
+            // Only the updates sent by the server can be binary, client messages must be JSON text.
+            Message::Binary(_) => {
+                self.send_error_to_client("Binary messages are not supported, send JSON text messages instead.".into())
+                    .await
             }
             Message::Ping(_) => Ok(()), // Axum handles PONG responses automatically.
             Message::Pong(_) => {
@@ -244,7 +316,7 @@ impl Subscriber {
         };
 
         match client_message {
-            ClientMessage::Subscribe { feed_ids, chain } => {
+            ClientMessage::Subscribe { feed_ids, chain, encoding, compression } => {
                 // Check if the chain is supported
                 if !self.state.hyperlane_validators_mapping.is_supported_chain(&chain) {
                     self.send_error_to_client(format!(
@@ -254,7 +326,11 @@ impl Subscriber {
                     .await?;
                     return Ok(());
                 }
-                // Check if all requested feed IDs are supported.
+                // Check if all requested feed IDs are valid & supported.
+                if let Err(e) = validate_feed_ids(&feed_ids) {
+                    self.send_error_to_client(format!("Can't subscribe: {}", e)).await?;
+                    return Ok(());
+                }
                 let stored_feed_ids = self.state.storage.feed_ids();
                 if let Some(missing_id) = stored_feed_ids.contains_vec(&feed_ids) {
                     self.send_error_to_client(format!("Can't subscribe: feed ID not supported {:}", missing_id))
@@ -264,6 +340,8 @@ impl Subscriber {
 
                 // Subscribe to the requested feed IDs.
                 self.active_chain = Some(chain);
+                self.encoding = encoding;
+                self.compression = compression;
                 for feed_id in feed_ids {
                     self.data_feeds_with_config.insert(feed_id, DataFeedClientConfig {});
                 }
@@ -0,0 +1,15 @@
+pub mod cli;
+pub mod configs;
+pub mod constants;
+pub mod errors;
+pub mod extractors;
+pub mod handlers;
+pub mod rpc;
+pub mod services;
+pub mod storage;
+#[cfg(any(test, feature = "bench"))]
+#[doc(hidden)]
+pub mod test_utils;
+pub mod types;
+
+pub use types::state::AppState;
@@ -1,19 +1,7 @@
-mod cli;
-mod configs;
-mod constants;
-mod errors;
-mod extractors;
-mod handlers;
-mod rpc;
-mod services;
-mod storage;
-mod types;
-
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::Result;
 use clap::Parser;
-use storage::TheorosStorage;
 use tracing::Level;
 
 use pragma_utils::{
@@ -21,10 +9,20 @@ use pragma_utils::{
     tracing::init_tracing,
 };
 
-use cli::TheorosCli;
-use rpc::{evm::HyperlaneValidatorsMapping, starknet::StarknetRpc};
-use services::{ApiService, HyperlaneService, IndexerService, MetricsService};
-use types::state::{AppState, WsState};
+use theoros::{
+    cli::TheorosCli,
+    rpc::{evm::HyperlaneValidatorsMapping, starknet::StarknetRpc},
+    services::{
+        ApiService, ApibaraClientConfig, EventSourceConfig, EventSourceKind, GrpcService, HyperlaneService,
+        IndexerService, MerkleTreeService, MetricsService, RetentionPolicy, RetentionService,
+    },
+    storage::{TheorosStorage, ValidatorsFetchersStorage},
+    types::{
+        feeds_metadata::FeedsMetadataRegistry,
+        hyperlane::cached::{FetchCacheConfig, FetchCacheMetrics},
+        state::{AppState, WsState},
+    },
+};
 
 const LOG_LEVEL: Level = Level::INFO;
 
@@ -38,41 +36,87 @@ async fn main() -> Result<()> {
     let starknet_rpc = StarknetRpc::new(config.madara_rpc_url);
     let hyperlane_validators_mapping = HyperlaneValidatorsMapping::from_config(&config.evm_config).await?;
 
+    let metrics_service = MetricsService::new(config.prometheus_external, config.metrics_port)?;
+
+    let fetch_cache_config = FetchCacheConfig {
+        not_signed_ttl: Duration::from_millis(config.checkpoints_not_signed_ttl_ms),
+        latest_index_ttl: Duration::from_millis(config.checkpoints_latest_index_ttl_ms),
+    };
+    let validators_overrides = config.validators_config.unwrap_or_default().overrides()?;
+    let validators_fetchers = ValidatorsFetchersStorage::new(config.allow_local_storage, validators_overrides)
+        .with_fetch_cache(fetch_cache_config, FetchCacheMetrics::register(&metrics_service.registry())?);
     let theoros_storage = TheorosStorage::from_rpc_state(
         &starknet_rpc,
         &config.pragma_feeds_registry_address,
         &config.hyperlane_validator_announce_address,
+        validators_fetchers,
     )
     .await?;
 
-    let metrics_service = MetricsService::new(config.prometheus_external, config.metrics_port)?;
+    let feeds_metadata = FeedsMetadataRegistry::from_files(&config.feeds_config_path, &config.currencies_config_path)?;
+    let registered_feed_ids: Vec<String> = theoros_storage.feed_ids().iter().collect();
+    for mismatch in feeds_metadata.find_mismatches(&registered_feed_ids) {
+        tracing::warn!("⚠️ Feeds config mismatch: {}", mismatch);
+    }
 
     let state = AppState {
         starknet_rpc: Arc::new(starknet_rpc),
         hyperlane_validators_mapping: Arc::new(hyperlane_validators_mapping),
         storage: Arc::new(theoros_storage),
+        feeds_metadata: Arc::new(feeds_metadata),
         metrics_registry: metrics_service.registry(),
         ws: Arc::new(WsState::new()),
     };
 
+    let event_source_config = match config.event_source {
+        EventSourceKind::Apibara => EventSourceConfig::Apibara(ApibaraClientConfig {
+            uri: config.apibara_dna_uri,
+            api_key: config.apibara_api_key,
+            connect_timeout: Duration::from_secs(config.apibara_connect_timeout_secs),
+            stream_timeout: Duration::from_secs(config.apibara_stream_timeout_secs),
+            max_message_size_bytes: config.apibara_max_message_size_bytes,
+        }),
+        EventSourceKind::StarknetRpc => {
+            EventSourceConfig::StarknetRpc { poll_interval: Duration::from_millis(config.events_poll_interval_ms) }
+        }
+    };
     let indexer_service = IndexerService::new(
         state.clone(),
-        config.apibara_dna_uri,
+        event_source_config,
         config.hyperlane_mailbox_address,
+        config.hyperlane_merkle_tree_hook_address,
         config.hyperlane_validator_announce_address,
         config.pragma_feeds_registry_address,
         state.starknet_rpc.block_number().await?,
+        &state.metrics_registry,
     )?;
-    let hyperlane_service = HyperlaneService::new(state.storage.clone());
+    let hyperlane_service =
+        HyperlaneService::new(state.storage.clone(), config.out_of_order_nonce_policy, &state.metrics_registry)?;
+    let merkle_tree_service = MerkleTreeService::new(
+        state.starknet_rpc.clone(),
+        state.storage.clone(),
+        config.hyperlane_merkle_tree_hook_address,
+        Duration::from_secs(config.merkle_tree_reconcile_interval_secs),
+        &state.metrics_registry,
+    )?;
+    let retention_policy = RetentionPolicy {
+        signed_checkpoints_retention: config.signed_checkpoints_retention,
+        unsigned_checkpoints_timeout: Duration::from_secs(config.unsigned_checkpoints_timeout_secs),
+    };
+    let retention_service = RetentionService::new(state.storage.clone(), retention_policy, &state.metrics_registry)?;
     let api_service = ApiService::new(state.clone(), &config.server_host, config.server_port);
 
-    ServiceGroup::default()
+    let mut services = ServiceGroup::default()
         .with(metrics_service)
         .with(indexer_service)
         .with(hyperlane_service)
-        .with(api_service)
-        .start_and_drive_to_end()
-        .await?;
+        .with(merkle_tree_service)
+        .with(retention_service)
+        .with(api_service);
+    if let Some(grpc_port) = config.grpc_port {
+        services.push(GrpcService::new(state.clone(), &config.server_host, grpc_port));
+    }
+    services.start_and_drive_to_end().await?;
 
     // Ensure that the tracing provider is shutdown correctly
     opentelemetry::global::shutdown_tracer_provider();
@@ -15,6 +15,11 @@ use crate::configs::evm_config::{EvmChainName, EvmConfig};
 pub struct HyperlaneValidatorsMapping(HashMap<EvmChainName, HashMap<Felt, u8>>);
 
 impl HyperlaneValidatorsMapping {
+    /// Creates a mapping from the validators & their indexes of each chain.
+    pub fn new(validators: HashMap<EvmChainName, HashMap<Felt, u8>>) -> Self {
+        Self(validators)
+    }
+
     pub async fn from_config(config: &EvmConfig) -> anyhow::Result<Self> {
         let mut contracts = HashMap::new();
 
@@ -28,7 +33,7 @@ impl HyperlaneValidatorsMapping {
             contracts.insert(*chain_name, validators);
         }
 
-        Ok(Self(contracts))
+        Ok(Self::new(contracts))
     }
 
     /// Get the available validators for a chain & their indexes
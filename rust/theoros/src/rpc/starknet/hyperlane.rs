@@ -1,12 +1,18 @@
+use alloy::primitives::B256;
+use anyhow::Context;
 use starknet::{
     core::types::{BlockId, BlockTag, Felt, FunctionCall},
     macros::selector,
     providers::Provider,
 };
 
-use pragma_utils::conversions::starknet::process_nested_felt_array;
+use pragma_utils::conversions::{apibara::FromFieldBytes, starknet::process_nested_felt_array};
 
 use super::StarknetRpc;
+use crate::types::hyperlane::{b256_from_words, IncrementalMerkleTree, TREE_DEPTH};
+
+/// Number of felts of a `ByteData` Cairo struct: the value (u256) & its size.
+const BYTE_DATA_FELT_SIZE: usize = 3;
 
 #[async_trait::async_trait]
 pub trait HyperlaneCalls {
@@ -27,8 +33,10 @@ pub trait HyperlaneCalls {
     /// Retrieves the latest checkpoint (root, index) tuple from the merkle tree hook contract.
     /// The index is the latest checkpoint index.
     /// The root is the latest checkpoint root.
-    #[allow(unused)]
-    async fn get_latest_checkpoint(&self, merkle_tree_hook_address: &Felt) -> anyhow::Result<Vec<Felt>>;
+    async fn get_latest_checkpoint(&self, merkle_tree_hook_address: &Felt) -> anyhow::Result<(B256, u32)>;
+
+    /// Retrieves the merkle tree (branch & count) from the merkle tree hook contract.
+    async fn get_merkle_tree(&self, merkle_tree_hook_address: &Felt) -> anyhow::Result<IncrementalMerkleTree>;
 }
 
 #[async_trait::async_trait]
@@ -64,13 +72,74 @@ impl HyperlaneCalls for StarknetRpc {
         Ok(response)
     }
 
-    async fn get_latest_checkpoint(&self, merkle_tree_hook_address: &Felt) -> anyhow::Result<Vec<Felt>> {
+    async fn get_latest_checkpoint(&self, merkle_tree_hook_address: &Felt) -> anyhow::Result<(B256, u32)> {
         let call = FunctionCall {
             contract_address: *merkle_tree_hook_address,
             entry_point_selector: selector!("latest_checkpoint"),
             calldata: vec![],
         };
         let response = self.0.call(call, BlockId::Tag(BlockTag::Pending)).await?;
-        Ok(response)
+        // (u256, u32) => [root_low, root_high, index]
+        let [root_low, root_high, index] = response.as_slice() else {
+            anyhow::bail!("Invalid latest checkpoint response: {:?}", response);
+        };
+        Ok((b256_from_words(root_low, root_high), u32::from_field_bytes(index.to_bytes_be())))
+    }
+
+    async fn get_merkle_tree(&self, merkle_tree_hook_address: &Felt) -> anyhow::Result<IncrementalMerkleTree> {
+        let call = FunctionCall {
+            contract_address: *merkle_tree_hook_address,
+            entry_point_selector: selector!("tree"),
+            calldata: vec![],
+        };
+        let response = self.0.call(call, BlockId::Tag(BlockTag::Pending)).await?;
+        parse_merkle_tree(&response)
+    }
+}
+
+/// Parses a `Tree` Cairo struct, which is:
+/// 0. branch length
+/// 1. branch, as `ByteData` (value low, value high, size) for each height
+/// 2. count low
+/// 3. count high
+fn parse_merkle_tree(felts: &[Felt]) -> anyhow::Result<IncrementalMerkleTree> {
+    let (branch_len, felts) = felts.split_first().context("Missing branch length")?;
+    let branch_len = u32::from_field_bytes(branch_len.to_bytes_be()) as usize;
+    anyhow::ensure!(branch_len == TREE_DEPTH, "Invalid branch length: {}", branch_len);
+    anyhow::ensure!(felts.len() == branch_len * BYTE_DATA_FELT_SIZE + 2, "Invalid merkle tree length");
+
+    let (branch_felts, count) = felts.split_at(branch_len * BYTE_DATA_FELT_SIZE);
+    let mut branch = [B256::ZERO; TREE_DEPTH];
+    for (node, byte_data) in branch.iter_mut().zip(branch_felts.chunks(BYTE_DATA_FELT_SIZE)) {
+        *node = b256_from_words(&byte_data[0], &byte_data[1]);
+    }
+    let [count_low, count_high] = count else {
+        anyhow::bail!("Missing merkle tree count");
+    };
+    anyhow::ensure!(*count_high == Felt::ZERO, "Invalid merkle tree count");
+    let count = u32::try_from(u128::from_field_bytes(count_low.to_bytes_be())).context("Invalid merkle tree count")?;
+    Ok(IncrementalMerkleTree::from_branch(branch, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_merkle_tree() {
+        let mut felts = vec![Felt::from(TREE_DEPTH)];
+        for height in 0..TREE_DEPTH {
+            felts.extend([Felt::from(height + 1), Felt::ZERO, Felt::from(32)]);
+        }
+        felts.extend([Felt::from(3), Felt::ZERO]);
+
+        let tree = parse_merkle_tree(&felts).unwrap();
+        assert_eq!(tree.count(), 3);
+        // With 3 leaves, the latest leaf is the first node of the branch
+        assert_eq!(tree.leaf(2), Some(B256::with_last_byte(1)));
+
+        assert!(parse_merkle_tree(&felts[..felts.len() - 1]).is_err());
+        felts[0] = Felt::from(TREE_DEPTH - 1);
+        assert!(parse_merkle_tree(&felts).is_err());
     }
 }
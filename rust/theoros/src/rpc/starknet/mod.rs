@@ -5,9 +5,15 @@ pub use hyperlane::*;
 pub use pragma_feeds_registry::*;
 
 use anyhow::Context;
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::{
+    core::types::{BlockId, EmittedEvent, EventFilter, Felt},
+    providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider},
+};
 use url::Url;
 
+/// Maximum number of events per page requested to `starknet_getEvents`.
+const EVENTS_CHUNK_SIZE: u64 = 1000;
+
 pub struct StarknetRpc(JsonRpcClient<HttpTransport>);
 
 impl StarknetRpc {
@@ -18,4 +24,36 @@ impl StarknetRpc {
     pub async fn block_number(&self) -> anyhow::Result<u64> {
         self.0.block_number().await.context("Fetching block number")
     }
+
+    /// Retrieves all the events emitted by the contract between the two blocks (included) whose
+    /// first key is one of the provided selectors, in the order they were emitted.
+    pub async fn get_events(
+        &self,
+        address: Felt,
+        selectors: &[Felt],
+        from_block: u64,
+        to_block: u64,
+    ) -> anyhow::Result<Vec<EmittedEvent>> {
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number(from_block)),
+            to_block: Some(BlockId::Number(to_block)),
+            address: Some(address),
+            keys: Some(vec![selectors.to_vec()]),
+        };
+
+        let mut events = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let page = self
+                .0
+                .get_events(filter.clone(), continuation_token, EVENTS_CHUNK_SIZE)
+                .await
+                .context("Fetching events")?;
+            events.extend(page.events);
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                return Ok(events);
+            }
+        }
+    }
 }
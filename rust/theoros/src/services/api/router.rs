@@ -9,6 +9,8 @@ use utoipa_swagger_ui::SwaggerUi;
 use crate::handlers::rest::get_calldata::get_calldata;
 use crate::handlers::rest::get_chains::get_chains;
 use crate::handlers::rest::get_data_feeds::get_data_feeds;
+use crate::handlers::rest::get_validators_lag::get_validators_lag;
+use crate::handlers::sse::subscribe_to_calldata::sse_route_handler;
 use crate::handlers::websocket::subscribe_to_calldata::ws_route_handler;
 use crate::AppState;
 
@@ -23,7 +25,9 @@ pub fn api_router<T: OpenApiT>(state: AppState) -> Router<AppState> {
                 .merge(calldata_routes(state.clone()))
                 .merge(data_feeds_routes(state.clone()))
                 .merge(chains_routes(state.clone()))
-                .merge(ws_route(state.clone())),
+                .merge(validators_routes(state.clone()))
+                .merge(ws_route(state.clone()))
+                .merge(sse_route(state.clone())),
         )
         .fallback(handler_404)
 }
@@ -40,6 +44,10 @@ fn ws_route(state: AppState) -> Router<AppState> {
     Router::new().route("/ws/calldata", get(ws_route_handler)).with_state(state)
 }
 
+fn sse_route(state: AppState) -> Router<AppState> {
+    Router::new().route("/sse/calldata", get(sse_route_handler)).with_state(state)
+}
+
 fn calldata_routes(state: AppState) -> Router<AppState> {
     Router::new().route("/calldata", get(get_calldata)).with_state(state)
 }
@@ -51,3 +59,7 @@ fn data_feeds_routes(state: AppState) -> Router<AppState> {
 fn chains_routes(state: AppState) -> Router<AppState> {
     Router::new().route("/chains", get(get_chains).with_state(state))
 }
+
+fn validators_routes(state: AppState) -> Router<AppState> {
+    Router::new().route("/validators/lag", get(get_validators_lag).with_state(state))
+}
@@ -0,0 +1,230 @@
+pub mod proto {
+    tonic::include_proto!("theoros.v1");
+}
+
+use std::{net::SocketAddr, pin::Pin, str::FromStr};
+
+use anyhow::{Context, Result};
+use futures::Stream;
+use tokio::{
+    sync::broadcast::{error::RecvError, Receiver},
+    task::JoinSet,
+};
+use tonic::{transport::Server, Request, Response, Status};
+
+use pragma_feeds::{AssetClass, Feed};
+use pragma_utils::services::Service;
+
+use crate::{
+    configs::evm_config::EvmChainName,
+    errors::CalldataError,
+    handlers::rest::get_calldata::validate_feed_ids,
+    types::{
+        calldata::{AsCalldata, Calldata},
+        feeds_metadata::CurrencyInfo,
+        hyperlane::NewUpdatesAvailableEvent,
+    },
+    AppState,
+};
+
+use proto::theoros_server::{Theoros, TheorosServer};
+
+pub struct GrpcService {
+    state: AppState,
+    host: String,
+    port: u16,
+}
+
+impl GrpcService {
+    pub fn new(state: AppState, host: &str, port: u16) -> Self {
+        Self { state, host: host.to_owned(), port }
+    }
+}
+
+#[async_trait::async_trait]
+impl Service for GrpcService {
+    async fn start(&mut self, join_set: &mut JoinSet<Result<()>>) -> anyhow::Result<()> {
+        let host = self.host.to_owned();
+        let port = self.port;
+        let state = self.state.clone();
+
+        join_set.spawn(async move {
+            let address = format!("{}:{}", host, port);
+            let socket_addr: SocketAddr = address.parse()?;
+
+            tracing::info!("🧩 gRPC server started at http://{}", socket_addr);
+            Server::builder()
+                .add_service(TheorosServer::new(TheorosGrpc { state }))
+                .serve(socket_addr)
+                .await
+                .context("😱 gRPC server stopped!")
+        });
+        Ok(())
+    }
+}
+
+/// Implementation of the Theoros gRPC API.
+struct TheorosGrpc {
+    state: AppState,
+}
+
+impl TheorosGrpc {
+    /// Checks that the chain & all the feed ids requested are valid & supported.
+    fn check_request(&self, chain: &str, feed_ids: &[String]) -> Result<EvmChainName, Status> {
+        let chain_name = EvmChainName::from_str(chain)
+            .ok()
+            .filter(|chain_name| self.state.hyperlane_validators_mapping.is_supported_chain(chain_name))
+            .ok_or_else(|| Status::invalid_argument(format!("The chain '{}' is not supported", chain)))?;
+
+        validate_feed_ids(feed_ids).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        if let Some(missing_id) = self.state.storage.feed_ids().contains_vec(feed_ids) {
+            return Err(Status::not_found(format!("Feed ID \"{}\" is not registered", missing_id)));
+        }
+
+        Ok(chain_name)
+    }
+}
+
+type CalldataUpdatesStream = Pin<Box<dyn Stream<Item = Result<proto::CalldataUpdate, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Theoros for TheorosGrpc {
+    async fn get_chains(
+        &self,
+        _request: Request<proto::GetChainsRequest>,
+    ) -> Result<Response<proto::GetChainsResponse>, Status> {
+        let chains = self.state.hyperlane_validators_mapping.chain_names().iter().map(|c| c.to_string()).collect();
+        Ok(Response::new(proto::GetChainsResponse { chains }))
+    }
+
+    async fn get_data_feeds(
+        &self,
+        request: Request<proto::GetDataFeedsRequest>,
+    ) -> Result<Response<proto::GetDataFeedsResponse>, Status> {
+        let asset_class = request
+            .into_inner()
+            .asset_class
+            .map(|asset_class| {
+                AssetClass::from_str(&asset_class)
+                    .map_err(|_| Status::invalid_argument(format!("Unknown asset class: {asset_class}")))
+            })
+            .transpose()?;
+
+        let mut data_feeds = Vec::with_capacity(self.state.storage.feed_ids().len());
+        for feed_id in self.state.storage.feed_ids().iter() {
+            let feed: Feed =
+                feed_id.parse().map_err(|_| Status::internal(format!("Could not parse feed: {feed_id}")))?;
+            if asset_class.is_some_and(|asset_class| feed.asset_class != asset_class) {
+                continue;
+            }
+            let enriched = self.state.feeds_metadata.enrich(feed);
+            data_feeds.push(proto::DataFeed {
+                feed_id: enriched.feed.feed_id,
+                asset_class: enriched.feed.asset_class.to_string(),
+                feed_type: enriched.feed.feed_type.to_string(),
+                pair_id: enriched.feed.pair_id,
+                name: enriched.name,
+                base_currency: enriched.base_currency.map(Into::into),
+                quote_currency: enriched.quote_currency.map(Into::into),
+            });
+        }
+        Ok(Response::new(proto::GetDataFeedsResponse { data_feeds }))
+    }
+
+    async fn get_calldata(
+        &self,
+        request: Request<proto::GetCalldataRequest>,
+    ) -> Result<Response<proto::GetCalldataResponse>, Status> {
+        let request = request.into_inner();
+        let chain_name = self.check_request(&request.chain, &request.feed_ids)?;
+
+        let mut calldata = Vec::with_capacity(request.feed_ids.len());
+        for feed_id in request.feed_ids {
+            let feed_calldata = Calldata::build_from(&self.state, chain_name, feed_id.clone())
+                .await
+                .and_then(|feed_calldata| as_feed_calldata(feed_id, &feed_calldata))
+                .map_err(|e| Status::internal(format!("Error while building the calldata: {e}")))?;
+            calldata.push(feed_calldata);
+        }
+
+        Ok(Response::new(proto::GetCalldataResponse { calldata }))
+    }
+
+    type SubscribeCalldataStream = CalldataUpdatesStream;
+
+    async fn subscribe_calldata(
+        &self,
+        request: Request<proto::SubscribeCalldataRequest>,
+    ) -> Result<Response<Self::SubscribeCalldataStream>, Status> {
+        let request = request.into_inner();
+        let chain_name = self.check_request(&request.chain, &request.feed_ids)?;
+
+        let subscriber = GrpcSubscriber {
+            state: self.state.clone(),
+            chain_name,
+            feed_ids: request.feed_ids,
+            feeds_receiver: self.state.storage.feeds_updated_tx().subscribe(),
+        };
+
+        let stream = futures::stream::unfold(subscriber, |mut subscriber| async move {
+            let update = subscriber.next_update().await?;
+            Some((Ok::<_, Status>(update), subscriber))
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// A client subscribed to calldata updates through gRPC.
+struct GrpcSubscriber {
+    state: AppState,
+    chain_name: EvmChainName,
+    feed_ids: Vec<String>,
+    feeds_receiver: Receiver<NewUpdatesAvailableEvent>,
+}
+
+impl GrpcSubscriber {
+    /// Waits for the next update notification & builds the calldata of all the subscribed feeds.
+    /// Returns [None] when the updates channel is closed, which ends the stream.
+    async fn next_update(&mut self) -> Option<proto::CalldataUpdate> {
+        loop {
+            match self.feeds_receiver.recv().await {
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!("🕸️ [gRPC] Subscriber lagged behind by {} notifications", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+
+            let mut data_feeds = Vec::with_capacity(self.feed_ids.len());
+            for feed_id in &self.feed_ids {
+                let calldata = Calldata::build_from(&self.state, self.chain_name, feed_id.clone())
+                    .await
+                    .and_then(|calldata| as_feed_calldata(feed_id.clone(), &calldata));
+                match calldata {
+                    Ok(calldata) => data_feeds.push(calldata),
+                    Err(e) => tracing::debug!("🕸️ [gRPC] Could not build calldata for {}: {}", feed_id, e),
+                }
+            }
+
+            if !data_feeds.is_empty() {
+                return Some(proto::CalldataUpdate { data_feeds });
+            }
+        }
+    }
+}
+
+impl From<CurrencyInfo> for proto::Currency {
+    fn from(currency: CurrencyInfo) -> Self {
+        Self {
+            name: currency.name,
+            ticker: currency.ticker,
+            decimals: currency.decimals,
+            coingecko_id: currency.coingecko_id,
+        }
+    }
+}
+
+fn as_feed_calldata(feed_id: String, calldata: &Calldata) -> Result<proto::FeedCalldata, CalldataError> {
+    Ok(proto::FeedCalldata { feed_id, encoded_calldata: calldata.as_bytes()?, nonce: calldata.hyperlane_msg.nonce })
+}
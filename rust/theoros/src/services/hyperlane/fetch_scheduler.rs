@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use starknet::core::types::Felt;
+use tokio::time::Instant;
+
+/// Delay before retrying a validator after its first failure.
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+/// Maximum delay between two attempts for a failing validator.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Fetch state of a single validator.
+#[derive(Debug, Clone, Default)]
+struct ValidatorFetchState {
+    /// Number of consecutive failed fetches.
+    consecutive_failures: u32,
+    /// The validator won't be queried before this instant.
+    retry_at: Option<Instant>,
+}
+
+/// Keeps track of the checkpoints fetching state of each validator, so we can
+/// back off from validators whose storage is failing.
+#[derive(Debug, Default)]
+pub struct CheckpointFetchScheduler {
+    validators: HashMap<Felt, ValidatorFetchState>,
+}
+
+impl CheckpointFetchScheduler {
+    /// Returns true if the validator can be queried at the provided instant.
+    pub fn is_ready(&self, validator: &Felt, now: Instant) -> bool {
+        self.validators.get(validator).and_then(|state| state.retry_at).is_none_or(|retry_at| now >= retry_at)
+    }
+
+    /// Records a successful fetch & resets the backoff of the validator.
+    pub fn record_success(&mut self, validator: Felt) {
+        self.validators.remove(&validator);
+    }
+
+    /// Records a failed fetch & backs off exponentially from the validator.
+    /// Returns the delay before the validator can be queried again.
+    pub fn record_failure(&mut self, validator: Felt, now: Instant) -> Duration {
+        let state = self.validators.entry(validator).or_default();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        let backoff = backoff_for(state.consecutive_failures);
+        state.retry_at = Some(now + backoff);
+        backoff
+    }
+
+    /// Forgets the validators that are not registered anymore.
+    pub fn retain_validators(&mut self, validators: &[Felt]) {
+        self.validators.retain(|validator, _| validators.contains(validator));
+    }
+}
+
+/// Exponential backoff: [BASE_BACKOFF] doubled for each consecutive failure, capped at [MAX_BACKOFF].
+fn backoff_for(consecutive_failures: u32) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    BASE_BACKOFF.saturating_mul(1 << exponent).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_for() {
+        assert_eq!(backoff_for(1), Duration::from_secs(1));
+        assert_eq!(backoff_for(2), Duration::from_secs(2));
+        assert_eq!(backoff_for(4), Duration::from_secs(8));
+        assert_eq!(backoff_for(7), MAX_BACKOFF);
+        assert_eq!(backoff_for(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_scheduler_backs_off_failing_validators() {
+        let mut scheduler = CheckpointFetchScheduler::default();
+        let validator = Felt::ONE;
+        let now = Instant::now();
+
+        assert!(scheduler.is_ready(&validator, now));
+
+        assert_eq!(scheduler.record_failure(validator, now), Duration::from_secs(1));
+        assert!(!scheduler.is_ready(&validator, now));
+        assert!(scheduler.is_ready(&validator, now + Duration::from_secs(1)));
+
+        assert_eq!(scheduler.record_failure(validator, now), Duration::from_secs(2));
+        assert!(!scheduler.is_ready(&validator, now + Duration::from_secs(1)));
+
+        scheduler.record_success(validator);
+        assert!(scheduler.is_ready(&validator, now));
+        assert_eq!(scheduler.record_failure(validator, now), Duration::from_secs(1));
+
+        // Other validators are not impacted
+        assert!(scheduler.is_ready(&Felt::TWO, now));
+    }
+
+    #[test]
+    fn test_scheduler_forgets_removed_validators() {
+        let mut scheduler = CheckpointFetchScheduler::default();
+        let now = Instant::now();
+        scheduler.record_failure(Felt::ONE, now);
+        scheduler.record_failure(Felt::TWO, now);
+
+        scheduler.retain_validators(&[Felt::TWO]);
+        assert!(scheduler.is_ready(&Felt::ONE, now));
+        assert!(!scheduler.is_ready(&Felt::TWO, now));
+    }
+}
@@ -0,0 +1,102 @@
+use prometheus::{IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry};
+use starknet::core::types::Felt;
+
+use crate::storage::{CheckpointVerification, ValidatorLag};
+
+/// Prometheus metrics about the validators signing the dispatched messages.
+#[derive(Clone)]
+pub struct ValidatorsLagMetrics {
+    latest_dispatched_nonce: IntGauge,
+    validator_latest_index: IntGaugeVec,
+    validator_lag: IntGaugeVec,
+    out_of_order_nonces: IntCounter,
+    invalid_checkpoints: IntCounterVec,
+    unverified_checkpoints: IntCounterVec,
+}
+
+impl ValidatorsLagMetrics {
+    pub fn register(registry: &Registry) -> anyhow::Result<Self> {
+        let latest_dispatched_nonce = IntGauge::with_opts(Opts::new(
+            "theoros_mailbox_latest_dispatched_nonce",
+            "Nonce of the latest message dispatched by the mailbox",
+        ))?;
+        let validator_latest_index = IntGaugeVec::new(
+            Opts::new("theoros_validator_latest_index", "Index of the latest checkpoint signed by the validator"),
+            &["validator"],
+        )?;
+        let validator_lag = IntGaugeVec::new(
+            Opts::new("theoros_validator_lag", "Number of dispatched messages not signed yet by the validator"),
+            &["validator"],
+        )?;
+
+        let out_of_order_nonces = IntCounter::with_opts(Opts::new(
+            "theoros_out_of_order_nonces_total",
+            "Pending nonces still not signed by all validators when a newer nonce is",
+        ))?;
+
+        let invalid_checkpoints = IntCounterVec::new(
+            Opts::new(
+                "theoros_invalid_checkpoints_total",
+                "Checkpoints signed by the validator rejected because they don't match the merkle tree",
+            ),
+            &["validator"],
+        )?;
+
+        let unverified_checkpoints = IntCounterVec::new(
+            Opts::new(
+                "theoros_unverified_checkpoints_total",
+                "Checkpoints signed by the validator that could not be verified against the merkle tree, by reason",
+            ),
+            &["validator", "reason"],
+        )?;
+
+        registry.register(Box::new(latest_dispatched_nonce.clone()))?;
+        registry.register(Box::new(validator_latest_index.clone()))?;
+        registry.register(Box::new(validator_lag.clone()))?;
+        registry.register(Box::new(out_of_order_nonces.clone()))?;
+        registry.register(Box::new(invalid_checkpoints.clone()))?;
+        registry.register(Box::new(unverified_checkpoints.clone()))?;
+
+        Ok(Self {
+            latest_dispatched_nonce,
+            validator_latest_index,
+            validator_lag,
+            out_of_order_nonces,
+            invalid_checkpoints,
+            unverified_checkpoints,
+        })
+    }
+
+    /// Counts a checkpoint of the validator that does not match the merkle tree.
+    pub fn record_invalid_checkpoint(&self, validator: &Felt) {
+        self.invalid_checkpoints.with_label_values(&[&format!("{:#x}", validator)]).inc();
+    }
+
+    /// Counts a checkpoint of the validator that could not be verified against the merkle tree.
+    pub fn record_unverified_checkpoint(&self, validator: &Felt, reason: CheckpointVerification) {
+        self.unverified_checkpoints.with_label_values(&[&format!("{:#x}", validator), &reason.to_string()]).inc();
+    }
+
+    /// Counts the nonces found older than a fully signed nonce.
+    pub fn record_out_of_order(&self, count: usize) {
+        self.out_of_order_nonces.inc_by(count as u64);
+    }
+
+    /// Updates the gauges with the current lag of every validator.
+    pub fn update(&self, latest_dispatched_nonce: Option<u32>, lags: &[ValidatorLag]) {
+        if let Some(nonce) = latest_dispatched_nonce {
+            self.latest_dispatched_nonce.set(nonce.into());
+        }
+
+        // Reset so the validators that are not registered anymore are removed
+        self.validator_latest_index.reset();
+        self.validator_lag.reset();
+        for lag in lags {
+            let validator = format!("{:#x}", lag.validator);
+            if let Some(latest_index) = lag.latest_index {
+                self.validator_latest_index.with_label_values(&[&validator]).set(latest_index.into());
+            }
+            self.validator_lag.with_label_values(&[&validator]).set(lag.lag.into());
+        }
+    }
+}
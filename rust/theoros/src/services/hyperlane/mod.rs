@@ -1,21 +1,37 @@
+mod fetch_scheduler;
+mod metrics;
+mod out_of_order;
+
 use std::{sync::Arc, time::Duration};
 
+use futures::StreamExt;
+use prometheus::Registry;
 use starknet::core::types::Felt;
-use tokio::task::JoinSet;
+use tokio::{task::JoinSet, time::Instant};
 
-use pragma_utils::{conversions::alloy::hex_str_to_u256, services::Service};
+use pragma_utils::services::Service;
 
-use crate::storage::TheorosStorage;
+use crate::storage::{CheckpointVerification, TheorosStorage};
 use crate::types::hyperlane::{
     DispatchUpdateInfos, FetchFromStorage, NewUpdatesAvailableEvent, SignedCheckpointWithMessageId,
 };
 
+use fetch_scheduler::CheckpointFetchScheduler;
+use metrics::ValidatorsLagMetrics;
+use out_of_order::OutOfOrderNonces;
+pub use out_of_order::OutOfOrderPolicy;
+
 /// Every [FETCH_INTERVAL] seconds, we check the pending checkpoints for all validators.
+/// We also check them as soon as a new Dispatch nonce is indexed.
 const FETCH_INTERVAL: Duration = Duration::from_secs(1);
+/// Maximum number of checkpoints fetched in parallel from a single validator storage.
+const MAX_CONCURRENT_FETCHES_PER_VALIDATOR: usize = 8;
 
 #[derive(Clone)]
 pub struct HyperlaneService {
     storage: Arc<TheorosStorage>,
+    out_of_order_policy: OutOfOrderPolicy,
+    metrics: ValidatorsLagMetrics,
 }
 
 #[async_trait::async_trait]
@@ -32,14 +48,25 @@ impl Service for HyperlaneService {
 }
 
 impl HyperlaneService {
-    pub fn new(storage: Arc<TheorosStorage>) -> Self {
-        Self { storage }
+    pub fn new(
+        storage: Arc<TheorosStorage>,
+        out_of_order_policy: OutOfOrderPolicy,
+        metrics_registry: &Registry,
+    ) -> anyhow::Result<Self> {
+        let metrics = ValidatorsLagMetrics::register(metrics_registry)?;
+        Ok(Self { storage, out_of_order_policy, metrics })
     }
 
     pub async fn run_forever(&self) -> anyhow::Result<()> {
+        let mut scheduler = CheckpointFetchScheduler::default();
+        let mut out_of_order = OutOfOrderNonces::default();
         loop {
-            self.process_validator_checkpoints().await;
-            tokio::time::sleep(FETCH_INTERVAL).await;
+            self.process_validator_checkpoints(&mut scheduler, &mut out_of_order).await;
+            self.update_lag_metrics();
+            tokio::select! {
+                _ = self.storage.unsigned_checkpoints().wait_for_new_nonce() => {}
+                _ = tokio::time::sleep(FETCH_INTERVAL) => {}
+            }
         }
     }
 
@@ -49,12 +76,17 @@ impl HyperlaneService {
     ///
     /// 1. **Retrieve Unsigned Nonces**:
     ///    - Fetches all the nonces currently stored in the `UnsignedCheckpointsStorage`.
+    ///    - Even when there are none, the validators are still queried below so their latest
+    ///      signed index, i.e their lag, stays up to date.
     ///
     /// 2. **Retrieve Validators and Fetchers**:
     ///    - Gets all registered validators and their corresponding fetchers from the `ValidatorsFetchersStorage`.
     ///
     /// 3. **Fetch Signed Checkpoints**:
-    ///    - Attempts to fetch the signed checkpoint all unsigned nonce from each validator's fetcher (in parallel),
+    ///    - For each validator that is not backing off, fetches its latest signed index first,
+    ///    - Then fetches the signed checkpoints of the unsigned nonces at or below this index,
+    ///      with at most [MAX_CONCURRENT_FETCHES_PER_VALIDATOR] requests in flight per validator.
+    ///    - Validators are processed in parallel. A validator failing is backed off exponentially.
     ///
     /// 4. **Process Completed Nonces**:
     ///    - After all fetches are completed, iterates over the unsigned nonces again.
@@ -62,33 +94,59 @@ impl HyperlaneService {
     ///    - **Note**: Currently, the function only proceeds if **all** validators have signed the nonce.
     ///      - There's a `TODO` to modify this behavior to use a quorum method (e.g., consider a nonce as valid if 66% of validators have signed it).
     ///    - If all validators have signed the nonce:
-    ///        - Calls `store_event_updates(nonce)` to process and store the updates associated with that nonce.
+    ///        - Calls `store_dispatch_updates(nonce)` to store the updates that are newer than the stored ones.
     ///        - Removes the nonce from the `UnsignedCheckpointsStorage`, as it has been fully processed.
     ///
-    async fn process_validator_checkpoints(&self) {
+    /// 5. **Handle Out Of Order Nonces**:
+    ///    - The unsigned nonces older than a fully signed nonce are handled according to the [OutOfOrderPolicy].
+    ///
+    async fn process_validator_checkpoints(
+        &self,
+        scheduler: &mut CheckpointFetchScheduler,
+        out_of_order: &mut OutOfOrderNonces,
+    ) {
         let unsigned_nonces = self.storage.unsigned_checkpoints().nonces().await;
-        if unsigned_nonces.is_empty() {
-            return;
-        }
 
         let validators_fetchers = self.storage.validators_fetchers().all();
-        let mut futures = Vec::with_capacity(unsigned_nonces.len());
-        for &nonce in &unsigned_nonces {
-            for (validator, fetcher) in &validators_fetchers {
-                let fut = self.fetch_checkpoint_for_validator(*validator, fetcher.clone(), nonce);
-                futures.push(fut);
+        let validator_addresses: Vec<Felt> = validators_fetchers.keys().cloned().collect();
+        scheduler.retain_validators(&validator_addresses);
+
+        let now = Instant::now();
+        let futures = validators_fetchers.iter().filter(|(validator, _)| scheduler.is_ready(validator, now)).map(
+            |(validator, fetcher)| async {
+                (*validator, self.fetch_checkpoints_for_validator(*validator, fetcher.clone(), &unsigned_nonces).await)
+            },
+        );
+        let results = futures::future::join_all(futures).await;
+
+        for (validator, result) in results {
+            match result {
+                Ok(latest_index) => {
+                    scheduler.record_success(validator);
+                    if let Some(latest_index) = latest_index {
+                        self.storage.validators_lag().set_latest_index(validator, latest_index);
+                    }
+                }
+                Err(e) => {
+                    let backoff = scheduler.record_failure(validator, Instant::now());
+                    tracing::error!(
+                        "🌉 [Hyperlane] Failed to fetch checkpoints for validator {:#x}, retrying in {:?}: {:?}",
+                        validator,
+                        backoff,
+                        e
+                    );
+                }
             }
         }
-        futures::future::join_all(futures).await;
 
         // NOTE: At the moment, we only process updates when ALL validators have signed a message.
         // TODO: We should instead use a quorum method - if 66% have signed, consider it ok.
-        let validator_addresses: Vec<Felt> = validators_fetchers.keys().cloned().collect();
-        for &nonce in &unsigned_nonces {
-            if !self.all_validators_signed_nonce(&validator_addresses, nonce) {
-                continue;
-            }
-            // TODO: If the nonce n+1 is fully signed, shall we ignore every nonces before..? Or raise an alert?
+        let signed_nonces: Vec<u32> = unsigned_nonces
+            .iter()
+            .copied()
+            .filter(|&nonce| self.all_validators_signed_nonce(&validator_addresses, nonce))
+            .collect();
+        for &nonce in &signed_nonces {
             tracing::info!("🌉 [Hyperlane] ✅ Nonce #{} is fully signed by all validators! Storing updates...", nonce);
             if let Err(e) = self.store_dispatch_updates(nonce).await {
                 tracing::error!("😱 Failed to store event updates for nonce {}: {:?}", nonce, e);
@@ -96,6 +154,41 @@ impl HyperlaneService {
             self.send_websocket_notification().await;
             self.storage.unsigned_checkpoints().remove(nonce).await;
         }
+
+        let outdated_nonces = out_of_order.newly_outdated(&unsigned_nonces, &signed_nonces);
+        self.handle_out_of_order_nonces(&outdated_nonces).await;
+    }
+
+    /// Applies the [OutOfOrderPolicy] on the unsigned nonces older than a fully signed nonce.
+    async fn handle_out_of_order_nonces(&self, outdated_nonces: &[u32]) {
+        self.metrics.record_out_of_order(outdated_nonces.len());
+        for &nonce in outdated_nonces {
+            match self.out_of_order_policy {
+                OutOfOrderPolicy::Skip => {
+                    tracing::warn!("🌉 [Hyperlane] ⏭️ Skipping nonce #{}: a newer nonce is already signed", nonce);
+                    self.storage.unsigned_checkpoints().remove(nonce).await;
+                }
+                OutOfOrderPolicy::Wait => {
+                    tracing::info!(
+                        "🌉 [Hyperlane] ⏳ Nonce #{} is not fully signed while a newer nonce is, waiting for it",
+                        nonce
+                    );
+                }
+                OutOfOrderPolicy::Alert => {
+                    tracing::error!(
+                        "🌉 [Hyperlane] 🚨 Nonce #{} is not fully signed while a newer nonce is! Still waiting for it",
+                        nonce
+                    );
+                }
+            }
+        }
+    }
+
+    /// Updates the metrics tracking how far behind the mailbox each validator is.
+    fn update_lag_metrics(&self) {
+        let validators: Vec<Felt> = self.storage.validators_fetchers().all().into_keys().collect();
+        let validators_lag = self.storage.validators_lag();
+        self.metrics.update(validators_lag.latest_dispatched_nonce(), &validators_lag.lags(&validators));
     }
 
     /// Checks if all validators have signed a given nonce.
@@ -103,38 +196,64 @@ impl HyperlaneService {
         self.storage.signed_checkpoints().all_validators_signed_nonce(validators_addresses, nonce)
     }
 
-    /// Given a validator & a nonce, query the fetcher to try to get the signed checkpoint.
-    /// If it exists, it will get stored in the Signed Checkpoints storage.
-    async fn fetch_checkpoint_for_validator(
+    /// Given a validator & the unsigned nonces, query the fetcher to get the signed checkpoints.
+    /// Only the nonces at or below the latest index signed by the validator are requested.
+    /// The checkpoints found get stored in the Signed Checkpoints storage.
+    /// Returns the latest index signed by the validator, or the first error encountered.
+    async fn fetch_checkpoints_for_validator(
         &self,
         validator: Felt,
         fetcher: Arc<dyn FetchFromStorage + Send + Sync>,
-        nonce: u32,
-    ) {
-        // If the validator already signed this nonce, ignore
-        if self.storage.signed_checkpoints().validator_signed_nonce(validator, nonce) {
-            return;
-        }
+        unsigned_nonces: &[u32],
+    ) -> anyhow::Result<Option<u32>> {
+        let Some(latest_index) = fetcher.latest_index().await? else {
+            tracing::debug!("🌉 [Hyperlane] Validator {:#x} has not signed any checkpoint yet", validator);
+            return Ok(None);
+        };
 
-        match fetcher.fetch(nonce).await {
-            Ok(Some(checkpoint)) => {
-                self.store_signed_checkpoint(validator, checkpoint);
-            }
-            Ok(None) => {
-                tracing::debug!("🌉 [Hyperlane] Validator {:#x} has not yet signed nonce {}", validator, nonce);
-            }
-            Err(e) => {
-                tracing::error!(
-                    "🌉 [Hyperlane] Failed to fetch checkpoint for validator {:#x} and nonce {}: {:?}",
-                    validator,
-                    nonce,
-                    e
-                );
+        // Ignore the nonces not signed yet & the ones already fetched
+        let nonces_to_fetch: Vec<u32> = unsigned_nonces
+            .iter()
+            .copied()
+            .filter(|&nonce| nonce <= latest_index)
+            .filter(|&nonce| !self.storage.signed_checkpoints().validator_signed_nonce(validator, nonce))
+            .collect();
+
+        let results: Vec<_> = futures::stream::iter(nonces_to_fetch)
+            .map(|nonce| {
+                let fetcher = fetcher.clone();
+                async move { (nonce, fetcher.fetch(nonce).await) }
+            })
+            .buffer_unordered(MAX_CONCURRENT_FETCHES_PER_VALIDATOR)
+            .collect()
+            .await;
+
+        let mut first_error = None;
+        for (nonce, result) in results {
+            match result {
+                Ok(Some(checkpoint)) => {
+                    self.store_signed_checkpoint(validator, checkpoint);
+                }
+                Ok(None) => {
+                    tracing::debug!("🌉 [Hyperlane] Validator {:#x} has not yet signed nonce {}", validator, nonce);
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e.context(format!("Fetching checkpoint for nonce {nonce}")));
+                }
             }
         }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(Some(latest_index)),
+        }
     }
 
     /// Store the signed checkpoint for the (validator;nonce) couple.
+    /// The checkpoints that don't match the merkle tree of the indexed messages are rejected, & the ones
+    /// whose message is not indexed yet are kept pending: they are fetched again (from the fetch cache) &
+    /// verified on the next ticks. The checkpoints that can't be verified because the tree is not synced
+    /// or was synced from a more recent snapshot are stored unverified.
     fn store_signed_checkpoint(&self, validator: Felt, checkpoint: SignedCheckpointWithMessageId) {
         let nonce = checkpoint.value.checkpoint.index;
 
@@ -143,23 +262,61 @@ impl HyperlaneService {
             return;
         }
 
+        match self.storage.merkle_tree().verify_checkpoint(&checkpoint.value) {
+            Ok(CheckpointVerification::Verified) => {}
+            Ok(CheckpointVerification::NotYetIndexed) => {
+                tracing::debug!(
+                    "🌉 [Hyperlane] Keeping checkpoint #{} of validator {:#x} pending until its message is indexed",
+                    nonce,
+                    validator
+                );
+                self.metrics.record_unverified_checkpoint(&validator, CheckpointVerification::NotYetIndexed);
+                return;
+            }
+            Ok(verification) => {
+                tracing::debug!(
+                    "🌉 [Hyperlane] Storing checkpoint #{} of validator {:#x} unverified: {}",
+                    nonce,
+                    validator,
+                    verification
+                );
+                self.metrics.record_unverified_checkpoint(&validator, verification);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "🌉 [Hyperlane] ❌ Rejecting checkpoint #{} of validator {:#x}: {}",
+                    nonce,
+                    validator,
+                    e
+                );
+                self.metrics.record_invalid_checkpoint(&validator);
+                return;
+            }
+        }
+
         self.storage.signed_checkpoints().add(validator, nonce, checkpoint);
         tracing::info!("🌉 [Hyperlane] Validator {:#x} signed checkpoint #{}", validator, nonce);
     }
 
-    /// Stores the updates once it has been signed.
+    /// Stores the updates once it has been signed, unless a newer update of the feed is already stored.
     /// Also sends an update to the websocket channel that an update has been stored.
     async fn store_dispatch_updates(&self, nonce: u32) -> anyhow::Result<()> {
-        let event = match self.storage.unsigned_checkpoints().get(nonce).await {
-            Some(e) => e,
-            None => unreachable!(),
+        // The nonce may have expired since it was fully signed, see the retention service
+        let Some(event) = self.storage.unsigned_checkpoints().get(nonce).await else {
+            anyhow::bail!("Nonce #{nonce} is not pending anymore");
         };
 
         for update in event.message.body.updates.iter() {
             let dispatch_update_infos = DispatchUpdateInfos::new(&event, update);
 
-            let feed_id = hex_str_to_u256(&update.feed_id())?;
-            self.storage.latest_update_per_feed().add(feed_id, dispatch_update_infos);
+            let feed_id = update.feed_id().into();
+            if !self.storage.latest_update_per_feed().add(feed_id, dispatch_update_infos) {
+                tracing::debug!(
+                    "🌉 [Hyperlane] Ignoring update of feed {} from nonce #{}: a newer update is already stored",
+                    update.feed_id(),
+                    nonce
+                );
+            }
         }
         Ok(())
     }
@@ -178,3 +335,36 @@ impl HyperlaneService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::{FeedIdsStorage, ValidatorsFetchersStorage};
+    use crate::test_utils::signed_checkpoint;
+    use crate::types::hyperlane::IncrementalMerkleTree;
+
+    use super::*;
+
+    #[test]
+    fn test_store_signed_checkpoint_predating_snapshot() {
+        let storage = Arc::new(TheorosStorage::new(FeedIdsStorage::default(), ValidatorsFetchersStorage::default()));
+        let service = HyperlaneService::new(storage.clone(), OutOfOrderPolicy::default(), &Registry::new()).unwrap();
+        let validator = Felt::ONE;
+
+        // Not verified while the tree is not synced
+        service.store_signed_checkpoint(validator, signed_checkpoint(1));
+        assert!(storage.signed_checkpoints().validator_signed_nonce(validator, 1));
+
+        // Synced from a snapshot of 5 leaves: the older checkpoints can't be verified but are stored
+        let mut tree = IncrementalMerkleTree::default();
+        for index in 0..5 {
+            tree.insert(signed_checkpoint(index).value.message_id.into()).unwrap();
+        }
+        storage.merkle_tree().sync(IncrementalMerkleTree::from_branch(tree.branch(), tree.count()));
+        service.store_signed_checkpoint(validator, signed_checkpoint(2));
+        assert!(storage.signed_checkpoints().validator_signed_nonce(validator, 2));
+
+        // Kept pending until the message is indexed
+        service.store_signed_checkpoint(validator, signed_checkpoint(5));
+        assert!(!storage.signed_checkpoints().validator_signed_nonce(validator, 5));
+    }
+}
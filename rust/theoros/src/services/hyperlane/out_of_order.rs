@@ -0,0 +1,74 @@
+use std::collections::BTreeSet;
+
+/// What to do with the pending nonces older than a nonce fully signed by all validators.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum OutOfOrderPolicy {
+    /// Drops the older nonces. Their updates are lost for the feeds not updated by the newer nonces.
+    Skip,
+    /// Keeps waiting for the older nonces to be signed. Their updates are only stored if no newer
+    /// update of the same feed is stored.
+    #[default]
+    Wait,
+    /// Keeps waiting like [OutOfOrderPolicy::Wait], but reports the older nonces as errors.
+    Alert,
+}
+
+/// Keeps track of the pending nonces already found older than a fully signed nonce,
+/// so each of them is only reported once.
+#[derive(Debug, Default)]
+pub struct OutOfOrderNonces {
+    reported: BTreeSet<u32>,
+}
+
+impl OutOfOrderNonces {
+    /// Returns the pending nonces older than the newest fully signed nonce that were not reported yet.
+    /// The nonces that are not pending anymore are forgotten.
+    pub fn newly_outdated(&mut self, pending_nonces: &[u32], signed_nonces: &[u32]) -> Vec<u32> {
+        self.reported.retain(|nonce| pending_nonces.contains(nonce) && !signed_nonces.contains(nonce));
+
+        let Some(&newest_signed) = signed_nonces.iter().max() else {
+            return vec![];
+        };
+        pending_nonces
+            .iter()
+            .copied()
+            .filter(|nonce| *nonce < newest_signed && !signed_nonces.contains(nonce))
+            .filter(|nonce| self.reported.insert(*nonce))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_out_of_order_policy() {
+        assert_eq!(OutOfOrderPolicy::from_str("skip").unwrap(), OutOfOrderPolicy::Skip);
+        assert_eq!(OutOfOrderPolicy::from_str("alert").unwrap(), OutOfOrderPolicy::Alert);
+        assert_eq!(OutOfOrderPolicy::default().to_string(), "wait");
+        assert!(OutOfOrderPolicy::from_str("ignore").is_err());
+    }
+
+    #[test]
+    fn test_newly_outdated_nonces() {
+        let mut out_of_order = OutOfOrderNonces::default();
+
+        // Nothing signed, or signed in order
+        assert!(out_of_order.newly_outdated(&[1, 2, 3], &[]).is_empty());
+        assert!(out_of_order.newly_outdated(&[1, 2, 3], &[1]).is_empty());
+
+        // Nonce 4 is signed before 2 & 3
+        assert_eq!(out_of_order.newly_outdated(&[2, 3, 4, 5], &[4]), vec![2, 3]);
+        // 2 & 3 are already reported
+        assert_eq!(out_of_order.newly_outdated(&[2, 3, 5, 6], &[6]), vec![5]);
+        assert!(out_of_order.newly_outdated(&[2, 3, 7], &[7]).is_empty());
+
+        // Nonce 3 got signed: only 2 is still reported
+        assert!(out_of_order.newly_outdated(&[2, 3], &[3]).is_empty());
+        assert_eq!(out_of_order.reported, BTreeSet::from([2]));
+    }
+}
@@ -0,0 +1,74 @@
+use prometheus::{IntCounter, IntGauge, Opts, Registry};
+
+/// State of the connection with the event source, as exported by the `theoros_indexer_connection_state` gauge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Disconnected = 0,
+    Connecting = 1,
+    Connected = 2,
+}
+
+/// Prometheus metrics about the connection of the indexer with its event source & the events it skipped.
+#[derive(Clone)]
+pub struct IndexerMetrics {
+    connection_state: IntGauge,
+    connections: IntCounter,
+    connection_errors: IntCounter,
+    last_message_timestamp: IntGauge,
+    skipped_events: IntCounter,
+}
+
+impl IndexerMetrics {
+    pub fn register(registry: &Registry) -> anyhow::Result<Self> {
+        let connection_state = IntGauge::with_opts(Opts::new(
+            "theoros_indexer_connection_state",
+            "State of the connection with the event source: 0 disconnected, 1 connecting, 2 connected",
+        ))?;
+        let connections = IntCounter::with_opts(Opts::new(
+            "theoros_indexer_connections_total",
+            "Number of successful connections to the event source",
+        ))?;
+        let connection_errors = IntCounter::with_opts(Opts::new(
+            "theoros_indexer_connection_errors_total",
+            "Number of failed connections & of connections to the event source interrupted by an error",
+        ))?;
+        let last_message_timestamp = IntGauge::with_opts(Opts::new(
+            "theoros_indexer_last_message_timestamp_seconds",
+            "Unix timestamp of the latest message received from the event source, heartbeats & empty polls included",
+        ))?;
+
+        let skipped_events = IntCounter::with_opts(Opts::new(
+            "theoros_indexer_skipped_events_total",
+            "Number of events skipped because they are malformed or can't be processed",
+        ))?;
+
+        registry.register(Box::new(connection_state.clone()))?;
+        registry.register(Box::new(connections.clone()))?;
+        registry.register(Box::new(connection_errors.clone()))?;
+        registry.register(Box::new(last_message_timestamp.clone()))?;
+        registry.register(Box::new(skipped_events.clone()))?;
+
+        Ok(Self { connection_state, connections, connection_errors, last_message_timestamp, skipped_events })
+    }
+
+    pub fn set_state(&self, state: ConnectionState) {
+        if state == ConnectionState::Connected {
+            self.connections.inc();
+        }
+        self.connection_state.set(state as i64);
+    }
+
+    /// Marks the connection as lost because of an error.
+    pub fn record_error(&self) {
+        self.connection_errors.inc();
+        self.connection_state.set(ConnectionState::Disconnected as i64);
+    }
+
+    pub fn record_message(&self) {
+        self.last_message_timestamp.set(chrono::Utc::now().timestamp());
+    }
+
+    pub fn record_skipped_event(&self) {
+        self.skipped_events.inc();
+    }
+}
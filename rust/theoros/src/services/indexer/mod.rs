@@ -1,184 +1,181 @@
-use std::cmp::max;
+mod metrics;
+pub mod sources;
 
-use anyhow::{anyhow, bail, Context, Result};
-use apibara_core::{
-    node::v1alpha2::DataFinality,
-    starknet::v1alpha2::{Block, Event, FieldElement, Filter, HeaderFilter},
-};
-use apibara_sdk::{configuration, ClientBuilder, Configuration, DataMessage, Uri};
-use futures_util::TryStreamExt;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use prometheus::Registry;
 use starknet::core::types::Felt;
-use starknet::core::utils::get_selector_from_name;
-use tokio::task::JoinSet;
+use starknet::macros::selector;
+use tokio::{sync::mpsc, task::JoinSet};
 
-use pragma_utils::{
-    conversions::apibara::{apibara_field_as_felt, felt_as_apibara_field},
-    services::Service,
-};
+use pragma_feeds::FeedId;
+use pragma_utils::services::Service;
 
-use crate::types::hyperlane::{DispatchEvent, FromStarknetEventData, ValidatorAnnouncementEvent};
+use crate::types::hyperlane::{
+    DispatchEvent, FromStarknetEventData, InsertedIntoTreeEvent, ValidatorAnnouncementEvent,
+};
 use crate::types::state::AppState;
 
-const INDEXING_STREAM_CHUNK_SIZE: usize = 1;
+pub use metrics::{ConnectionState, IndexerMetrics};
+use sources::{ApibaraEventSource, EventSource, EventSourceConfig, EventsFilter, IndexedEvent, StarknetRpcEventSource};
+
+/// Maximum number of batches of events received from the source & not processed yet.
+const EVENTS_CHANNEL_SIZE: usize = 16;
 
 const START_INDEXER_DELTA: u64 = 5;
 
-lazy_static::lazy_static! {
-    // Pragma Dispatcher
-    pub static ref DISPATCH_EVENT_SELECTOR: FieldElement = felt_as_apibara_field(&get_selector_from_name("Dispatch").unwrap());
-    // Hyperlane mailbox
-    pub static ref VALIDATOR_ANNOUNCEMENT_SELECTOR: FieldElement = felt_as_apibara_field(&get_selector_from_name("ValidatorAnnouncement").unwrap());
-    // Pragma Feeds Registry
-    pub static ref NEW_FEED_ID_EVENT_SELECTOR: FieldElement = felt_as_apibara_field(&get_selector_from_name("NewFeedId").unwrap());
-    pub static ref REMOVED_FEED_ID_EVENT_SELECTOR: FieldElement = felt_as_apibara_field(&get_selector_from_name("RemovedFeedId").unwrap());
-}
+// Pragma Dispatcher
+pub const DISPATCH_EVENT_SELECTOR: Felt = selector!("Dispatch");
+// Hyperlane mailbox
+pub const VALIDATOR_ANNOUNCEMENT_SELECTOR: Felt = selector!("ValidatorAnnouncement");
+// Hyperlane merkle tree hook
+pub const INSERTED_INTO_TREE_SELECTOR: Felt = selector!("InsertedIntoTree");
+// Pragma Feeds Registry
+pub const NEW_FEED_ID_EVENT_SELECTOR: Felt = selector!("NewFeedId");
+pub const REMOVED_FEED_ID_EVENT_SELECTOR: Felt = selector!("RemovedFeedId");
 
-#[derive(Clone)]
 pub struct IndexerService {
     state: AppState,
-    uri: Uri,
-    stream_config: Configuration<Filter>,
+    /// Taken when the service starts.
+    source: Option<Box<dyn EventSource>>,
+    metrics: IndexerMetrics,
 }
 
 #[async_trait::async_trait]
 impl Service for IndexerService {
     async fn start(&mut self, join_set: &mut JoinSet<anyhow::Result<()>>) -> anyhow::Result<()> {
-        let service = self.clone();
+        let source = self.source.take().context("Indexer service already started")?;
+        let (events_sender, events_receiver) = mpsc::channel(EVENTS_CHANNEL_SIZE);
+        join_set.spawn(source.run_forever(events_sender));
+
+        let service = Self { state: self.state.clone(), source: None, metrics: self.metrics.clone() };
         join_set.spawn(async move {
             tracing::info!("🧩 Indexer service started");
-            service.run_forever().await?;
-            Ok(())
+            service.run_forever(events_receiver).await
         });
         Ok(())
     }
 }
 
 impl IndexerService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         state: AppState,
-        apibara_uri: Uri,
+        source_config: EventSourceConfig,
         hyperlane_mailbox_address: Felt,
+        hyperlane_merkle_tree_hook_address: Felt,
         hyperlane_validator_announce_address: Felt,
         pragma_feeds_registry_address: Felt,
         current_block: u64,
+        registry: &Registry,
     ) -> Result<Self> {
-        let stream_config = Configuration::<Filter>::default()
-            .with_starting_block(max(0, current_block.saturating_sub(START_INDEXER_DELTA)))
-            .with_filter(|mut filter| {
-                filter
-                    .with_header(HeaderFilter::weak())
-                    .add_event(|event| {
-                        event
-                            .with_from_address(felt_as_apibara_field(&hyperlane_mailbox_address))
-                            .with_keys(vec![DISPATCH_EVENT_SELECTOR.clone()])
-                    })
-                    .add_event(|event| {
-                        event
-                            .with_from_address(felt_as_apibara_field(&hyperlane_validator_announce_address))
-                            .with_keys(vec![VALIDATOR_ANNOUNCEMENT_SELECTOR.clone()])
-                    })
-                    .add_event(|event| {
-                        event
-                            .with_from_address(felt_as_apibara_field(&pragma_feeds_registry_address))
-                            .with_keys(vec![NEW_FEED_ID_EVENT_SELECTOR.clone(), REMOVED_FEED_ID_EVENT_SELECTOR.clone()])
-                    })
-                    .build()
-            })
-            .with_finality(DataFinality::DataStatusPending);
-
-        let indexer_service = Self { state, uri: apibara_uri, stream_config };
-        Ok(indexer_service)
-    }
-
-    /// Runs the indexer forever.
-    pub async fn run_forever(mut self) -> Result<()> {
-        let (config_client, config_stream) = configuration::channel(INDEXING_STREAM_CHUNK_SIZE);
-
-        config_client.send(self.stream_config.clone()).await.context("Sending indexing stream configuration")?;
-
-        let mut stream = ClientBuilder::default()
-            .connect(self.uri.clone())
-            .await
-            .map_err(|e| anyhow!("Error while connecting to Apibara DNA: {}", e))?
-            .start_stream::<Filter, Block, _>(config_stream)
-            .await
-            .map_err(|e| anyhow!("Error while starting indexing stream: {}", e))?;
+        let filters = [
+            EventsFilter { address: hyperlane_mailbox_address, selectors: vec![DISPATCH_EVENT_SELECTOR] },
+            EventsFilter { address: hyperlane_merkle_tree_hook_address, selectors: vec![INSERTED_INTO_TREE_SELECTOR] },
+            EventsFilter {
+                address: hyperlane_validator_announce_address,
+                selectors: vec![VALIDATOR_ANNOUNCEMENT_SELECTOR],
+            },
+            EventsFilter {
+                address: pragma_feeds_registry_address,
+                selectors: vec![NEW_FEED_ID_EVENT_SELECTOR, REMOVED_FEED_ID_EVENT_SELECTOR],
+            },
+        ];
+        let starting_block = current_block.saturating_sub(START_INDEXER_DELTA);
+        let metrics = IndexerMetrics::register(registry)?;
 
-        loop {
-            match stream.try_next().await {
-                Ok(Some(response)) => {
-                    self.process_batch(response).await?;
-                }
-                Ok(None) => continue,
-                Err(e) => bail!("Error while streaming indexed batch: {}", e),
+        let source: Box<dyn EventSource> = match source_config {
+            EventSourceConfig::Apibara(client_config) => {
+                Box::new(ApibaraEventSource::new(client_config, &filters, starting_block, metrics.clone()))
             }
-        }
+            EventSourceConfig::StarknetRpc { poll_interval } => Box::new(StarknetRpcEventSource::new(
+                Arc::clone(&state.starknet_rpc),
+                &filters,
+                starting_block,
+                poll_interval,
+                metrics.clone(),
+            )),
+        };
+
+        Ok(Self { state, source: Some(source), metrics })
     }
 
-    /// Process a batch of blocks indexed by Apibara DNA
-    async fn process_batch(&mut self, batch: DataMessage<Block>) -> Result<()> {
-        match batch {
-            DataMessage::Data { cursor: _, end_cursor: _, finality: _, batch } => {
-                for block in batch {
-                    for event in block.clone().events.into_iter().filter_map(|e| e.event) {
-                        if event.from_address.is_none() {
-                            continue;
-                        }
-                        self.process_event(event, &block).await?;
-                    }
+    /// Processes the events received from the source, forever.
+    /// The events that can't be processed are skipped, so a malformed event can't stop the indexer.
+    async fn run_forever(self, mut events_receiver: mpsc::Receiver<Vec<IndexedEvent>>) -> Result<()> {
+        while let Some(events) = events_receiver.recv().await {
+            for event in events {
+                let (from_address, block_number) = (event.from_address, event.block_number);
+                if let Err(e) = self.process_event(event).await {
+                    tracing::warn!(
+                        "📨 [Indexer] Skipping an event of {:#x} from block {:?} that can't be processed: {:?}",
+                        from_address,
+                        block_number,
+                        e
+                    );
+                    self.metrics.record_skipped_event();
                 }
             }
-            DataMessage::Invalidate { cursor } => match cursor {
-                Some(c) => bail!("Indexed an invalidate request data at {}", &c.order_key),
-                None => bail!("Invalidate request without cursor provided"),
-            },
-            DataMessage::Heartbeat => {}
         }
-        Ok(())
+        bail!("Event source stopped")
     }
 
-    /// Decodes a starknet [Event].
-    async fn process_event(&self, event: Event, block: &Block) -> Result<()> {
+    /// Decodes an [IndexedEvent].
+    async fn process_event(&self, event: IndexedEvent) -> Result<()> {
         let event_selector = event.keys.first().context("No event selector")?;
-        let event_data: Vec<Felt> = event.data.iter().map(apibara_field_as_felt).collect();
+        let event_data = event.data;
         match event_selector {
-            selector if selector == &*DISPATCH_EVENT_SELECTOR => {
-                self.decode_dispatch_event(event_data, block).await?;
+            selector if selector == &DISPATCH_EVENT_SELECTOR => {
+                self.decode_dispatch_event(event_data, event.block_number).await?;
             }
-            selector if selector == &*VALIDATOR_ANNOUNCEMENT_SELECTOR => {
+            selector if selector == &INSERTED_INTO_TREE_SELECTOR => {
+                self.decode_inserted_into_tree_event(event_data)?;
+            }
+            selector if selector == &VALIDATOR_ANNOUNCEMENT_SELECTOR => {
                 self.decode_validator_announce_event(event_data).await?;
             }
-            selector if selector == &*NEW_FEED_ID_EVENT_SELECTOR => {
-                self.decode_new_feed_id_event(event_data);
+            selector if selector == &NEW_FEED_ID_EVENT_SELECTOR => {
+                self.decode_new_feed_id_event(event_data)?;
+            }
+            selector if selector == &REMOVED_FEED_ID_EVENT_SELECTOR => {
+                self.decode_removed_feed_id_event(event_data)?;
             }
-            selector if selector == &*REMOVED_FEED_ID_EVENT_SELECTOR => {
-                self.decode_removed_feed_id_event(event_data);
+            selector => {
+                tracing::debug!("📨 [Indexer] Ignoring an event with the unknown selector {:#x}", selector);
             }
-            _ => unreachable!(),
         }
         Ok(())
     }
 
     /// Decodes a DispatchEvent from the Starknet event data.
-    async fn decode_dispatch_event(&self, event_data: Vec<Felt>, block: &Block) -> anyhow::Result<()> {
+    async fn decode_dispatch_event(&self, event_data: Vec<Felt>, block_number: Option<u64>) -> anyhow::Result<()> {
         let dispatch_event = DispatchEvent::from_starknet_event_data(event_data).context("Parsing DispatchEvent")?;
         let nonce = dispatch_event.message.header.nonce;
-        match &block.header {
-            Some(h) => {
-                tracing::info!(
-                    "📨 [Indexer] [Block {}] Indexed a Dispatch event with nonce #{}",
-                    h.block_number,
-                    nonce,
-                );
+        match block_number {
+            Some(block_number) => {
+                tracing::info!("📨 [Indexer] [Block {}] Indexed a Dispatch event with nonce #{}", block_number, nonce);
             }
             None => {
                 tracing::info!("📨 [Indexer] Indexed a Dispatch event with nonce #{}", nonce);
             }
         };
+        self.state.storage.validators_lag().set_latest_dispatched_nonce(nonce);
         self.state.storage.unsigned_checkpoints().add(nonce, &dispatch_event).await;
         Ok(())
     }
 
+    /// Decodes an InsertedIntoTreeEvent from the Starknet event data & inserts the message id
+    /// in the merkle tree.
+    fn decode_inserted_into_tree_event(&self, event_data: Vec<Felt>) -> anyhow::Result<()> {
+        let event = InsertedIntoTreeEvent::from_starknet_event_data(event_data).context("Parsing InsertedIntoTree")?;
+        tracing::debug!("📨 [Indexer] Indexed an InsertedIntoTree event with index #{}", event.index);
+        if let Err(e) = self.state.storage.merkle_tree().insert(event.message_id, event.index) {
+            tracing::warn!("📨 [Indexer] 🌳 Merkle tree out of sync, it will be synced again: {}", e);
+        }
+        Ok(())
+    }
+
     /// Decodes a ValidatorAnnouncementEvent from the Starknet event data.
     async fn decode_validator_announce_event(&self, event_data: Vec<Felt>) -> anyhow::Result<()> {
         tracing::info!("📨 [Indexer] Indexed a ValidatorAnnouncement event");
@@ -190,16 +187,25 @@ impl IndexerService {
     }
 
     /// Decodes a NewFeedId event from the Starknet event data.
-    fn decode_new_feed_id_event(&self, event_data: Vec<Felt>) {
-        let feed_id = event_data[1].to_hex_string();
+    fn decode_new_feed_id_event(&self, event_data: Vec<Felt>) -> anyhow::Result<()> {
+        let feed_id = event_data.get(1).context("No feed id in NewFeedId")?;
+        let feed_id = match FeedId::try_from(*feed_id) {
+            Ok(feed_id) => feed_id.to_string(),
+            Err(e) => {
+                tracing::warn!("📨 [Indexer] Ignoring NewFeedId event with an invalid feed id: {}", e);
+                return Ok(());
+            }
+        };
         tracing::info!("📨 [Indexer] Indexed a NewFeedId event for: {}", feed_id);
         self.state.storage.feed_ids().add(feed_id);
+        Ok(())
     }
 
     /// Decodes a RemovedFeedId event from the Starknet event data.
-    fn decode_removed_feed_id_event(&self, event_data: Vec<Felt>) {
-        let feed_id = event_data[1].to_hex_string();
+    fn decode_removed_feed_id_event(&self, event_data: Vec<Felt>) -> anyhow::Result<()> {
+        let feed_id = event_data.get(1).context("No feed id in RemovedFeedId")?.to_hex_string();
         tracing::info!("📨 [Indexer] Indexed a RemovedFeedId event for: {}", feed_id);
         self.state.storage.feed_ids().remove(&feed_id);
+        Ok(())
     }
 }
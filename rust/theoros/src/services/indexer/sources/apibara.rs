@@ -0,0 +1,194 @@
+use std::{fmt, time::Duration};
+
+use anyhow::{anyhow, bail, Context, Result};
+use apibara_core::{
+    node::v1alpha2::DataFinality,
+    starknet::v1alpha2::{Block, Filter, HeaderFilter},
+};
+use apibara_sdk::{configuration, ClientBuilder, Configuration, DataMessage, Uri};
+use futures_util::TryStreamExt;
+use tokio::sync::mpsc;
+
+use pragma_utils::conversions::apibara::{apibara_field_as_felt, felt_as_apibara_field};
+
+use super::{EventSource, EventsFilter, IndexedEvent};
+use crate::services::indexer::{ConnectionState, IndexerMetrics};
+
+const INDEXING_STREAM_CHUNK_SIZE: usize = 1;
+
+/// Settings of the connection with Apibara DNA.
+#[derive(Clone)]
+pub struct ApibaraClientConfig {
+    pub uri: Uri,
+    /// Key sent as bearer token to authenticate with Apibara DNA.
+    pub api_key: Option<String>,
+    /// Maximum duration to establish the connection.
+    pub connect_timeout: Duration,
+    /// Maximum duration without receiving any message from the stream, heartbeats included.
+    pub stream_timeout: Duration,
+    /// Maximum size of a message received from the stream, in bytes.
+    pub max_message_size_bytes: usize,
+}
+
+impl ApibaraClientConfig {
+    /// The connection is encrypted with TLS for the `https` URIs.
+    pub fn uses_tls(&self) -> bool {
+        self.uri.scheme_str() == Some("https")
+    }
+}
+
+// Never log the API key
+impl fmt::Debug for ApibaraClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApibaraClientConfig")
+            .field("uri", &self.uri)
+            .field("tls", &self.uses_tls())
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("connect_timeout", &self.connect_timeout)
+            .field("stream_timeout", &self.stream_timeout)
+            .field("max_message_size_bytes", &self.max_message_size_bytes)
+            .finish()
+    }
+}
+
+/// Streams the events from an Apibara DNA server, including the pending blocks.
+pub struct ApibaraEventSource {
+    client_config: ApibaraClientConfig,
+    stream_config: Configuration<Filter>,
+    metrics: IndexerMetrics,
+}
+
+impl ApibaraEventSource {
+    pub fn new(
+        client_config: ApibaraClientConfig,
+        filters: &[EventsFilter],
+        starting_block: u64,
+        metrics: IndexerMetrics,
+    ) -> Self {
+        let stream_config = Configuration::<Filter>::default()
+            .with_starting_block(starting_block)
+            .with_filter(|mut filter| {
+                filter.with_header(HeaderFilter::weak());
+                for events_filter in filters {
+                    for selector in &events_filter.selectors {
+                        filter.add_event(|event| {
+                            event
+                                .with_from_address(felt_as_apibara_field(&events_filter.address))
+                                .with_keys(vec![felt_as_apibara_field(selector)])
+                        });
+                    }
+                }
+                filter.build()
+            })
+            .with_finality(DataFinality::DataStatusPending);
+
+        Self { client_config, stream_config, metrics }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSource for ApibaraEventSource {
+    async fn run_forever(self: Box<Self>, events_sender: mpsc::Sender<Vec<IndexedEvent>>) -> Result<()> {
+        let (config_client, config_stream) = configuration::channel(INDEXING_STREAM_CHUNK_SIZE);
+
+        config_client.send(self.stream_config.clone()).await.context("Sending indexing stream configuration")?;
+
+        let client_config = &self.client_config;
+        tracing::info!("📨 [Indexer] Connecting to Apibara DNA with {:?}", client_config);
+        self.metrics.set_state(ConnectionState::Connecting);
+        let stream = async {
+            let client = ClientBuilder::default()
+                .with_bearer_token(client_config.api_key.clone())
+                .with_max_message_size(client_config.max_message_size_bytes)
+                .with_timeout(client_config.stream_timeout)
+                .connect(client_config.uri.clone());
+            tokio::time::timeout(client_config.connect_timeout, client)
+                .await
+                .map_err(|_| {
+                    anyhow!("Timed out after {:?} while connecting to Apibara DNA", client_config.connect_timeout)
+                })?
+                .map_err(|e| anyhow!("Error while connecting to Apibara DNA: {}", e))?
+                .start_stream::<Filter, Block, _>(config_stream)
+                .await
+                .map_err(|e| anyhow!("Error while starting indexing stream: {}", e))
+        };
+        let mut stream = match stream.await {
+            Ok(stream) => stream,
+            Err(e) => {
+                self.metrics.record_error();
+                return Err(e);
+            }
+        };
+        self.metrics.set_state(ConnectionState::Connected);
+        tracing::info!("📨 [Indexer] Connected to Apibara DNA");
+
+        loop {
+            match stream.try_next().await {
+                Ok(Some(response)) => {
+                    self.metrics.record_message();
+                    if let Some(events) = indexed_events(response)?.filter(|events| !events.is_empty()) {
+                        events_sender.send(events).await.context("Indexer stopped")?;
+                    }
+                }
+                Ok(None) => continue,
+                Err(e) => {
+                    self.metrics.record_error();
+                    bail!("Error while streaming indexed batch: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Extracts the events of a batch of blocks indexed by Apibara DNA.
+/// Returns [None] for the heartbeats.
+fn indexed_events(batch: DataMessage<Block>) -> Result<Option<Vec<IndexedEvent>>> {
+    match batch {
+        DataMessage::Data { cursor: _, end_cursor: _, finality: _, batch } => {
+            let mut events = Vec::new();
+            for block in batch {
+                let block_number = block.header.as_ref().map(|header| header.block_number);
+                for event in block.events.into_iter().filter_map(|e| e.event) {
+                    let Some(from_address) = event.from_address.as_ref() else {
+                        continue;
+                    };
+                    events.push(IndexedEvent {
+                        block_number,
+                        from_address: apibara_field_as_felt(from_address),
+                        keys: event.keys.iter().map(apibara_field_as_felt).collect(),
+                        data: event.data.iter().map(apibara_field_as_felt).collect(),
+                    });
+                }
+            }
+            Ok(Some(events))
+        }
+        DataMessage::Invalidate { cursor } => match cursor {
+            Some(c) => bail!("Indexed an invalidate request data at {}", &c.order_key),
+            None => bail!("Invalidate request without cursor provided"),
+        },
+        DataMessage::Heartbeat => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_client_config_debug_redacts_api_key() {
+        let config = ApibaraClientConfig {
+            uri: Uri::from_str("https://mainnet.starknet.a5a.ch").unwrap(),
+            api_key: Some("dna_secret".into()),
+            connect_timeout: Duration::from_secs(10),
+            stream_timeout: Duration::from_secs(45),
+            max_message_size_bytes: 1024,
+        };
+        assert!(config.uses_tls());
+
+        let logged = format!("{:?}", config);
+        assert!(!logged.contains("dna_secret"));
+        assert!(logged.contains("<redacted>"));
+    }
+}
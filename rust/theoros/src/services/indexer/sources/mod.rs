@@ -0,0 +1,56 @@
+mod apibara;
+mod starknet_rpc;
+
+pub use apibara::{ApibaraClientConfig, ApibaraEventSource};
+pub use starknet_rpc::StarknetRpcEventSource;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use starknet::core::types::Felt;
+use tokio::sync::mpsc;
+
+/// Where the indexer reads the events of the indexed contracts from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum EventSourceKind {
+    /// Streams the events from an Apibara DNA server.
+    #[default]
+    Apibara,
+    /// Polls the events with `starknet_getEvents`, e.g to run against a local devnet.
+    StarknetRpc,
+}
+
+/// Settings of the event source used by the indexer.
+#[derive(Debug, Clone)]
+pub enum EventSourceConfig {
+    Apibara(ApibaraClientConfig),
+    StarknetRpc {
+        /// Duration between two polls when no new block was produced.
+        poll_interval: Duration,
+    },
+}
+
+/// Events of a contract to index, selected by their first key.
+#[derive(Debug, Clone)]
+pub struct EventsFilter {
+    pub address: Felt,
+    pub selectors: Vec<Felt>,
+}
+
+/// An event emitted by one of the indexed contracts.
+#[derive(Debug, Clone)]
+pub struct IndexedEvent {
+    pub block_number: Option<u64>,
+    pub from_address: Felt,
+    pub keys: Vec<Felt>,
+    pub data: Vec<Felt>,
+}
+
+/// Source of the events consumed by the indexer.
+#[async_trait::async_trait]
+pub trait EventSource: Send {
+    /// Sends the events matching the filters of the source to the indexer, forever.
+    /// The events are sent in batches, in the order they were emitted.
+    async fn run_forever(self: Box<Self>, events_sender: mpsc::Sender<Vec<IndexedEvent>>) -> Result<()>;
+}
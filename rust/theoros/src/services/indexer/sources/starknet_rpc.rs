@@ -0,0 +1,270 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use starknet::core::types::Felt;
+use tokio::sync::mpsc;
+
+use super::{EventSource, EventsFilter, IndexedEvent};
+use crate::{
+    rpc::starknet::StarknetRpc,
+    services::indexer::{ConnectionState, IndexerMetrics},
+};
+
+/// Delay before polling again after a first failed poll.
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Maximum delay between two polls while the RPC is failing.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// The RPC methods used to poll the events, so the RPC can be mocked in the tests.
+#[async_trait::async_trait]
+trait EventsProvider: Send + Sync {
+    async fn block_number(&self) -> Result<u64>;
+
+    /// Retrieves the events emitted by the contract between the two blocks (included), in order.
+    async fn get_events(
+        &self,
+        address: Felt,
+        selectors: &[Felt],
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<IndexedEvent>>;
+}
+
+#[async_trait::async_trait]
+impl EventsProvider for StarknetRpc {
+    async fn block_number(&self) -> Result<u64> {
+        StarknetRpc::block_number(self).await
+    }
+
+    async fn get_events(
+        &self,
+        address: Felt,
+        selectors: &[Felt],
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<IndexedEvent>> {
+        let events = StarknetRpc::get_events(self, address, selectors, from_block, to_block).await?;
+        Ok(events
+            .into_iter()
+            .map(|event| IndexedEvent {
+                block_number: event.block_number,
+                from_address: event.from_address,
+                keys: event.keys,
+                data: event.data,
+            })
+            .collect())
+    }
+}
+
+/// Polls the events of the accepted blocks with `starknet_getEvents`.
+///
+/// Unlike Apibara, the pending blocks are not indexed. The events of a batch are sorted by block,
+/// but the order of the events of different contracts emitted in the same block is not kept.
+/// When the RPC fails, the poll is retried with an exponential backoff from the same block.
+pub struct StarknetRpcEventSource {
+    provider: Arc<dyn EventsProvider>,
+    filters: Vec<EventsFilter>,
+    /// First block not polled yet.
+    next_block: u64,
+    poll_interval: Duration,
+    metrics: IndexerMetrics,
+}
+
+impl StarknetRpcEventSource {
+    pub fn new(
+        starknet_rpc: Arc<StarknetRpc>,
+        filters: &[EventsFilter],
+        starting_block: u64,
+        poll_interval: Duration,
+        metrics: IndexerMetrics,
+    ) -> Self {
+        Self { provider: starknet_rpc, filters: filters.to_vec(), next_block: starting_block, poll_interval, metrics }
+    }
+
+    /// Retrieves the events emitted up to the latest block, if any new block was produced.
+    async fn poll(&mut self) -> Result<Option<Vec<IndexedEvent>>> {
+        let latest_block = self.provider.block_number().await?;
+        if latest_block < self.next_block {
+            return Ok(None);
+        }
+
+        let mut events = Vec::new();
+        for filter in &self.filters {
+            let emitted_events = self
+                .provider
+                .get_events(filter.address, &filter.selectors, self.next_block, latest_block)
+                .await
+                .with_context(|| format!("Fetching the events of {:#x}", filter.address))?;
+            events.extend(emitted_events);
+        }
+        // Stable, so the events of each contract stay in order
+        events.sort_by_key(|event| event.block_number);
+
+        self.next_block = latest_block + 1;
+        Ok(Some(events))
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSource for StarknetRpcEventSource {
+    async fn run_forever(mut self: Box<Self>, events_sender: mpsc::Sender<Vec<IndexedEvent>>) -> Result<()> {
+        tracing::info!(
+            "📨 [Indexer] Polling the events with starknet_getEvents every {:?}, from block #{}",
+            self.poll_interval,
+            self.next_block
+        );
+        self.metrics.set_state(ConnectionState::Connecting);
+
+        let mut connected = false;
+        let mut consecutive_failures = 0;
+        loop {
+            match self.poll().await {
+                Ok(events) => {
+                    consecutive_failures = 0;
+                    if !connected {
+                        self.metrics.set_state(ConnectionState::Connected);
+                        connected = true;
+                    }
+                    self.metrics.record_message();
+                    match events {
+                        Some(events) if !events.is_empty() => {
+                            events_sender.send(events).await.context("Indexer stopped")?;
+                        }
+                        Some(_) => {}
+                        None => tokio::time::sleep(self.poll_interval).await,
+                    }
+                }
+                Err(e) => {
+                    self.metrics.record_error();
+                    connected = false;
+                    consecutive_failures += 1;
+                    let delay = retry_delay(consecutive_failures);
+                    tracing::warn!(
+                        "📨 [Indexer] Error while polling the events from block #{}, retrying in {:?}: {:?}",
+                        self.next_block,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+/// Exponential backoff: [BASE_RETRY_DELAY] doubled for each consecutive failure, capped at [MAX_RETRY_DELAY].
+fn retry_delay(consecutive_failures: u32) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    BASE_RETRY_DELAY.saturating_mul(1 << exponent).min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use anyhow::bail;
+    use prometheus::Registry;
+
+    use super::*;
+
+    /// RPC serving the events of a list of blocks, recording the requested ranges.
+    #[derive(Default)]
+    struct MockProvider {
+        latest_block: Mutex<Option<u64>>,
+        events: Vec<IndexedEvent>,
+        requests: Mutex<Vec<(Felt, u64, u64)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventsProvider for MockProvider {
+        async fn block_number(&self) -> Result<u64> {
+            match *self.latest_block.lock().unwrap() {
+                Some(block) => Ok(block),
+                None => bail!("RPC unavailable"),
+            }
+        }
+
+        async fn get_events(
+            &self,
+            address: Felt,
+            _selectors: &[Felt],
+            from_block: u64,
+            to_block: u64,
+        ) -> Result<Vec<IndexedEvent>> {
+            self.requests.lock().unwrap().push((address, from_block, to_block));
+            Ok(self
+                .events
+                .iter()
+                .filter(|event| event.from_address == address)
+                .filter(|event| event.block_number.is_some_and(|block| (from_block..=to_block).contains(&block)))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn event(from_address: u64, block_number: u64, index: u64) -> IndexedEvent {
+        IndexedEvent {
+            block_number: Some(block_number),
+            from_address: Felt::from(from_address),
+            keys: vec![],
+            data: vec![Felt::from(index)],
+        }
+    }
+
+    fn event_source(provider: Arc<MockProvider>, starting_block: u64) -> StarknetRpcEventSource {
+        let filters = [1u64, 2].map(|address| EventsFilter { address: Felt::from(address), selectors: vec![] });
+        StarknetRpcEventSource {
+            provider,
+            filters: filters.to_vec(),
+            next_block: starting_block,
+            poll_interval: Duration::from_secs(1),
+            metrics: IndexerMetrics::register(&Registry::new()).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_range_and_ordering() {
+        let provider = Arc::new(MockProvider {
+            latest_block: Mutex::new(Some(4)),
+            events: vec![
+                event(1, 4, 0),
+                event(1, 5, 1),
+                event(1, 7, 2),
+                event(2, 5, 3),
+                event(2, 6, 4),
+                event(1, 8, 5),
+            ],
+            ..Default::default()
+        });
+        let mut source = event_source(provider.clone(), 5);
+
+        // No new block
+        assert!(source.poll().await.unwrap().is_none());
+        assert!(provider.requests.lock().unwrap().is_empty());
+
+        *provider.latest_block.lock().unwrap() = Some(7);
+        let events = source.poll().await.unwrap().unwrap();
+        let indexes: Vec<Felt> = events.iter().map(|event| event.data[0]).collect();
+        assert_eq!(indexes, [1u64, 3, 4, 2].map(Felt::from));
+        assert_eq!(*provider.requests.lock().unwrap(), [(Felt::from(1), 5, 7), (Felt::from(2), 5, 7)]);
+        assert_eq!(source.next_block, 8);
+
+        // A failed poll is retried from the same block
+        *provider.latest_block.lock().unwrap() = None;
+        assert!(source.poll().await.is_err());
+        assert_eq!(source.next_block, 8);
+
+        *provider.latest_block.lock().unwrap() = Some(8);
+        let events = source.poll().await.unwrap().unwrap();
+        assert_eq!(events.iter().map(|event| event.data[0]).collect::<Vec<_>>(), [Felt::from(5)]);
+        assert_eq!(source.next_block, 9);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(3), Duration::from_secs(4));
+        assert_eq!(retry_delay(7), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(u32::MAX), MAX_RETRY_DELAY);
+    }
+}
@@ -0,0 +1,44 @@
+use prometheus::{IntCounter, IntGauge, Opts, Registry};
+
+/// Prometheus metrics about the merkle tree tracked locally.
+#[derive(Clone)]
+pub struct MerkleTreeMetrics {
+    leaves: IntGauge,
+    syncs: IntCounter,
+    root_mismatches: IntCounter,
+}
+
+impl MerkleTreeMetrics {
+    pub fn register(registry: &Registry) -> anyhow::Result<Self> {
+        let leaves = IntGauge::with_opts(Opts::new(
+            "theoros_merkle_tree_leaves",
+            "Number of message ids in the merkle tree tracked locally, 0 when not synced",
+        ))?;
+        let syncs = IntCounter::with_opts(Opts::new(
+            "theoros_merkle_tree_syncs_total",
+            "Number of times the merkle tree was synced with the on-chain tree",
+        ))?;
+        let root_mismatches = IntCounter::with_opts(Opts::new(
+            "theoros_merkle_tree_root_mismatches_total",
+            "Number of times the local merkle tree root did not match the on-chain latest checkpoint",
+        ))?;
+
+        registry.register(Box::new(leaves.clone()))?;
+        registry.register(Box::new(syncs.clone()))?;
+        registry.register(Box::new(root_mismatches.clone()))?;
+
+        Ok(Self { leaves, syncs, root_mismatches })
+    }
+
+    pub fn update_leaves(&self, count: Option<u32>) {
+        self.leaves.set(count.map_or(0, i64::from));
+    }
+
+    pub fn record_sync(&self) {
+        self.syncs.inc();
+    }
+
+    pub fn record_root_mismatch(&self) {
+        self.root_mismatches.inc();
+    }
+}
@@ -0,0 +1,132 @@
+mod metrics;
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context;
+use prometheus::Registry;
+use starknet::core::types::Felt;
+use tokio::task::JoinSet;
+
+use pragma_utils::services::Service;
+
+use crate::rpc::starknet::{HyperlaneCalls, StarknetRpc};
+use crate::storage::TheorosStorage;
+
+use metrics::MerkleTreeMetrics;
+
+/// Keeps the merkle tree built from the indexed message ids consistent with the Hyperlane
+/// `MerkleTreeHook` contract.
+///
+/// The tree is first synced with the on-chain tree, since the indexer does not start from the
+/// first message. Then, every reconciliation interval, the root of the tree is compared with the
+/// on-chain latest checkpoint & the tree is synced again if they don't match.
+#[derive(Clone)]
+pub struct MerkleTreeService {
+    starknet_rpc: Arc<StarknetRpc>,
+    storage: Arc<TheorosStorage>,
+    merkle_tree_hook_address: Felt,
+    reconcile_interval: Duration,
+    metrics: MerkleTreeMetrics,
+}
+
+#[async_trait::async_trait]
+impl Service for MerkleTreeService {
+    async fn start(&mut self, join_set: &mut JoinSet<anyhow::Result<()>>) -> anyhow::Result<()> {
+        let service = self.clone();
+        join_set.spawn(async move {
+            tracing::info!("🌳 Merkle tree service started");
+            service.run_forever().await;
+            Ok(())
+        });
+        Ok(())
+    }
+}
+
+impl MerkleTreeService {
+    pub fn new(
+        starknet_rpc: Arc<StarknetRpc>,
+        storage: Arc<TheorosStorage>,
+        merkle_tree_hook_address: Felt,
+        reconcile_interval: Duration,
+        metrics_registry: &Registry,
+    ) -> anyhow::Result<Self> {
+        let metrics = MerkleTreeMetrics::register(metrics_registry)?;
+        Ok(Self { starknet_rpc, storage, merkle_tree_hook_address, reconcile_interval, metrics })
+    }
+
+    pub async fn run_forever(&self) {
+        let mut interval = tokio::time::interval(self.reconcile_interval);
+        // Number of leaves of the local tree the last time it was behind the on-chain tree
+        let mut behind_at = None;
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.reconcile(&mut behind_at).await {
+                tracing::error!("🌳 [MerkleTree] Failed to reconcile the merkle tree: {:?}", e);
+            }
+            self.metrics.update_leaves(self.storage.merkle_tree().count());
+        }
+    }
+
+    /// Syncs the tree if needed, or compares its root with the on-chain latest checkpoint.
+    /// If the local tree is still behind the on-chain tree since the previous reconciliation
+    /// without any new message indexed, a message was missed & the tree is synced again.
+    async fn reconcile(&self, behind_at: &mut Option<u32>) -> anyhow::Result<()> {
+        let merkle_tree = self.storage.merkle_tree();
+        let Some(count) = merkle_tree.count() else {
+            *behind_at = None;
+            return self.sync().await;
+        };
+
+        let (root, index) = self
+            .starknet_rpc
+            .get_latest_checkpoint(&self.merkle_tree_hook_address)
+            .await
+            .context("Fetching the latest checkpoint")?;
+
+        if index >= count {
+            if *behind_at == Some(count) {
+                tracing::warn!("🌳 [MerkleTree] Still missing messages up to #{}, syncing again", index);
+                *behind_at = None;
+                return self.sync().await;
+            }
+            tracing::debug!("🌳 [MerkleTree] Messages up to #{} not indexed yet", index);
+            *behind_at = Some(count);
+            return Ok(());
+        }
+        *behind_at = None;
+
+        match index.checked_add(1).and_then(|count| merkle_tree.root_at(count)) {
+            Some(local_root) if local_root != root => {
+                tracing::error!(
+                    "🌳 [MerkleTree] 🚨 Root of checkpoint #{} is {} on-chain but {} locally, syncing again",
+                    index,
+                    root,
+                    local_root
+                );
+                self.metrics.record_root_mismatch();
+                self.sync().await
+            }
+            Some(_) => {
+                tracing::debug!("🌳 [MerkleTree] Root of checkpoint #{} matches the on-chain root", index);
+                Ok(())
+            }
+            None => {
+                tracing::debug!("🌳 [MerkleTree] Checkpoint #{} is older than the local tree", index);
+                Ok(())
+            }
+        }
+    }
+
+    /// Replaces the local tree with the on-chain tree.
+    async fn sync(&self) -> anyhow::Result<()> {
+        let tree = self
+            .starknet_rpc
+            .get_merkle_tree(&self.merkle_tree_hook_address)
+            .await
+            .context("Fetching the merkle tree")?;
+        tracing::info!("🌳 [MerkleTree] Synced with the on-chain tree of {} messages", tree.count());
+        self.storage.merkle_tree().sync(tree);
+        self.metrics.record_sync();
+        Ok(())
+    }
+}
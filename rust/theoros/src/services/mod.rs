@@ -1,9 +1,18 @@
 pub mod api;
+pub mod grpc;
 pub mod hyperlane;
 pub mod indexer;
+pub mod merkle_tree;
 pub mod metrics;
+pub mod retention;
 
 pub use api::ApiService;
-pub use hyperlane::HyperlaneService;
-pub use indexer::IndexerService;
+pub use grpc::GrpcService;
+pub use hyperlane::{HyperlaneService, OutOfOrderPolicy};
+pub use indexer::{
+    sources::{ApibaraClientConfig, EventSourceConfig, EventSourceKind},
+    IndexerService,
+};
+pub use merkle_tree::MerkleTreeService;
 pub use metrics::MetricsService;
+pub use retention::{RetentionPolicy, RetentionService};
@@ -0,0 +1,50 @@
+use prometheus::{IntCounter, IntGaugeVec, Opts, Registry};
+
+/// Prometheus metrics about the checkpoints retention & the size of the stores.
+#[derive(Clone)]
+pub struct RetentionMetrics {
+    signed_checkpoints_pruned: IntCounter,
+    unsigned_checkpoints_expired: IntCounter,
+    storage_entries: IntGaugeVec,
+    storage_size: IntGaugeVec,
+}
+
+impl RetentionMetrics {
+    pub fn register(registry: &Registry) -> anyhow::Result<Self> {
+        let signed_checkpoints_pruned = IntCounter::with_opts(Opts::new(
+            "theoros_signed_checkpoints_pruned_total",
+            "Number of signed checkpoints removed by the retention policy",
+        ))?;
+        let unsigned_checkpoints_expired = IntCounter::with_opts(Opts::new(
+            "theoros_unsigned_checkpoints_expired_total",
+            "Number of dispatched nonces dropped before being signed by all validators",
+        ))?;
+        let storage_entries =
+            IntGaugeVec::new(Opts::new("theoros_storage_entries", "Number of entries of each store"), &["store"])?;
+        let storage_size = IntGaugeVec::new(
+            Opts::new("theoros_storage_approximate_size_bytes", "Approximate memory used by each store"),
+            &["store"],
+        )?;
+
+        registry.register(Box::new(signed_checkpoints_pruned.clone()))?;
+        registry.register(Box::new(unsigned_checkpoints_expired.clone()))?;
+        registry.register(Box::new(storage_entries.clone()))?;
+        registry.register(Box::new(storage_size.clone()))?;
+
+        Ok(Self { signed_checkpoints_pruned, unsigned_checkpoints_expired, storage_entries, storage_size })
+    }
+
+    pub fn record_pruned(&self, count: usize) {
+        self.signed_checkpoints_pruned.inc_by(count as u64);
+    }
+
+    pub fn record_expired(&self, count: usize) {
+        self.unsigned_checkpoints_expired.inc_by(count as u64);
+    }
+
+    /// Updates the number of entries & the approximate size of a store.
+    pub fn update_store(&self, store: &str, entries: usize, size: usize) {
+        self.storage_entries.with_label_values(&[store]).set(entries as i64);
+        self.storage_size.with_label_values(&[store]).set(size as i64);
+    }
+}
@@ -0,0 +1,132 @@
+mod metrics;
+
+use std::{sync::Arc, time::Duration};
+
+use prometheus::Registry;
+use tokio::task::JoinSet;
+
+use pragma_utils::services::Service;
+
+use crate::storage::TheorosStorage;
+
+use metrics::RetentionMetrics;
+
+/// Every [RETENTION_INTERVAL] seconds, the retention policy is applied on the checkpoints.
+const RETENTION_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long the checkpoints are kept in the storage.
+#[derive(Debug, Clone, Copy)]
+pub struct RetentionPolicy {
+    /// Number of nonces for which the signed checkpoints are kept, before the oldest
+    /// nonce still referenced by the latest update of a feed.
+    pub signed_checkpoints_retention: u32,
+    /// Duration after which a nonce that is not signed by all validators is dropped.
+    pub unsigned_checkpoints_timeout: Duration,
+}
+
+#[derive(Clone)]
+pub struct RetentionService {
+    storage: Arc<TheorosStorage>,
+    policy: RetentionPolicy,
+    metrics: RetentionMetrics,
+}
+
+#[async_trait::async_trait]
+impl Service for RetentionService {
+    async fn start(&mut self, join_set: &mut JoinSet<anyhow::Result<()>>) -> anyhow::Result<()> {
+        let service = self.clone();
+        join_set.spawn(async move {
+            tracing::info!("🧹 Retention service started");
+            service.run_forever().await;
+            Ok(())
+        });
+        Ok(())
+    }
+}
+
+impl RetentionService {
+    pub fn new(
+        storage: Arc<TheorosStorage>,
+        policy: RetentionPolicy,
+        metrics_registry: &Registry,
+    ) -> anyhow::Result<Self> {
+        let metrics = RetentionMetrics::register(metrics_registry)?;
+        Ok(Self { storage, policy, metrics })
+    }
+
+    pub async fn run_forever(&self) {
+        let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+        loop {
+            interval.tick().await;
+            self.prune_signed_checkpoints();
+            self.expire_unsigned_checkpoints().await;
+            self.report_storage_size().await;
+        }
+    }
+
+    /// Removes the signed checkpoints that can't be used to build calldata anymore,
+    /// from the storage & from the fetch caches of the validators.
+    fn prune_signed_checkpoints(&self) {
+        let oldest_nonce = self.storage.latest_update_per_feed().oldest_nonce();
+        let Some(threshold) = prune_threshold(oldest_nonce, self.policy.signed_checkpoints_retention) else {
+            return;
+        };
+        let pruned = self.storage.signed_checkpoints().prune_below(threshold);
+        if pruned > 0 {
+            tracing::debug!("🧹 Pruned {} signed checkpoints below nonce #{}", pruned, threshold);
+            self.metrics.record_pruned(pruned);
+        }
+        let pruned_from_caches = self.storage.validators_fetchers().prune_caches_below(threshold);
+        if pruned_from_caches > 0 {
+            tracing::debug!("🧹 Pruned {} cached checkpoints below nonce #{}", pruned_from_caches, threshold);
+        }
+    }
+
+    /// Drops the nonces that are still not signed by all validators after the timeout.
+    async fn expire_unsigned_checkpoints(&self) {
+        let timeout = self.policy.unsigned_checkpoints_timeout;
+        let expired = self.storage.unsigned_checkpoints().remove_expired(timeout).await;
+        for nonce in &expired {
+            tracing::warn!("⚠️ Nonce #{} was not signed by all validators after {:?}, dropping it", nonce, timeout);
+        }
+        self.metrics.record_expired(expired.len());
+    }
+
+    /// Reports the number of entries & the approximate memory used by each store.
+    async fn report_storage_size(&self) {
+        let signed = self.storage.signed_checkpoints();
+        let unsigned = self.storage.unsigned_checkpoints();
+        let latest_updates = self.storage.latest_update_per_feed();
+        let merkle_tree = self.storage.merkle_tree();
+        let (fetch_cache_entries, fetch_cache_size) = self.storage.validators_fetchers().caches_size();
+        let stores = [
+            ("signed_checkpoints", signed.len(), signed.approximate_size()),
+            ("unsigned_checkpoints", unsigned.len().await, unsigned.approximate_size().await),
+            ("latest_update_per_feed", latest_updates.len(), latest_updates.approximate_size()),
+            ("merkle_tree", merkle_tree.count().unwrap_or_default() as usize, merkle_tree.approximate_size()),
+            ("checkpoints_fetch_cache", fetch_cache_entries, fetch_cache_size),
+        ];
+        for (store, entries, size) in stores {
+            tracing::debug!("🧹 Store {} has {} entries (~{} bytes)", store, entries, size);
+            self.metrics.update_store(store, entries, size);
+        }
+    }
+}
+
+/// Nonce below which the signed checkpoints can be removed, if any.
+fn prune_threshold(oldest_referenced_nonce: Option<u32>, retention: u32) -> Option<u32> {
+    oldest_referenced_nonce.map(|nonce| nonce.saturating_sub(retention)).filter(|threshold| *threshold > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_threshold() {
+        assert_eq!(prune_threshold(None, 10), None);
+        assert_eq!(prune_threshold(Some(100), 10), Some(90));
+        assert_eq!(prune_threshold(Some(100), 0), Some(100));
+        assert_eq!(prune_threshold(Some(5), 10), None);
+    }
+}
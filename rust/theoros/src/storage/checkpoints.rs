@@ -1,78 +1,245 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::mem::size_of;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
 use starknet::core::types::Felt;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 
-use crate::types::hyperlane::{DispatchEvent, SignedCheckpointWithMessageId};
+use crate::types::hyperlane::{DispatchEvent, DispatchUpdate, SignedCheckpointWithMessageId};
+
+/// A dispatched event waiting to be signed by the validators.
+#[derive(Clone)]
+struct PendingEvent {
+    event: DispatchEvent,
+    added_at: Instant,
+}
 
 /// Mapping between messages nonces and their corresponding Event.
 #[derive(Clone, Default)]
-pub struct UnsignedCheckpointsStorage(Arc<RwLock<BTreeMap<u32, DispatchEvent>>>);
+pub struct UnsignedCheckpointsStorage {
+    events: Arc<RwLock<BTreeMap<u32, PendingEvent>>>,
+    /// Notified every time a new nonce is added.
+    new_nonce: Arc<Notify>,
+}
 
 impl UnsignedCheckpointsStorage {
     /// Insert a new mapping between a nonce & an Event.
     pub async fn add(&self, nonce: u32, event: &DispatchEvent) {
-        let mut lock = self.0.write().await;
-        lock.insert(nonce, event.clone());
+        let mut lock = self.events.write().await;
+        lock.insert(nonce, PendingEvent { event: event.clone(), added_at: Instant::now() });
+        self.new_nonce.notify_one();
     }
 
     /// Retrieve all nonces currently stored, in ascending order.
     pub async fn nonces(&self) -> Vec<u32> {
-        let lock = self.0.read().await;
+        let lock = self.events.read().await;
         lock.keys().cloned().collect()
     }
 
     /// Remove a nonce from the storage.
     pub async fn remove(&self, nonce: u32) {
-        let mut lock = self.0.write().await;
+        let mut lock = self.events.write().await;
         lock.remove(&nonce);
     }
 
     /// Get the event associated with a nonce.
     pub async fn get(&self, nonce: u32) -> Option<DispatchEvent> {
-        let lock = self.0.read().await;
-        lock.get(&nonce).cloned()
+        let lock = self.events.read().await;
+        lock.get(&nonce).map(|pending| pending.event.clone())
+    }
+
+    /// Removes the nonces added more than `timeout` ago, returning them in ascending order.
+    pub async fn remove_expired(&self, timeout: Duration) -> Vec<u32> {
+        let mut lock = self.events.write().await;
+        let now = Instant::now();
+        let expired: Vec<u32> = lock
+            .iter()
+            .filter(|(_, pending)| now.duration_since(pending.added_at) >= timeout)
+            .map(|(nonce, _)| *nonce)
+            .collect();
+        for nonce in &expired {
+            lock.remove(nonce);
+        }
+        expired
+    }
+
+    /// Number of nonces currently stored.
+    pub async fn len(&self) -> usize {
+        self.events.read().await.len()
+    }
+
+    /// Returns true if no nonce is pending.
+    pub async fn is_empty(&self) -> bool {
+        self.events.read().await.is_empty()
+    }
+
+    /// Approximate memory used by the stored events, in bytes.
+    pub async fn approximate_size(&self) -> usize {
+        let lock = self.events.read().await;
+        lock.values()
+            .map(|pending| {
+                size_of::<(u32, PendingEvent)>()
+                    + pending.event.message.body.updates.capacity() * size_of::<DispatchUpdate>()
+            })
+            .sum()
+    }
+
+    /// Waits until a new nonce is added.
+    /// If a nonce was added since the last call, returns immediately.
+    pub async fn wait_for_new_nonce(&self) {
+        self.new_nonce.notified().await;
     }
 }
 
-/// Mapping between the validators and their signed checkpoint for a given nonce.
+/// Mapping between the nonces and the signed checkpoint of each validator.
+/// Indexed by nonce first, so the checkpoints of a nonce are retrieved in O(validators).
 #[derive(Debug, Default)]
-pub struct SignedCheckpointsStorage(Arc<DashMap<(Felt, u32), SignedCheckpointWithMessageId>>);
+pub struct SignedCheckpointsStorage(Arc<DashMap<u32, HashMap<Felt, SignedCheckpointWithMessageId>>>);
 
 impl SignedCheckpointsStorage {
+    /// Number of checkpoints stored, for all nonces & validators.
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.0.iter().map(|entry| entry.value().len()).sum()
+    }
+
+    /// Returns true if no checkpoint is stored.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|entry| entry.value().is_empty())
     }
 
     /// Adds or updates the [SignedCheckpointWithMessageId] for the given validator
     pub fn add(&self, validator: Felt, nonce: u32, checkpoint: SignedCheckpointWithMessageId) {
-        self.0.insert((validator, nonce), checkpoint);
+        self.0.entry(nonce).or_default().insert(validator, checkpoint);
     }
 
-    // For the provided list of validators, returns all their signed checkpoints for the
-    // provided message_id.
+    /// For the provided list of validators, returns their signed checkpoints for the
+    /// provided nonce, in the order of the validators.
     pub fn get(&self, validators: &[Felt], searched_nonce: u32) -> Vec<(Felt, SignedCheckpointWithMessageId)> {
-        let mut checkpoints = Vec::with_capacity(self.len());
-        // Iterate over the map with tuple key (validator, message_id)
-        for validator_signatures_map in self.0.iter() {
-            let (validator, nonce) = validator_signatures_map.key();
-            if nonce == &searched_nonce && validators.contains(validator) {
-                checkpoints.push((*validator, validator_signatures_map.value().clone()));
-            }
-        }
-
-        checkpoints
+        let Some(checkpoints) = self.0.get(&searched_nonce) else {
+            return vec![];
+        };
+        validators
+            .iter()
+            .filter_map(|validator| checkpoints.get(validator).map(|checkpoint| (*validator, checkpoint.clone())))
+            .collect()
     }
 
     // Check if the given validator has a checkpoint for the given nonce.
     pub fn validator_signed_nonce(&self, validator: Felt, nonce: u32) -> bool {
-        self.0.contains_key(&(validator, nonce))
+        self.0.get(&nonce).is_some_and(|checkpoints| checkpoints.contains_key(&validator))
     }
 
     /// Checks if all validators have signed a nonce.
     pub fn all_validators_signed_nonce(&self, validators: &[Felt], nonce: u32) -> bool {
-        validators.iter().all(|validator| self.0.contains_key(&(*validator, nonce)))
+        match self.0.get(&nonce) {
+            Some(checkpoints) => validators.iter().all(|validator| checkpoints.contains_key(validator)),
+            None => validators.is_empty(),
+        }
+    }
+
+    /// Removes the checkpoints of all validators for the nonces below the provided one.
+    /// Returns the number of checkpoints removed.
+    pub fn prune_below(&self, nonce: u32) -> usize {
+        let mut pruned = 0;
+        self.0.retain(|checkpoints_nonce, checkpoints| {
+            let keep = *checkpoints_nonce >= nonce;
+            if !keep {
+                pruned += checkpoints.len();
+            }
+            keep
+        });
+        pruned
+    }
+
+    /// Approximate memory used by the stored checkpoints, in bytes.
+    pub fn approximate_size(&self) -> usize {
+        self.0
+            .iter()
+            .map(|entry| {
+                let checkpoints = entry.value();
+                size_of::<(u32, HashMap<Felt, SignedCheckpointWithMessageId>)>()
+                    + checkpoints.capacity() * size_of::<(Felt, SignedCheckpointWithMessageId)>()
+                    + checkpoints.values().map(|checkpoint| checkpoint.value.checkpoint.root.capacity()).sum::<usize>()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::U256;
+
+    use super::*;
+    use crate::{
+        test_utils::signed_checkpoint,
+        types::hyperlane::{DispatchMessage, DispatchMessageBody, DispatchMessageHeader},
+    };
+
+    fn dispatch_event(nonce: u32) -> DispatchEvent {
+        DispatchEvent {
+            sender: U256::from_words(0, 0),
+            destination_domain: 0,
+            recipient_address: U256::from_words(0, 0),
+            message: DispatchMessage {
+                header: DispatchMessageHeader {
+                    version: 3,
+                    nonce,
+                    origin: 0,
+                    sender: U256::from_words(0, 0),
+                    destination: 0,
+                    recipient: U256::from_words(0, 0),
+                },
+                body: DispatchMessageBody { nb_updated: 0, updates: vec![] },
+                raw_body: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn test_prune_signed_checkpoints() {
+        let storage = SignedCheckpointsStorage::default();
+        for nonce in 0..5 {
+            storage.add(Felt::ONE, nonce, signed_checkpoint(nonce));
+            storage.add(Felt::TWO, nonce, signed_checkpoint(nonce));
+        }
+
+        assert_eq!(storage.prune_below(3), 6);
+        assert_eq!(storage.len(), 4);
+        assert!(!storage.validator_signed_nonce(Felt::ONE, 2));
+        assert!(storage.all_validators_signed_nonce(&[Felt::ONE, Felt::TWO], 3));
+        assert_eq!(storage.prune_below(3), 0);
+    }
+
+    #[test]
+    fn test_get_signed_checkpoints() {
+        let storage = SignedCheckpointsStorage::default();
+        storage.add(Felt::ONE, 1, signed_checkpoint(1));
+        storage.add(Felt::TWO, 1, signed_checkpoint(1));
+        storage.add(Felt::THREE, 2, signed_checkpoint(2));
+
+        let checkpoints = storage.get(&[Felt::TWO, Felt::THREE, Felt::ONE], 1);
+        assert_eq!(checkpoints, vec![(Felt::TWO, signed_checkpoint(1)), (Felt::ONE, signed_checkpoint(1))]);
+        assert!(storage.get(&[Felt::ONE], 3).is_empty());
+
+        assert!(storage.all_validators_signed_nonce(&[Felt::ONE, Felt::TWO], 1));
+        assert!(!storage.all_validators_signed_nonce(&[Felt::ONE, Felt::THREE], 1));
+        assert!(storage.validator_signed_nonce(Felt::THREE, 2));
+        assert!(!storage.validator_signed_nonce(Felt::THREE, 1));
+        assert_eq!(storage.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_expire_unsigned_checkpoints() {
+        let storage = UnsignedCheckpointsStorage::default();
+        storage.add(1, &dispatch_event(1)).await;
+        storage.add(2, &dispatch_event(2)).await;
+
+        assert!(storage.remove_expired(Duration::from_secs(3600)).await.is_empty());
+        assert_eq!(storage.len().await, 2);
+
+        assert_eq!(storage.remove_expired(Duration::ZERO).await, vec![1, 2]);
+        assert_eq!(storage.len().await, 0);
     }
 }
@@ -33,6 +33,11 @@ impl FeedIdsStorage {
         self.0.len()
     }
 
+    /// Returns true if the storage contains no feed ID.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     /// Returns an iterator over the feed IDs.
     pub fn iter(&self) -> impl Iterator<Item = String> {
         self.0.iter().map(|ref_multi| ref_multi.key().clone()).collect::<Vec<_>>().into_iter()
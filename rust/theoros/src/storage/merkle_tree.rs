@@ -0,0 +1,253 @@
+use std::{
+    str::FromStr,
+    sync::{RwLock, RwLockReadGuard},
+};
+
+use alloy::primitives::{B256, U256};
+
+use crate::{
+    errors::MerkleTreeError,
+    types::hyperlane::{CheckpointWithMessageId, IncrementalMerkleTree, MerkleProof},
+};
+
+/// Outcome of the verification of a checkpoint against the [MerkleTreeStorage].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum CheckpointVerification {
+    /// The message id & the root of the checkpoint match the tree.
+    Verified,
+    /// The message of the checkpoint is not indexed yet, so it can only be verified later.
+    NotYetIndexed,
+    /// The checkpoint is older than the on-chain snapshot the tree was synced from,
+    /// so the tree does not have the nodes needed to verify it.
+    PredatesSnapshot,
+    /// The tree is not synced, so nothing can be verified.
+    Unsynced,
+}
+
+/// Merkle tree of the message ids inserted in the Hyperlane `MerkleTreeHook`, used to verify
+/// the checkpoints signed by the validators.
+/// The tree is [None] until it is synced with the on-chain tree, & gets unsynced when
+/// a message is missed so it can be synced again.
+#[derive(Debug, Default)]
+pub struct MerkleTreeStorage(RwLock<Option<IncrementalMerkleTree>>);
+
+impl MerkleTreeStorage {
+    /// Replaces the tree, i.e with a snapshot of the on-chain tree.
+    pub fn sync(&self, tree: IncrementalMerkleTree) {
+        *self.0.write().expect("poisoned lock") = Some(tree);
+    }
+
+    /// Drops the tree until it is synced again.
+    pub fn unsync(&self) {
+        *self.0.write().expect("poisoned lock") = None;
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.read().is_some()
+    }
+
+    /// Number of leaves in the tree, if synced.
+    pub fn count(&self) -> Option<u32> {
+        self.read().as_ref().map(IncrementalMerkleTree::count)
+    }
+
+    /// Inserts the id of the message of the provided index.
+    /// Returns false if the tree is not synced or if the message is already in the tree.
+    /// If a message was missed or does not match the one in the tree, the tree gets unsynced.
+    pub fn insert(&self, message_id: B256, index: u32) -> Result<bool, MerkleTreeError> {
+        let mut guard = self.0.write().expect("poisoned lock");
+        let Some(tree) = guard.as_mut() else {
+            return Ok(false);
+        };
+
+        if index < tree.count() {
+            return match tree.leaf(index) {
+                Some(leaf) if leaf != message_id => {
+                    *guard = None;
+                    Err(MerkleTreeError::MessageIdMismatch { index, expected: leaf, found: message_id })
+                }
+                _ => Ok(false),
+            };
+        }
+        if index > tree.count() {
+            let expected = tree.count();
+            *guard = None;
+            return Err(MerkleTreeError::UnexpectedIndex { expected, found: index });
+        }
+        tree.insert(message_id)?;
+        Ok(true)
+    }
+
+    /// Root of the tree when it only had its first `count` leaves, if known.
+    pub fn root_at(&self, count: u32) -> Option<B256> {
+        self.read().as_ref().and_then(|tree| tree.root_at(count))
+    }
+
+    /// Verifies that a signed checkpoint matches the tree: the message id must be the one
+    /// inserted at the checkpoint index & the root must be the root of the tree at this index.
+    /// Fails on a mismatch, otherwise tells whether the checkpoint could be verified, see [CheckpointVerification].
+    pub fn verify_checkpoint(
+        &self,
+        checkpoint: &CheckpointWithMessageId,
+    ) -> Result<CheckpointVerification, MerkleTreeError> {
+        let guard = self.read();
+        let Some(tree) = guard.as_ref() else {
+            return Ok(CheckpointVerification::Unsynced);
+        };
+
+        let index = checkpoint.checkpoint.index;
+        if index >= tree.count() {
+            return Ok(CheckpointVerification::NotYetIndexed);
+        }
+        let message_id = B256::from(checkpoint.message_id);
+        if let Some(leaf) = tree.leaf(index) {
+            if leaf != message_id {
+                return Err(MerkleTreeError::MessageIdMismatch { index, expected: leaf, found: message_id });
+            }
+        }
+
+        let root = &checkpoint.checkpoint.root;
+        let root = U256::from_str(root).map(B256::from).map_err(|_| MerkleTreeError::InvalidRoot(root.clone()))?;
+        // The index is below the count, so the root is only unknown if it predates the snapshot
+        match tree.root_at(index + 1) {
+            Some(expected) if expected != root => Err(MerkleTreeError::RootMismatch { index, expected, found: root }),
+            Some(_) => Ok(CheckpointVerification::Verified),
+            None => Ok(CheckpointVerification::PredatesSnapshot),
+        }
+    }
+
+    /// Proof that the message of index `leaf_index` is included in the checkpoint of
+    /// index `checkpoint_index`, if the tree contains the nodes needed.
+    pub fn proof(&self, leaf_index: u32, checkpoint_index: u32) -> Option<MerkleProof> {
+        let count = checkpoint_index.checked_add(1)?;
+        self.read().as_ref().and_then(|tree| tree.proof(leaf_index, count))
+    }
+
+    /// Approximate memory used by the tree.
+    pub fn approximate_size(&self) -> usize {
+        self.read().as_ref().map_or(0, IncrementalMerkleTree::approximate_size)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Option<IncrementalMerkleTree>> {
+        self.0.read().expect("poisoned lock")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::keccak256;
+
+    use super::*;
+    use crate::types::hyperlane::Checkpoint;
+
+    fn message_id(index: u32) -> B256 {
+        keccak256(format!("message-{index}"))
+    }
+
+    fn checkpoint(root: B256, index: u32, message_id: B256) -> CheckpointWithMessageId {
+        CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: U256::from(1),
+                mailbox_domain: 1,
+                root: root.to_string(),
+                index,
+            },
+            message_id: message_id.into(),
+        }
+    }
+
+    #[test]
+    fn test_insert_messages() {
+        let storage = MerkleTreeStorage::default();
+        assert!(!storage.insert(message_id(0), 0).unwrap());
+
+        storage.sync(IncrementalMerkleTree::default());
+        assert!(storage.insert(message_id(0), 0).unwrap());
+        assert!(storage.insert(message_id(1), 1).unwrap());
+        // Already inserted
+        assert!(!storage.insert(message_id(1), 1).unwrap());
+        assert_eq!(storage.count(), Some(2));
+
+        // A different message at an existing index unsyncs the tree
+        assert!(matches!(storage.insert(message_id(2), 1), Err(MerkleTreeError::MessageIdMismatch { index: 1, .. })));
+        assert!(!storage.is_synced());
+
+        // So does a missed message
+        storage.sync(IncrementalMerkleTree::default());
+        assert!(matches!(
+            storage.insert(message_id(3), 3),
+            Err(MerkleTreeError::UnexpectedIndex { expected: 0, found: 3 })
+        ));
+        assert_eq!(storage.count(), None);
+    }
+
+    #[test]
+    fn test_verify_checkpoint() {
+        let storage = MerkleTreeStorage::default();
+        let mut tree = IncrementalMerkleTree::default();
+        tree.insert(message_id(0)).unwrap();
+        let root = tree.root();
+        assert_eq!(
+            storage.verify_checkpoint(&checkpoint(root, 0, message_id(0))).unwrap(),
+            CheckpointVerification::Unsynced
+        );
+
+        storage.sync(tree);
+        storage.insert(message_id(1), 1).unwrap();
+        assert_eq!(
+            storage.verify_checkpoint(&checkpoint(root, 0, message_id(0))).unwrap(),
+            CheckpointVerification::Verified
+        );
+        let latest_root = storage.root_at(2).unwrap();
+        assert_eq!(
+            storage.verify_checkpoint(&checkpoint(latest_root, 1, message_id(1))).unwrap(),
+            CheckpointVerification::Verified
+        );
+
+        assert_eq!(
+            storage.verify_checkpoint(&checkpoint(latest_root, 2, message_id(2))).unwrap(),
+            CheckpointVerification::NotYetIndexed
+        );
+
+        assert!(matches!(
+            storage.verify_checkpoint(&checkpoint(latest_root, 0, message_id(0))),
+            Err(MerkleTreeError::RootMismatch { index: 0, .. })
+        ));
+        assert!(matches!(
+            storage.verify_checkpoint(&checkpoint(latest_root, 1, message_id(0))),
+            Err(MerkleTreeError::MessageIdMismatch { index: 1, .. })
+        ));
+
+        let proof = storage.proof(1, 1).unwrap();
+        assert_eq!(proof.root(), latest_root);
+        assert_eq!(storage.proof(0, 1).unwrap().root(), latest_root);
+        assert_eq!(storage.proof(2, 1), None);
+    }
+
+    #[test]
+    fn test_verify_checkpoint_from_snapshot() {
+        let mut full_tree = IncrementalMerkleTree::default();
+        for index in 0..5 {
+            full_tree.insert(message_id(index)).unwrap();
+        }
+        let storage = MerkleTreeStorage::default();
+        storage.sync(IncrementalMerkleTree::from_branch(full_tree.branch(), full_tree.count()));
+        storage.insert(message_id(5), 5).unwrap();
+
+        // The roots of the checkpoints before the snapshot are unknown
+        let root = full_tree.root_at(3).unwrap();
+        assert_eq!(
+            storage.verify_checkpoint(&checkpoint(root, 2, message_id(2))).unwrap(),
+            CheckpointVerification::PredatesSnapshot
+        );
+        assert_eq!(
+            storage.verify_checkpoint(&checkpoint(full_tree.root(), 4, message_id(4))).unwrap(),
+            CheckpointVerification::Verified
+        );
+        assert_eq!(
+            storage.verify_checkpoint(&checkpoint(storage.root_at(6).unwrap(), 5, message_id(5))).unwrap(),
+            CheckpointVerification::Verified
+        );
+    }
+}
@@ -1,12 +1,16 @@
 pub mod checkpoints;
 pub mod feed_id;
+pub mod merkle_tree;
 pub mod updates;
 pub mod validator;
+pub mod validators_lag;
 
 pub use checkpoints::*;
 pub use feed_id::*;
+pub use merkle_tree::*;
 pub use updates::*;
 pub use validator::*;
+pub use validators_lag::*;
 
 use starknet::core::types::Felt;
 use tokio::sync::broadcast::Sender;
@@ -23,6 +27,8 @@ pub struct TheorosStorage {
     signed_checkpoints: SignedCheckpointsStorage,
     unsigned_checkpoints: UnsignedCheckpointsStorage,
     latest_update_per_feed: LatestUpdatePerFeedStorage,
+    validators_lag: ValidatorsLagStorage,
+    merkle_tree: MerkleTreeStorage,
     // websocket notifications
     feeds_updated_tx: Sender<NewUpdatesAvailableEvent>,
 }
@@ -32,26 +38,33 @@ impl TheorosStorage {
         rpc_client: &StarknetRpc,
         pragma_feeds_registry_address: &Felt,
         hyperlane_validator_announce_address: &Felt,
+        mut validators_fetchers: ValidatorsFetchersStorage,
     ) -> anyhow::Result<Self> {
         let initial_validators = rpc_client.get_announced_validators(hyperlane_validator_announce_address).await?;
         let initial_locations = rpc_client
             .get_announced_storage_locations(hyperlane_validator_announce_address, &initial_validators)
             .await?;
 
-        let mut validators_fetchers = ValidatorsFetchersStorage::default();
         validators_fetchers.fill_with_initial_state(initial_validators, initial_locations).await?;
 
         let supported_feed_ids = rpc_client.get_feed_ids(pragma_feeds_registry_address).await?;
         let feed_ids = FeedIdsStorage::from_rpc_response(supported_feed_ids);
 
-        Ok(Self {
+        Ok(Self::new(feed_ids, validators_fetchers))
+    }
+
+    /// Creates an empty storage for the provided feed ids & validators.
+    pub fn new(feed_ids: FeedIdsStorage, validators_fetchers: ValidatorsFetchersStorage) -> Self {
+        Self {
             feed_ids,
             validators_fetchers,
             signed_checkpoints: SignedCheckpointsStorage::default(),
             unsigned_checkpoints: UnsignedCheckpointsStorage::default(),
             latest_update_per_feed: LatestUpdatePerFeedStorage::default(),
+            validators_lag: ValidatorsLagStorage::default(),
+            merkle_tree: MerkleTreeStorage::default(),
             feeds_updated_tx: tokio::sync::broadcast::channel(FEED_UPDATED_CHANNEL_CAPACITY).0,
-        })
+        }
     }
 
     pub fn feed_ids(&self) -> &FeedIdsStorage {
@@ -74,6 +87,14 @@ impl TheorosStorage {
         &self.unsigned_checkpoints
     }
 
+    pub fn validators_lag(&self) -> &ValidatorsLagStorage {
+        &self.validators_lag
+    }
+
+    pub fn merkle_tree(&self) -> &MerkleTreeStorage {
+        &self.merkle_tree
+    }
+
     pub fn feeds_updated_tx(&self) -> &Sender<NewUpdatesAvailableEvent> {
         &self.feeds_updated_tx
     }
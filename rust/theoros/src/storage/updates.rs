@@ -1,7 +1,8 @@
+use std::mem::size_of;
 use std::sync::Arc;
 
 use alloy::primitives::U256;
-use dashmap::DashMap;
+use dashmap::{mapref::entry::Entry, DashMap};
 
 use crate::types::hyperlane::DispatchUpdateInfos;
 
@@ -10,13 +11,90 @@ use crate::types::hyperlane::DispatchUpdateInfos;
 pub struct LatestUpdatePerFeedStorage(Arc<DashMap<U256, DispatchUpdateInfos>>);
 
 impl LatestUpdatePerFeedStorage {
-    /// Insert the latest [`DispatchUpdateInfos`] for a feed id.
-    pub fn add(&self, feed_id: U256, event: DispatchUpdateInfos) {
-        self.0.insert(feed_id, event);
+    /// Insert the latest [`DispatchUpdateInfos`] for a feed id, unless the stored one is newer.
+    /// Returns true if the update was stored.
+    pub fn add(&self, feed_id: U256, event: DispatchUpdateInfos) -> bool {
+        match self.0.entry(feed_id) {
+            Entry::Occupied(mut entry) => {
+                if !event.is_newer_than(entry.get()) {
+                    return false;
+                }
+                entry.insert(event);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(event);
+            }
+        }
+        true
     }
 
     /// Retrieves the latest [`DispatchUpdateInfos`] for a feed id.
     pub fn get(&self, feed_id: &U256) -> Option<DispatchUpdateInfos> {
         self.0.get(feed_id).map(|r| r.value().clone())
     }
+
+    /// Returns the oldest nonce referenced by the latest updates, if any.
+    pub fn oldest_nonce(&self) -> Option<u32> {
+        self.0.iter().map(|entry| entry.value().nonce).min()
+    }
+
+    /// Number of feeds with a latest update.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if no feed has been updated yet.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Approximate memory used by the latest updates, in bytes.
+    pub fn approximate_size(&self) -> usize {
+        let messages_size: usize = self.0.iter().map(|entry| entry.value().message.len()).sum();
+        self.len() * size_of::<(U256, DispatchUpdateInfos)>() + messages_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Bytes;
+    use pragma_feeds::{AssetClass, FeedId, FeedType};
+    use starknet::core::types::{Felt, U256 as StarknetU256};
+
+    use super::*;
+    use crate::types::hyperlane::{DispatchUpdate, MetadataUpdate, SpotMedianUpdate};
+
+    fn update_infos(nonce: u32, timestamp: u64) -> DispatchUpdateInfos {
+        let feed_id = FeedId::new(AssetClass::Crypto, FeedType::UniqueSpotMedian, "BTC/USD").unwrap();
+        let update = SpotMedianUpdate {
+            pair_id: StarknetU256::from_words(0, 0),
+            metadata: MetadataUpdate { timestamp, num_sources_aggregated: 3, decimals: 8 },
+            price: StarknetU256::from_words(nonce.into(), 0),
+            volume: StarknetU256::from_words(0, 0),
+        };
+        DispatchUpdateInfos {
+            nonce,
+            emitter_chain_id: 0,
+            emitter_address: Felt::ZERO,
+            update: DispatchUpdate::SpotMedian { update, feed_id },
+            message: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn test_latest_update_is_monotonic() {
+        let storage = LatestUpdatePerFeedStorage::default();
+        let feed_id = U256::from(1);
+
+        assert!(storage.add(feed_id, update_infos(5, 100)));
+        // Older nonce finalized after a newer one
+        assert!(!storage.add(feed_id, update_infos(4, 90)));
+        // Same nonce processed twice
+        assert!(!storage.add(feed_id, update_infos(5, 100)));
+        assert_eq!(storage.get(&feed_id).unwrap().nonce, 5);
+
+        assert!(storage.add(feed_id, update_infos(6, 100)));
+        assert!(storage.add(feed_id, update_infos(7, 110)));
+        assert_eq!(storage.get(&feed_id).unwrap().nonce, 7);
+    }
 }
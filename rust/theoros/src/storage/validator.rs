@@ -5,14 +5,38 @@ use anyhow::bail;
 use dashmap::DashMap;
 use starknet::core::types::Felt;
 
-use crate::types::hyperlane::{CheckpointStorage, FetchFromStorage, ValidatorAnnouncementEvent};
+use crate::configs::validators_config::ValidatorStorageConfig;
+use crate::types::hyperlane::{
+    cached::{CachedStorage, FetchCacheConfig, FetchCacheMetrics},
+    fallback::FallbackStorage,
+    CheckpointStorage, FetchFromStorage, ValidatorAnnouncementEvent,
+};
 
 /// Mapping between the validators and their fetcher used to
 /// retrieve signed checkpoints.
 #[derive(Debug, Default)]
-pub struct ValidatorsFetchersStorage(Arc<DashMap<Felt, Arc<dyn FetchFromStorage + Send + Sync>>>);
+pub struct ValidatorsFetchersStorage {
+    fetchers: Arc<DashMap<Felt, Arc<dyn FetchFromStorage + Send + Sync>>>,
+    /// Whether the validators announcing a local storage are fetched. Disabled by default
+    /// since only a validator running on the same machine can be fetched this way.
+    allow_local_storage: bool,
+    /// Storage overrides from the configuration, applied over the on-chain announcements.
+    overrides: HashMap<Felt, ValidatorStorageConfig>,
+    /// If set, the fetchers are wrapped in a [CachedStorage].
+    fetch_cache: Option<(FetchCacheConfig, FetchCacheMetrics)>,
+}
 
 impl ValidatorsFetchersStorage {
+    pub fn new(allow_local_storage: bool, overrides: HashMap<Felt, ValidatorStorageConfig>) -> Self {
+        Self { fetchers: Default::default(), allow_local_storage, overrides, fetch_cache: None }
+    }
+
+    /// Caches the requests of the fetchers, see [CachedStorage].
+    pub fn with_fetch_cache(mut self, config: FetchCacheConfig, metrics: FetchCacheMetrics) -> Self {
+        self.fetch_cache = Some((config, metrics));
+        self
+    }
+
     /// Fills the [DashMap] with the initial state fetched from the RPC.
     pub async fn fill_with_initial_state(
         &mut self,
@@ -24,40 +48,110 @@ impl ValidatorsFetchersStorage {
         }
 
         for (validator, location) in validators.into_iter().zip(locations.into_iter()) {
-            // TODO: This should be a feature. We sometime want to have a local storage.
-            if location[location.len() - 1].starts_with("file") {
-                continue;
-            }
-            let storage = CheckpointStorage::from_str(&location[location.len() - 1])?;
-            let storage_fetcher = storage.build().await?;
-            self.0.insert(validator, storage_fetcher);
+            self.build_and_add(validator, location.last().map(String::as_str)).await?;
         }
 
         Ok(())
     }
 
-    /// Adds or updates the [CheckpointStorage] for the given validator
-    pub async fn build_and_add(&self, validator: Felt, storage: CheckpointStorage) -> anyhow::Result<()> {
-        let storage_fetcher = storage.build().await?;
-        self.0.insert(validator, storage_fetcher);
+    /// Adds or updates the fetcher of the validator from its latest announced storage location
+    /// & the overrides of the configuration. Blacklisted validators are never fetched.
+    /// If none of its locations can be fetched, the previous fetcher of the validator is removed.
+    pub async fn build_and_add(&self, validator: Felt, announced_location: Option<&str>) -> anyhow::Result<()> {
+        let storage_config = self.overrides.get(&validator).cloned().unwrap_or_default();
+        if storage_config.blacklisted {
+            tracing::info!("🚫 Validator {:#x} is blacklisted, its checkpoints won't be fetched", validator);
+            return Ok(());
+        }
+
+        // The configured locations have the priority over the announced one, if not pinned
+        let mut locations = storage_config.locations;
+        if let Some(location) = announced_location {
+            if !storage_config.pinned && !locations.iter().any(|configured| configured == location) {
+                locations.push(location.to_owned());
+            }
+        }
+
+        let mut fetchers = Vec::with_capacity(locations.len());
+        for location in locations {
+            let storage = CheckpointStorage::from_str(&location)?;
+            if matches!(storage, CheckpointStorage::LocalStorage { .. }) && !self.allow_local_storage {
+                tracing::warn!(
+                    "⚠️ Ignoring the local storage of validator {:#x}, use --allow-local-storage to fetch it",
+                    validator
+                );
+                continue;
+            }
+            fetchers.push(storage.build().await?);
+        }
+
+        let storage_fetcher: Arc<dyn FetchFromStorage + Send + Sync> = match fetchers.len() {
+            0 => {
+                if self.fetchers.remove(&validator).is_some() {
+                    tracing::warn!(
+                        "⚠️ Validator {:#x} has no storage location left to fetch, its checkpoints won't be fetched anymore",
+                        validator
+                    );
+                }
+                return Ok(());
+            }
+            1 => fetchers.remove(0),
+            _ => Arc::new(FallbackStorage::new(fetchers)),
+        };
+        let storage_fetcher: Arc<dyn FetchFromStorage + Send + Sync> = match &self.fetch_cache {
+            Some((config, metrics)) => Arc::new(CachedStorage::new(storage_fetcher, *config, metrics.clone())),
+            None => storage_fetcher,
+        };
+        self.fetchers.insert(validator, storage_fetcher);
         Ok(())
     }
 
     /// Adds or updates the [CheckpointStorage] for the given validator from a [ValidatorAnnouncementEvent]
-    /// NOTE: This won't work with local storage.
-    /// TODO: This should be a feature. We sometime want to have a local storage.
     pub async fn add_from_announcement_event(&self, event: ValidatorAnnouncementEvent) -> anyhow::Result<()> {
         let validator: Felt = event.validator.into();
-        if event.storage_location.starts_with("file") {
+        if self.overrides.get(&validator).is_some_and(|storage_config| storage_config.pinned) {
+            tracing::info!("📌 Validator {:#x} is pinned, ignoring its announced storage location", validator);
             return Ok(());
         }
-        let storage = CheckpointStorage::from_str(&event.storage_location)?;
-        self.build_and_add(validator, storage).await?;
+        self.build_and_add(validator, Some(&event.storage_location)).await?;
         Ok(())
     }
 
+    /// Removes the checkpoints below the nonce from the fetch caches, returning the number removed.
+    pub fn prune_caches_below(&self, nonce: u32) -> usize {
+        self.fetchers.iter().map(|entry| entry.value().prune_cache_below(nonce)).sum()
+    }
+
+    /// Returns the number of entries of the fetch caches & their approximate memory usage in bytes.
+    pub fn caches_size(&self) -> (usize, usize) {
+        self.fetchers
+            .iter()
+            .map(|entry| entry.value().cache_size())
+            .fold((0, 0), |(entries, size), (fetcher_entries, fetcher_size)| {
+                (entries + fetcher_entries, size + fetcher_size)
+            })
+    }
+
     /// Returns all registered mappings between validators & their location storage.
     pub fn all(&self) -> HashMap<Felt, Arc<dyn FetchFromStorage + Send + Sync>> {
-        self.0.iter().map(|entry| (*entry.key(), entry.value().clone())).collect()
+        self.fetchers.iter().map(|entry| (*entry.key(), entry.value().clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_build_and_add_removes_fetcher_without_location() {
+        let storage = ValidatorsFetchersStorage::new(false, HashMap::new());
+        let validator = Felt::ONE;
+
+        storage.build_and_add(validator, Some("https://cdn.example.com/validator")).await.unwrap();
+        assert_eq!(storage.all()[&validator].announcement_location(), "https://cdn.example.com/validator");
+
+        // The new announced location is a local storage, which is not fetched
+        storage.build_and_add(validator, Some("file:///tmp/validator")).await.unwrap();
+        assert!(storage.all().is_empty());
     }
 }
@@ -0,0 +1,102 @@
+use std::sync::RwLock;
+
+use dashmap::DashMap;
+use starknet::core::types::Felt;
+
+/// How far behind the mailbox a validator is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorLag {
+    pub validator: Felt,
+    /// Index of the latest checkpoint signed by the validator, if any.
+    pub latest_index: Option<u32>,
+    /// Number of dispatched messages that the validator did not sign yet.
+    pub lag: u32,
+}
+
+/// Tracks the latest checkpoint index signed by each validator & the latest nonce
+/// dispatched by the mailbox, so we can find the validators that stopped signing.
+#[derive(Debug, Default)]
+pub struct ValidatorsLagStorage {
+    latest_indexes: DashMap<Felt, u32>,
+    latest_dispatched_nonce: RwLock<Option<u32>>,
+}
+
+impl ValidatorsLagStorage {
+    /// Stores the latest index signed by the validator.
+    pub fn set_latest_index(&self, validator: Felt, index: u32) {
+        self.latest_indexes.insert(validator, index);
+    }
+
+    /// Returns the latest index signed by the validator, if known.
+    pub fn latest_index(&self, validator: &Felt) -> Option<u32> {
+        self.latest_indexes.get(validator).map(|index| *index)
+    }
+
+    /// Stores the nonce of a dispatched message, if it is the most recent one.
+    pub fn set_latest_dispatched_nonce(&self, nonce: u32) {
+        let mut latest_nonce = self.latest_dispatched_nonce.write().expect("poisoned lock");
+        *latest_nonce = Some(latest_nonce.map_or(nonce, |latest| latest.max(nonce)));
+    }
+
+    /// Returns the nonce of the latest message dispatched by the mailbox.
+    pub fn latest_dispatched_nonce(&self) -> Option<u32> {
+        *self.latest_dispatched_nonce.read().expect("poisoned lock")
+    }
+
+    /// Returns the lag of each of the provided validators.
+    pub fn lags(&self, validators: &[Felt]) -> Vec<ValidatorLag> {
+        let latest_nonce = self.latest_dispatched_nonce();
+        validators
+            .iter()
+            .map(|validator| {
+                let latest_index = self.latest_index(validator);
+                ValidatorLag { validator: *validator, latest_index, lag: compute_lag(latest_nonce, latest_index) }
+            })
+            .collect()
+    }
+}
+
+/// Number of messages dispatched after the latest index signed by a validator.
+/// Nonces start at 0, so a validator that never signed lags by `latest_nonce + 1`.
+fn compute_lag(latest_nonce: Option<u32>, latest_index: Option<u32>) -> u32 {
+    match (latest_nonce, latest_index) {
+        (None, _) => 0,
+        (Some(nonce), Some(index)) => nonce.saturating_sub(index),
+        (Some(nonce), None) => nonce.saturating_add(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_lag() {
+        assert_eq!(compute_lag(None, None), 0);
+        assert_eq!(compute_lag(None, Some(3)), 0);
+        assert_eq!(compute_lag(Some(10), Some(10)), 0);
+        assert_eq!(compute_lag(Some(10), Some(7)), 3);
+        assert_eq!(compute_lag(Some(10), None), 11);
+        // A validator may have read a more recent message than the indexer
+        assert_eq!(compute_lag(Some(10), Some(12)), 0);
+    }
+
+    #[test]
+    fn test_validators_lags() {
+        let storage = ValidatorsLagStorage::default();
+        storage.set_latest_dispatched_nonce(12);
+        storage.set_latest_dispatched_nonce(8);
+        storage.set_latest_index(Felt::ONE, 12);
+        storage.set_latest_index(Felt::TWO, 5);
+
+        assert_eq!(storage.latest_dispatched_nonce(), Some(12));
+        assert_eq!(
+            storage.lags(&[Felt::ONE, Felt::TWO, Felt::THREE]),
+            vec![
+                ValidatorLag { validator: Felt::ONE, latest_index: Some(12), lag: 0 },
+                ValidatorLag { validator: Felt::TWO, latest_index: Some(5), lag: 7 },
+                ValidatorLag { validator: Felt::THREE, latest_index: None, lag: 13 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! Fixtures shared by the unit tests & the benches.
+
+use alloy::{primitives::U256, signers::Signature};
+
+use crate::types::hyperlane::{Checkpoint, CheckpointWithMessageId, SignedCheckpointWithMessageId};
+
+/// Returns a checkpoint signed at the provided index, with a dummy signature.
+pub fn signed_checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+    SignedCheckpointWithMessageId {
+        value: CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: U256::from(1),
+                mailbox_domain: 1,
+                root: format!("{:#x}", U256::from(index)),
+                index,
+            },
+            message_id: U256::from(index),
+        },
+        signature: Signature::test_signature(),
+    }
+}
@@ -1,20 +1,26 @@
 use std::str::FromStr;
 
-use alloy::{primitives::U256, signers::Signature};
-use anyhow::Context;
-use pragma_utils::conversions::alloy::hex_str_to_u256;
+use alloy::{
+    primitives::{keccak256, Bytes, B256, U256},
+    signers::Signature,
+};
+use pragma_feeds::FeedId;
 use serde::{Deserialize, Serialize};
 use starknet::core::types::Felt;
 
 use crate::{
     configs::evm_config::EvmChainName,
     constants::{HYPERLANE_VERSION, PRAGMA_MAJOR_VERSION, PRAGMA_MINOR_VERSION, TRAILING_HEADER_SIZE},
-    types::hyperlane::{CheckpointWithMessageId, DispatchUpdate},
+    errors::CalldataError,
+    types::hyperlane::{
+        CheckpointWithMessageId, DispatchUpdate, DispatchUpdateInfos, MessageIdMultisigMetadata,
+        SignedCheckpointWithMessageId,
+    },
     types::state::AppState,
 };
 
 pub trait AsCalldata {
-    fn as_bytes(&self) -> Vec<u8>;
+    fn as_bytes(&self) -> Result<Vec<u8>, CalldataError>;
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -32,54 +38,49 @@ pub struct Calldata {
 }
 
 impl Calldata {
-    pub async fn build_from(state: &AppState, chain_name: EvmChainName, feed_id: String) -> anyhow::Result<Calldata> {
-        let feed_id = hex_str_to_u256(&feed_id)?;
-        let update_info = state.storage.latest_update_per_feed().get(&feed_id).context("No update found")?;
-
-        let validator_index_map =
-            state.hyperlane_validators_mapping.get_validators(&chain_name).context("No validators found")?;
-
-        let validators: Vec<Felt> = validator_index_map.keys().copied().collect();
-        let checkpoints = state.storage.signed_checkpoints().get(&validators, update_info.nonce);
-        anyhow::ensure!(!checkpoints.is_empty(), "No signatures found");
-
-        // Ensure all nonce have the same checkpoint
-        let nonce_checkpoint = &checkpoints[0].1.value;
-        anyhow::ensure!(
-            checkpoints.iter().all(|(_, checkpoint)| &(checkpoint.value) == nonce_checkpoint),
-            "Inconsistent checkpoint values found"
-        );
+    pub async fn build_from(
+        state: &AppState,
+        chain_name: EvmChainName,
+        feed_id: String,
+    ) -> Result<Calldata, CalldataError> {
+        let (update_info, signed_checkpoints) = latest_signed_update(state, chain_name, feed_id)?;
+        Self::from_signed_checkpoints(&update_info, signed_checkpoints)
+    }
 
-        let signatures: Vec<ValidatorSignature> = checkpoints
-            .iter()
-            .filter_map(|(validator, signed_checkpoint)| {
-                validator_index_map
-                    .get(validator)
-                    .map(|&idx| ValidatorSignature { validator_index: idx, signature: signed_checkpoint.signature })
-            })
-            .collect();
+    /// Builds the calldata of an update from the checkpoints signed by the validators, along with
+    /// the index of each validator.
+    /// The signatures are sorted by validator index & deduplicated, so the encoding is canonical:
+    /// the same signatures always produce the same calldata, in the order expected by the decoder.
+    pub fn from_signed_checkpoints(
+        update_info: &DispatchUpdateInfos,
+        signed_checkpoints: Vec<(u8, SignedCheckpointWithMessageId)>,
+    ) -> Result<Calldata, CalldataError> {
+        let nonce = update_info.nonce;
+        let (nonce_checkpoint, signatures) = canonical_signatures(nonce, signed_checkpoints)?;
 
-        let update = match update_info.update {
+        let update = match &update_info.update {
             DispatchUpdate::SpotMedian { update, .. } => update,
         };
+        let update_data = update.to_bytes();
 
         let payload = Payload {
-            checkpoint: nonce_checkpoint.clone(),
+            checkpoint: nonce_checkpoint,
             num_updates: 1,
             proof_len: 0,
             proof: vec![],
-            update_data_len: update.to_bytes().len() as u16,
-            update_data: update.to_bytes(),
-            feed_id,
-            publish_time: update.metadata.timestamp,
+            update_data_len: encoded_len(update_data.len(), CalldataError::UpdateTooLong)?,
+            update_data,
+            feed_id: update_info.update.feed_id().into(),
+            publish_time: update_info.update.publish_time(),
         };
 
         let hyperlane_message = HyperlaneMessage {
             hyperlane_version: HYPERLANE_VERSION,
             emitter_chain_id: update_info.emitter_chain_id,
             emitter_address: update_info.emitter_address,
-            nonce: update_info.nonce,
-            signers_len: signatures.len() as u8,
+            nonce,
+            timestamp: update_info.update.publish_time(),
+            signers_len: encoded_len(signatures.len(), CalldataError::TooManySignatures)?,
             signatures,
             payload,
         };
@@ -88,18 +89,131 @@ impl Calldata {
             major_version: PRAGMA_MAJOR_VERSION,
             minor_version: PRAGMA_MINOR_VERSION,
             trailing_header_size: TRAILING_HEADER_SIZE,
-            hyperlane_msg_size: hyperlane_message.as_bytes().len().try_into()?,
+            hyperlane_msg_size: encoded_len(hyperlane_message.as_bytes()?.len(), CalldataError::MessageTooLong)?,
             hyperlane_msg: hyperlane_message,
         })
     }
 }
 
+/// Arguments of `Mailbox.process`, delivering the message of an update through the standard
+/// Hyperlane contracts, verified by a `MessageIdMultisigIsm`.
+///
+/// The ISM expects the signatures in the order of its own validator set. [MailboxCalldata::build_from]
+/// orders them like the validator set of the Pragma contract of the chain, so the ISM must be deployed
+/// with the same validators, in the same order.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MailboxCalldata {
+    /// Metadata of the ISM, see [MessageIdMultisigMetadata]
+    pub metadata: MessageIdMultisigMetadata,
+    /// Message dispatched by the Pragma mailbox, in the Hyperlane encoding
+    pub message: Bytes,
+}
+
+impl MailboxCalldata {
+    pub async fn build_from(
+        state: &AppState,
+        chain_name: EvmChainName,
+        feed_id: String,
+    ) -> Result<MailboxCalldata, CalldataError> {
+        let (update_info, signed_checkpoints) = latest_signed_update(state, chain_name, feed_id)?;
+        Self::from_signed_checkpoints(&update_info, signed_checkpoints)
+    }
+
+    /// Builds the `Mailbox.process` arguments of the message of an update from the checkpoints
+    /// signed by the validators, along with the index of each validator in the validator set of the ISM.
+    /// Like for [Calldata], the signatures are sorted by validator index & deduplicated.
+    pub fn from_signed_checkpoints(
+        update_info: &DispatchUpdateInfos,
+        signed_checkpoints: Vec<(u8, SignedCheckpointWithMessageId)>,
+    ) -> Result<MailboxCalldata, CalldataError> {
+        let nonce = update_info.nonce;
+        let (checkpoint, signatures) = canonical_signatures(nonce, signed_checkpoints)?;
+
+        // The ISM verifies the signatures against the id of the delivered message
+        if keccak256(&update_info.message) != B256::from(checkpoint.message_id) {
+            return Err(CalldataError::MessageIdMismatch(nonce));
+        }
+
+        let root = U256::from_str(&checkpoint.checkpoint.root)
+            .map_err(|_| CalldataError::InvalidCheckpointRoot(checkpoint.checkpoint.root.clone()))?;
+        let metadata = MessageIdMultisigMetadata {
+            merkle_tree_hook_address: checkpoint.checkpoint.merkle_tree_hook_address,
+            root: root.into(),
+            index: checkpoint.checkpoint.index,
+            signatures: signatures.into_iter().map(|signature| signature.signature).collect(),
+        };
+
+        Ok(MailboxCalldata { metadata, message: update_info.message.clone() })
+    }
+}
+
+/// Returns the latest update of the feed & the checkpoints of its nonce signed by the validators
+/// of the chain, along with the index of each validator.
+fn latest_signed_update(
+    state: &AppState,
+    chain_name: EvmChainName,
+    feed_id: String,
+) -> Result<(DispatchUpdateInfos, Vec<(u8, SignedCheckpointWithMessageId)>), CalldataError> {
+    let parsed_feed_id = FeedId::from_str(&feed_id).map_err(|_| CalldataError::InvalidFeedId(feed_id.clone()))?;
+    let update_info = state
+        .storage
+        .latest_update_per_feed()
+        .get(&(&parsed_feed_id).into())
+        .ok_or(CalldataError::UpdateNotFound(feed_id))?;
+
+    let validator_index_map = state
+        .hyperlane_validators_mapping
+        .get_validators(&chain_name)
+        .ok_or(CalldataError::NoValidators(chain_name))?;
+
+    let validators: Vec<Felt> = validator_index_map.keys().copied().collect();
+    let signed_checkpoints = state
+        .storage
+        .signed_checkpoints()
+        .get(&validators, update_info.nonce)
+        .into_iter()
+        .filter_map(|(validator, checkpoint)| validator_index_map.get(&validator).map(|&idx| (idx, checkpoint)))
+        .collect();
+
+    Ok((update_info, signed_checkpoints))
+}
+
+/// Sorts the signed checkpoints by validator index & removes the duplicates.
+/// Returns the checkpoint signed by all the validators & their signatures.
+fn canonical_signatures(
+    nonce: u32,
+    mut signed_checkpoints: Vec<(u8, SignedCheckpointWithMessageId)>,
+) -> Result<(CheckpointWithMessageId, Vec<ValidatorSignature>), CalldataError> {
+    signed_checkpoints.sort_by(|(a_index, a), (b_index, b)| {
+        a_index.cmp(b_index).then_with(|| a.signature.as_bytes().cmp(&b.signature.as_bytes()))
+    });
+    signed_checkpoints.dedup_by_key(|(validator_index, _)| *validator_index);
+
+    let (_, first_checkpoint) = signed_checkpoints.first().ok_or(CalldataError::NoSignatures(nonce))?;
+    // Ensure all validators signed the same checkpoint
+    let nonce_checkpoint = first_checkpoint.value.clone();
+    if signed_checkpoints.iter().any(|(_, checkpoint)| checkpoint.value != nonce_checkpoint) {
+        return Err(CalldataError::InconsistentCheckpoints(nonce));
+    }
+
+    let signatures = signed_checkpoints
+        .into_iter()
+        .map(|(validator_index, checkpoint)| ValidatorSignature { validator_index, signature: checkpoint.signature })
+        .collect();
+    Ok((nonce_checkpoint, signatures))
+}
+
+/// Converts a length into the integer type used to encode it, or returns the provided error.
+fn encoded_len<T: TryFrom<usize>>(len: usize, error: fn(usize) -> CalldataError) -> Result<T, CalldataError> {
+    T::try_from(len).map_err(|_| error(len))
+}
+
 impl AsCalldata for Calldata {
-    fn as_bytes(&self) -> Vec<u8> {
+    fn as_bytes(&self) -> Result<Vec<u8>, CalldataError> {
         let mut bytes = vec![self.major_version, self.minor_version, self.trailing_header_size];
         bytes.extend_from_slice(&self.hyperlane_msg_size.to_be_bytes());
-        bytes.extend_from_slice(&self.hyperlane_msg.as_bytes());
-        bytes
+        bytes.extend_from_slice(&self.hyperlane_msg.as_bytes()?);
+        Ok(bytes)
     }
 }
 
@@ -112,6 +226,8 @@ pub struct HyperlaneMessage {
     /// List of signatures
     pub signatures: Vec<ValidatorSignature>,
     pub nonce: u32,
+    /// Timestamp of the message, i.e the publish time of the update
+    pub timestamp: u64,
     /// Chain ID of the emitter (pragma chain id)
     pub emitter_chain_id: u32,
     /// Address of the emitter (pragma chain mailbox address)
@@ -120,17 +236,17 @@ pub struct HyperlaneMessage {
 }
 
 impl AsCalldata for HyperlaneMessage {
-    fn as_bytes(&self) -> Vec<u8> {
+    fn as_bytes(&self) -> Result<Vec<u8>, CalldataError> {
         let mut bytes = vec![self.hyperlane_version, self.signers_len];
         for signer in &self.signatures {
-            bytes.push(signer.validator_index);
-            bytes.extend_from_slice(&signer.signature.as_bytes());
+            bytes.extend_from_slice(&signer.as_bytes()?);
         }
         bytes.extend_from_slice(&self.nonce.to_be_bytes());
+        bytes.extend_from_slice(&self.timestamp.to_be_bytes());
         bytes.extend_from_slice(&self.emitter_chain_id.to_be_bytes());
         bytes.extend_from_slice(&self.emitter_address.to_bytes_be());
-        bytes.extend_from_slice(&self.payload.as_bytes());
-        bytes
+        bytes.extend_from_slice(&self.payload.as_bytes()?);
+        Ok(bytes)
     }
 }
 
@@ -142,10 +258,10 @@ pub struct ValidatorSignature {
 }
 
 impl AsCalldata for ValidatorSignature {
-    fn as_bytes(&self) -> Vec<u8> {
+    fn as_bytes(&self) -> Result<Vec<u8>, CalldataError> {
         let mut bytes = vec![self.validator_index];
         bytes.extend_from_slice(&self.signature.as_bytes());
-        bytes
+        Ok(bytes)
     }
 }
 
@@ -169,12 +285,15 @@ pub struct Payload {
 }
 
 impl AsCalldata for Payload {
-    fn as_bytes(&self) -> Vec<u8> {
+    fn as_bytes(&self) -> Result<Vec<u8>, CalldataError> {
+        let checkpoint = &self.checkpoint.checkpoint;
+        let root = U256::from_str(&checkpoint.root)
+            .map_err(|_| CalldataError::InvalidCheckpointRoot(checkpoint.root.clone()))?;
+
         let mut bytes = vec![];
-        bytes.extend_from_slice(self.checkpoint.checkpoint.merkle_tree_hook_address.to_be_bytes::<32>().as_slice());
-        let root: [u8; 32] = U256::from_str(&self.checkpoint.checkpoint.root).unwrap().to_be_bytes();
-        bytes.extend_from_slice(root.as_slice());
-        bytes.extend_from_slice(self.checkpoint.checkpoint.index.to_be_bytes().as_slice());
+        bytes.extend_from_slice(checkpoint.merkle_tree_hook_address.to_be_bytes::<32>().as_slice());
+        bytes.extend_from_slice(root.to_be_bytes::<32>().as_slice());
+        bytes.extend_from_slice(checkpoint.index.to_be_bytes().as_slice());
         bytes.extend_from_slice(self.checkpoint.message_id.to_be_bytes::<32>().as_slice());
         bytes.push(self.num_updates);
         bytes.extend_from_slice(&self.update_data_len.to_be_bytes());
@@ -183,9 +302,241 @@ impl AsCalldata for Payload {
             bytes.extend_from_slice(proof.as_bytes());
         }
         bytes.extend_from_slice(&self.update_data);
-        let feed_id: [u8; 32] = self.feed_id.to_be_bytes();
-        bytes.extend_from_slice(feed_id.as_slice());
+        bytes.extend_from_slice(self.feed_id.to_be_bytes::<32>().as_slice());
         bytes.extend_from_slice(&self.publish_time.to_be_bytes());
-        bytes
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{hex, primitives::Address};
+    use serde::Deserialize;
+    use starknet::core::types::U256 as StarknetU256;
+
+    use super::*;
+    use crate::types::hyperlane::{Checkpoint, MetadataUpdate, SpotMedianUpdate};
+
+    /// Golden vectors, shared with the Solidity `PragmaDecoder` tests.
+    const CALLDATA_VECTORS: &str = include_str!("../../../../test-vectors/calldata.json");
+
+    #[derive(Deserialize)]
+    struct GoldenVectors {
+        vectors: Vec<GoldenVector>,
+    }
+
+    #[derive(Clone, Deserialize)]
+    struct GoldenVector {
+        name: String,
+        validators: Vec<String>,
+        emitter_chain_id: u32,
+        emitter_address: String,
+        merkle_tree_hook_address: String,
+        nonce: u32,
+        checkpoint_root: String,
+        message_id: String,
+        feed_id: String,
+        publish_time: u64,
+        num_sources_aggregated: u16,
+        decimals: u8,
+        price: String,
+        volume: String,
+        signatures: Vec<GoldenSignature>,
+        calldata: String,
+    }
+
+    #[derive(Clone, Deserialize)]
+    struct GoldenSignature {
+        validator_index: u8,
+        signature: String,
+    }
+
+    fn golden_vectors() -> Vec<GoldenVector> {
+        serde_json::from_str::<GoldenVectors>(CALLDATA_VECTORS).unwrap().vectors
+    }
+
+    fn update_infos(vector: &GoldenVector) -> DispatchUpdateInfos {
+        let feed_id = FeedId::from_str(&vector.feed_id).unwrap();
+        let pair_id_bytes = feed_id.pair_id_bytes();
+        let update = SpotMedianUpdate {
+            pair_id: StarknetU256::from_words(
+                u128::from_be_bytes(pair_id_bytes[16..].try_into().unwrap()),
+                u128::from_be_bytes(pair_id_bytes[..16].try_into().unwrap()),
+            ),
+            metadata: MetadataUpdate {
+                timestamp: vector.publish_time,
+                num_sources_aggregated: vector.num_sources_aggregated,
+                decimals: vector.decimals,
+            },
+            price: StarknetU256::from_words(vector.price.parse().unwrap(), 0),
+            volume: StarknetU256::from_words(vector.volume.parse().unwrap(), 0),
+        };
+        DispatchUpdateInfos {
+            nonce: vector.nonce,
+            emitter_chain_id: vector.emitter_chain_id,
+            emitter_address: Felt::from_hex(&vector.emitter_address).unwrap(),
+            update: DispatchUpdate::SpotMedian { update, feed_id },
+            message: Bytes::new(),
+        }
+    }
+
+    fn signed_checkpoints(vector: &GoldenVector) -> Vec<(u8, SignedCheckpointWithMessageId)> {
+        let checkpoint = CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: U256::from_str(&vector.merkle_tree_hook_address).unwrap(),
+                mailbox_domain: vector.emitter_chain_id,
+                root: vector.checkpoint_root.clone(),
+                index: vector.nonce,
+            },
+            message_id: U256::from_str(&vector.message_id).unwrap(),
+        };
+        vector
+            .signatures
+            .iter()
+            .map(|golden| {
+                let signature = Signature::from_str(&golden.signature).unwrap();
+                (golden.validator_index, SignedCheckpointWithMessageId { value: checkpoint.clone(), signature })
+            })
+            .collect()
+    }
+
+    /// Digest signed by the Hyperlane validators, as verified by the Solidity decoder.
+    fn checkpoint_digest(value: &CheckpointWithMessageId) -> B256 {
+        let checkpoint = &value.checkpoint;
+        let domain_hash = keccak256(
+            [
+                checkpoint.mailbox_domain.to_be_bytes().as_slice(),
+                checkpoint.merkle_tree_hook_address.to_be_bytes::<32>().as_slice(),
+                b"HYPERLANE",
+            ]
+            .concat(),
+        );
+        let root = U256::from_str(&checkpoint.root).unwrap();
+        keccak256(
+            [
+                domain_hash.as_slice(),
+                root.to_be_bytes::<32>().as_slice(),
+                checkpoint.index.to_be_bytes().as_slice(),
+                value.message_id.to_be_bytes::<32>().as_slice(),
+            ]
+            .concat(),
+        )
+    }
+
+    #[test]
+    fn test_golden_vectors() {
+        for vector in golden_vectors() {
+            let update_infos = update_infos(&vector);
+            let signed_checkpoints = signed_checkpoints(&vector);
+
+            // The signatures must be valid for the Solidity decoder to accept the calldata
+            for (validator_index, signed) in &signed_checkpoints {
+                let signer = signed.signature.recover_address_from_msg(checkpoint_digest(&signed.value)).unwrap();
+                let validator = Address::from_str(&vector.validators[*validator_index as usize]).unwrap();
+                assert_eq!(signer, validator, "{}: invalid signature of validator {}", vector.name, validator_index);
+            }
+
+            let calldata = Calldata::from_signed_checkpoints(&update_infos, signed_checkpoints.clone()).unwrap();
+            assert_eq!(hex::encode_prefixed(calldata.as_bytes().unwrap()), vector.calldata, "{}", vector.name);
+
+            let indexes: Vec<u8> = calldata.hyperlane_msg.signatures.iter().map(|s| s.validator_index).collect();
+            assert!(indexes.windows(2).all(|pair| pair[0] < pair[1]), "{}: signatures not sorted", vector.name);
+
+            // The encoding does not depend on the order the signatures were received in
+            let mut reversed = signed_checkpoints;
+            reversed.reverse();
+            let reversed_calldata = Calldata::from_signed_checkpoints(&update_infos, reversed).unwrap();
+            assert_eq!(reversed_calldata, calldata, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_calldata_errors() {
+        let vector = golden_vectors().remove(0);
+        let update_infos = update_infos(&vector);
+
+        assert!(matches!(
+            Calldata::from_signed_checkpoints(&update_infos, vec![]),
+            Err(CalldataError::NoSignatures(nonce)) if nonce == vector.nonce
+        ));
+
+        let mut inconsistent = signed_checkpoints(&vector);
+        inconsistent[0].1.value.message_id = U256::ZERO;
+        assert!(matches!(
+            Calldata::from_signed_checkpoints(&update_infos, inconsistent),
+            Err(CalldataError::InconsistentCheckpoints(_))
+        ));
+
+        let invalid_root = GoldenVector { checkpoint_root: "not a root".into(), ..vector.clone() };
+        assert!(matches!(
+            Calldata::from_signed_checkpoints(&update_infos, signed_checkpoints(&invalid_root)),
+            Err(CalldataError::InvalidCheckpointRoot(root)) if root == "not a root"
+        ));
+    }
+
+    /// Update of the vector & its checkpoints, signed for a delivery through the Hyperlane mailbox.
+    fn mailbox_update(vector: &GoldenVector) -> (DispatchUpdateInfos, Vec<(u8, SignedCheckpointWithMessageId)>) {
+        let mut update_infos = update_infos(vector);
+        update_infos.message = Bytes::from_static(b"hyperlane message");
+
+        let mut signed_checkpoints = signed_checkpoints(vector);
+        for (_, signed) in signed_checkpoints.iter_mut() {
+            signed.value.message_id = keccak256(&update_infos.message).into();
+        }
+        (update_infos, signed_checkpoints)
+    }
+
+    #[test]
+    fn test_mailbox_calldata() {
+        let vector = golden_vectors().remove(1);
+        let (mut update_infos, signed_checkpoints) = mailbox_update(&vector);
+
+        let calldata = MailboxCalldata::from_signed_checkpoints(&update_infos, signed_checkpoints.clone()).unwrap();
+        assert_eq!(calldata.message, update_infos.message);
+
+        let metadata = &calldata.metadata;
+        assert_eq!(metadata.index, vector.nonce);
+        assert_eq!(U256::from_be_bytes(metadata.root.0), U256::from_str(&vector.checkpoint_root).unwrap());
+        // Same signatures & order as the Pragma calldata
+        let pragma_calldata = Calldata::from_signed_checkpoints(&update_infos, signed_checkpoints.clone()).unwrap();
+        let pragma_signatures: Vec<Signature> =
+            pragma_calldata.hyperlane_msg.signatures.iter().map(|s| s.signature).collect();
+        assert_eq!(metadata.signatures, pragma_signatures);
+
+        // The message delivered must be the one signed by the validators
+        update_infos.message = Bytes::from_static(b"another message");
+        assert!(matches!(
+            MailboxCalldata::from_signed_checkpoints(&update_infos, signed_checkpoints),
+            Err(CalldataError::MessageIdMismatch(nonce)) if nonce == vector.nonce
+        ));
+    }
+
+    #[test]
+    fn test_mailbox_calldata_follows_ism_validator_order() {
+        let vector = golden_vectors().remove(0);
+        let (update_infos, signed_checkpoints) = mailbox_update(&vector);
+
+        // The ISM lists the validators in the reverse order of the Pragma contract
+        let ism_validators: Vec<&String> = vector.validators.iter().rev().collect();
+        let ism_index = |pragma_index: u8| {
+            let validator = &vector.validators[pragma_index as usize];
+            ism_validators.iter().position(|ism_validator| *ism_validator == validator).unwrap() as u8
+        };
+        let ism_signed_checkpoints: Vec<(u8, SignedCheckpointWithMessageId)> =
+            signed_checkpoints.iter().map(|(index, signed)| (ism_index(*index), signed.clone())).collect();
+
+        let calldata = MailboxCalldata::from_signed_checkpoints(&update_infos, ism_signed_checkpoints).unwrap();
+        let expected_signatures: Vec<Signature> = (0..ism_validators.len() as u8)
+            .filter_map(|index| signed_checkpoints.iter().find(|(pragma_index, _)| ism_index(*pragma_index) == index))
+            .map(|(_, signed)| signed.signature)
+            .collect();
+        assert_eq!(calldata.metadata.signatures, expected_signatures);
+
+        // Differs from the order of the Pragma contract
+        let pragma_calldata = MailboxCalldata::from_signed_checkpoints(&update_infos, signed_checkpoints).unwrap();
+        assert_ne!(calldata.metadata.signatures, pragma_calldata.metadata.signatures);
+        let mut reversed = pragma_calldata.metadata.signatures;
+        reversed.reverse();
+        assert_eq!(calldata.metadata.signatures, reversed);
     }
 }
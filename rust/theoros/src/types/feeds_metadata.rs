@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use alloy::primitives::U256;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use pragma_feeds::{pair_id::split_pair_id, AssetClass, Feed};
+use pragma_utils::conversions::alloy::hex_str_to_u256;
+
+use crate::configs::{
+    currencies_config::{CurrenciesConfig, CurrencyConfig},
+    feeds_config::FeedsConfig,
+};
+
+/// Informations about a currency, from the currencies configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CurrencyInfo {
+    pub name: String,
+    pub ticker: String,
+    pub decimals: u32,
+    pub coingecko_id: Option<String>,
+}
+
+impl From<&CurrencyConfig> for CurrencyInfo {
+    fn from(currency: &CurrencyConfig) -> Self {
+        Self {
+            name: currency.name.clone(),
+            ticker: currency.ticker.clone(),
+            decimals: currency.decimals,
+            coingecko_id: currency.coingecko_id.clone(),
+        }
+    }
+}
+
+/// A [Feed] enriched with the metadata found in the configuration.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FeedWithMetadata {
+    #[serde(flatten)]
+    pub feed: Feed,
+    /// Human readable name of the feed, e.g "BTC/USD: Spot Median"
+    pub name: Option<String>,
+    pub base_currency: Option<CurrencyInfo>,
+    pub quote_currency: Option<CurrencyInfo>,
+}
+
+/// Inconsistencies between the feeds registered on-chain & the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedMetadataMismatch {
+    /// A feed is registered on-chain but missing from the feeds configuration.
+    MissingFromConfig(String),
+    /// A feed is in the feeds configuration but not registered on-chain.
+    MissingFromRegistry(String),
+    /// A currency of a registered feed is missing from the currencies configuration.
+    UnknownCurrency { feed_id: String, ticker: String },
+    /// A feed id could not be parsed.
+    InvalidFeedId(String),
+}
+
+impl std::fmt::Display for FeedMetadataMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingFromConfig(feed_id) => write!(f, "feed {feed_id} is registered but not in the feeds config"),
+            Self::MissingFromRegistry(feed_id) => write!(f, "feed {feed_id} is in the feeds config but not registered"),
+            Self::UnknownCurrency { feed_id, ticker } => {
+                write!(f, "currency {ticker} of feed {feed_id} is not in the currencies config")
+            }
+            Self::InvalidFeedId(feed_id) => write!(f, "feed {feed_id} could not be parsed"),
+        }
+    }
+}
+
+/// Metadata of the feeds & currencies, loaded from the `feeds.yaml` & `currencies.yaml` configurations.
+#[derive(Debug, Default)]
+pub struct FeedsMetadataRegistry {
+    /// Feed id => name of the feed
+    feed_names: HashMap<U256, String>,
+    /// Ticker => currency
+    currencies: HashMap<String, CurrencyInfo>,
+}
+
+impl FeedsMetadataRegistry {
+    pub fn from_configs(feeds: &FeedsConfig, currencies: &CurrenciesConfig) -> anyhow::Result<Self> {
+        let mut feed_names = HashMap::with_capacity(feeds.feeds.len());
+        for feed in &feeds.feeds {
+            let feed_id =
+                hex_str_to_u256(&feed.id).with_context(|| format!("Invalid feed id in config: {}", feed.id))?;
+            feed_names.insert(feed_id, feed.name.clone());
+        }
+        let currencies = currencies.0.iter().map(|c| (c.ticker.to_uppercase(), CurrencyInfo::from(c))).collect();
+        Ok(Self { feed_names, currencies })
+    }
+
+    /// Loads the registry from the configuration files.
+    /// A missing file is not an error: the registry will just not contain its metadata.
+    pub fn from_files(feeds_path: &str, currencies_path: &str) -> anyhow::Result<Self> {
+        let feeds = if Path::new(feeds_path).exists() {
+            FeedsConfig::from_file(feeds_path)
+                .with_context(|| format!("Failed to load feeds config from path: {}", feeds_path))?
+        } else {
+            tracing::warn!("⚠️ Feeds config not found at path {}, feeds won't have names", feeds_path);
+            FeedsConfig::default()
+        };
+        let currencies = if Path::new(currencies_path).exists() {
+            CurrenciesConfig::from_file(currencies_path)
+                .with_context(|| format!("Failed to load currencies config from path: {}", currencies_path))?
+        } else {
+            tracing::warn!("⚠️ Currencies config not found at path {}, feeds won't have currencies", currencies_path);
+            CurrenciesConfig::default()
+        };
+        Self::from_configs(&feeds, &currencies)
+    }
+
+    /// Returns the name of the feed, if it is in the configuration.
+    pub fn feed_name(&self, feed_id: &str) -> Option<&String> {
+        let feed_id = hex_str_to_u256(feed_id).ok()?;
+        self.feed_names.get(&feed_id)
+    }
+
+    /// Returns the currency for the provided ticker, if it is in the configuration.
+    pub fn currency(&self, ticker: &str) -> Option<&CurrencyInfo> {
+        self.currencies.get(&ticker.to_uppercase())
+    }
+
+    /// Enriches a [Feed] with its name & the informations about its base & quote currencies.
+    pub fn enrich(&self, feed: Feed) -> FeedWithMetadata {
+        let (base, quote) = currency_tickers(&feed);
+        FeedWithMetadata {
+            name: self.feed_name(&feed.feed_id).cloned(),
+            base_currency: base.and_then(|ticker| self.currency(ticker)).cloned(),
+            quote_currency: quote.and_then(|ticker| self.currency(ticker)).cloned(),
+            feed,
+        }
+    }
+
+    /// Compares the feeds registered on-chain with the configuration & returns
+    /// all the inconsistencies found.
+    pub fn find_mismatches(&self, registered_feed_ids: &[String]) -> Vec<FeedMetadataMismatch> {
+        let mut mismatches = Vec::new();
+        let mut registered = Vec::with_capacity(registered_feed_ids.len());
+
+        for feed_id in registered_feed_ids {
+            let Ok(id) = hex_str_to_u256(feed_id) else {
+                mismatches.push(FeedMetadataMismatch::InvalidFeedId(feed_id.clone()));
+                continue;
+            };
+            registered.push(id);
+
+            if !self.feed_names.contains_key(&id) {
+                mismatches.push(FeedMetadataMismatch::MissingFromConfig(feed_id.clone()));
+            }
+
+            let Ok(feed) = feed_id.parse::<Feed>() else {
+                mismatches.push(FeedMetadataMismatch::InvalidFeedId(feed_id.clone()));
+                continue;
+            };
+            let (base, quote) = currency_tickers(&feed);
+            for ticker in [base, quote].into_iter().flatten() {
+                if self.currency(ticker).is_none() {
+                    mismatches.push(FeedMetadataMismatch::UnknownCurrency {
+                        feed_id: feed_id.clone(),
+                        ticker: ticker.to_owned(),
+                    });
+                }
+            }
+        }
+
+        let mut config_only: Vec<&U256> = self.feed_names.keys().filter(|id| !registered.contains(*id)).collect();
+        config_only.sort();
+        for feed_id in config_only {
+            mismatches.push(FeedMetadataMismatch::MissingFromRegistry(format!("{:#x}", feed_id)));
+        }
+
+        mismatches
+    }
+}
+
+/// Returns the tickers of the base & quote currencies of the feed.
+/// The base of an equity is its ISIN, not a currency.
+fn currency_tickers(feed: &Feed) -> (Option<&str>, Option<&str>) {
+    match split_pair_id(&feed.pair_id) {
+        Some((_, quote)) if feed.asset_class == AssetClass::Equities => (None, Some(quote)),
+        Some((base, quote)) => (Some(base), Some(quote)),
+        None => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEEDS_YAML: &str = r#"
+feeds:
+  - name: "BTC/USD: Spot Median"
+    id: "0x4254432f555344"
+  - name: "ETH/USD: Spot Median"
+    id: "0x4554482f555344"
+"#;
+
+    const CURRENCIES_YAML: &str = r#"
+- name: "Bitcoin"
+  decimals: 8
+  ticker: "BTC"
+  coingecko_id: "bitcoin"
+- name: "US Dollar"
+  decimals: 8
+  ticker: "USD"
+  abstract: true
+"#;
+
+    fn registry() -> FeedsMetadataRegistry {
+        let feeds: FeedsConfig = serde_yaml::from_str(FEEDS_YAML).unwrap();
+        let currencies: CurrenciesConfig = serde_yaml::from_str(CURRENCIES_YAML).unwrap();
+        FeedsMetadataRegistry::from_configs(&feeds, &currencies).unwrap()
+    }
+
+    #[test]
+    fn test_enrich_feed() {
+        let registry = registry();
+        let feed: Feed = "0x4254432f555344".parse().unwrap();
+
+        let enriched = registry.enrich(feed);
+
+        assert_eq!(enriched.name.as_deref(), Some("BTC/USD: Spot Median"));
+        assert_eq!(enriched.base_currency.unwrap().coingecko_id.as_deref(), Some("bitcoin"));
+        assert_eq!(enriched.quote_currency.unwrap().decimals, 8);
+    }
+
+    #[test]
+    fn test_find_mismatches() {
+        let registry = registry();
+        let registered = vec!["0x4254432f555344".to_string(), "0x534f4c2f555344".to_string()];
+
+        let mismatches = registry.find_mismatches(&registered);
+
+        assert_eq!(
+            mismatches,
+            vec![
+                FeedMetadataMismatch::MissingFromConfig("0x534f4c2f555344".into()),
+                FeedMetadataMismatch::UnknownCurrency { feed_id: "0x534f4c2f555344".into(), ticker: "SOL".into() },
+                FeedMetadataMismatch::MissingFromRegistry("0x4554482f555344".into()),
+            ]
+        );
+    }
+}
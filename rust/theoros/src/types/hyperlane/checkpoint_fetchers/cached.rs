@@ -0,0 +1,296 @@
+use std::{
+    fmt,
+    mem::size_of,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use prometheus::{IntCounterVec, Opts, Registry};
+
+use crate::types::hyperlane::{FetchFromStorage, SignedCheckpointWithMessageId};
+
+/// Settings of the [CachedStorage].
+#[derive(Debug, Clone, Copy)]
+pub struct FetchCacheConfig {
+    /// How long a "not signed yet" result is cached.
+    pub not_signed_ttl: Duration,
+    /// How long the latest index of a validator is cached.
+    pub latest_index_ttl: Duration,
+}
+
+/// Kind of the cached entries, used as metrics label.
+#[derive(Debug, Clone, Copy, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+enum CacheEntryKind {
+    Checkpoint,
+    NotSigned,
+    LatestIndex,
+}
+
+/// Prometheus metrics of the checkpoints fetch cache, showing the requests saved.
+#[derive(Clone)]
+pub struct FetchCacheMetrics {
+    hits: IntCounterVec,
+    misses: IntCounterVec,
+}
+
+impl FetchCacheMetrics {
+    pub fn register(registry: &Registry) -> anyhow::Result<Self> {
+        let hits = IntCounterVec::new(
+            Opts::new("theoros_checkpoints_cache_hits_total", "Checkpoint storage requests served from the cache"),
+            &["kind"],
+        )?;
+        let misses = IntCounterVec::new(
+            Opts::new("theoros_checkpoints_cache_misses_total", "Checkpoint storage requests sent to the storage"),
+            &["kind"],
+        )?;
+
+        registry.register(Box::new(hits.clone()))?;
+        registry.register(Box::new(misses.clone()))?;
+
+        Ok(Self { hits, misses })
+    }
+
+    fn hit(&self, kind: CacheEntryKind) {
+        self.hits.with_label_values(&[&kind.to_string()]).inc();
+    }
+
+    fn miss(&self, kind: CacheEntryKind) {
+        self.misses.with_label_values(&[&kind.to_string()]).inc();
+    }
+}
+
+/// Caching decorator of a [FetchFromStorage]:
+/// - signed checkpoints are immutable & cached until they are pruned by the retention service,
+/// - nonces not signed yet are cached for [FetchCacheConfig::not_signed_ttl],
+/// - the latest index is cached for [FetchCacheConfig::latest_index_ttl].
+///
+/// Errors are never cached.
+pub struct CachedStorage {
+    inner: Arc<dyn FetchFromStorage + Send + Sync>,
+    config: FetchCacheConfig,
+    metrics: FetchCacheMetrics,
+    checkpoints: DashMap<u32, SignedCheckpointWithMessageId>,
+    /// Nonces not signed yet, with the instant their entry expires.
+    not_signed: DashMap<u32, Instant>,
+    /// Latest index, with the instant it expires.
+    latest_index: Mutex<Option<(Option<u32>, Instant)>>,
+}
+
+impl CachedStorage {
+    pub fn new(
+        inner: Arc<dyn FetchFromStorage + Send + Sync>,
+        config: FetchCacheConfig,
+        metrics: FetchCacheMetrics,
+    ) -> Self {
+        Self {
+            inner,
+            config,
+            metrics,
+            checkpoints: DashMap::new(),
+            not_signed: DashMap::new(),
+            latest_index: Mutex::new(None),
+        }
+    }
+
+    /// Removes the "not signed yet" entries that expired.
+    fn evict_expired_not_signed(&self, now: Instant) {
+        self.not_signed.retain(|_, expires_at| now < *expires_at);
+    }
+
+    fn cached_latest_index(&self, now: Instant) -> Option<Option<u32>> {
+        let cached = self.latest_index.lock().expect("poisoned lock");
+        cached.filter(|(_, expires_at)| now < *expires_at).map(|(index, _)| index)
+    }
+}
+
+impl fmt::Debug for FetchCacheMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FetchCacheMetrics").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for CachedStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedStorage")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .field("checkpoints", &self.checkpoints.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl FetchFromStorage for CachedStorage {
+    async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        if let Some(checkpoint) = self.checkpoints.get(&index) {
+            self.metrics.hit(CacheEntryKind::Checkpoint);
+            return Ok(Some(checkpoint.clone()));
+        }
+        let now = Instant::now();
+        if self.not_signed.get(&index).is_some_and(|expires_at| now < *expires_at) {
+            self.metrics.hit(CacheEntryKind::NotSigned);
+            return Ok(None);
+        }
+
+        self.metrics.miss(CacheEntryKind::Checkpoint);
+        let checkpoint = self.inner.fetch(index).await?;
+        match &checkpoint {
+            Some(checkpoint) => {
+                self.not_signed.remove(&index);
+                self.checkpoints.insert(index, checkpoint.clone());
+            }
+            None => {
+                self.evict_expired_not_signed(now);
+                self.not_signed.insert(index, now + self.config.not_signed_ttl);
+            }
+        }
+        Ok(checkpoint)
+    }
+
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let now = Instant::now();
+        if let Some(latest_index) = self.cached_latest_index(now) {
+            self.metrics.hit(CacheEntryKind::LatestIndex);
+            return Ok(latest_index);
+        }
+
+        self.metrics.miss(CacheEntryKind::LatestIndex);
+        let latest_index = self.inner.latest_index().await?;
+        *self.latest_index.lock().expect("poisoned lock") = Some((latest_index, now + self.config.latest_index_ttl));
+        Ok(latest_index)
+    }
+
+    fn announcement_location(&self) -> String {
+        self.inner.announcement_location()
+    }
+
+    /// Removes the cached entries of the nonces below the provided one, that won't be fetched anymore.
+    fn prune_cache_below(&self, index: u32) -> usize {
+        let mut pruned = 0;
+        self.checkpoints.retain(|cached_index, _| {
+            let keep = *cached_index >= index;
+            if !keep {
+                pruned += 1;
+            }
+            keep
+        });
+        self.not_signed.retain(|cached_index, _| *cached_index >= index);
+        pruned
+    }
+
+    fn cache_size(&self) -> (usize, usize) {
+        let entries = self.checkpoints.len() + self.not_signed.len();
+        let size = self.checkpoints.capacity() * size_of::<(u32, SignedCheckpointWithMessageId)>()
+            + self.checkpoints.iter().map(|entry| entry.value().value.checkpoint.root.capacity()).sum::<usize>()
+            + self.not_signed.capacity() * size_of::<(u32, Instant)>();
+        (entries, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::test_utils::signed_checkpoint;
+
+    /// Storage where only the nonces up to `latest_index` are signed, counting the requests.
+    #[derive(Debug, Default)]
+    struct MockStorage {
+        latest_index: u32,
+        requests: AtomicU32,
+    }
+
+    #[async_trait]
+    impl FetchFromStorage for MockStorage {
+        async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+            self.requests.fetch_add(1, Ordering::Relaxed);
+            Ok((index <= self.latest_index).then(|| signed_checkpoint(index)))
+        }
+
+        async fn latest_index(&self) -> Result<Option<u32>> {
+            self.requests.fetch_add(1, Ordering::Relaxed);
+            Ok(Some(self.latest_index))
+        }
+
+        fn announcement_location(&self) -> String {
+            "mock://".into()
+        }
+    }
+
+    fn cached_storage(inner: Arc<MockStorage>, config: FetchCacheConfig) -> CachedStorage {
+        let metrics = FetchCacheMetrics::register(&Registry::new()).unwrap();
+        CachedStorage::new(inner, config, metrics)
+    }
+
+    #[tokio::test]
+    async fn test_cached_storage() {
+        let inner = Arc::new(MockStorage { latest_index: 3, ..Default::default() });
+        let config =
+            FetchCacheConfig { not_signed_ttl: Duration::from_secs(3600), latest_index_ttl: Duration::from_secs(3600) };
+        let storage = cached_storage(inner.clone(), config);
+
+        for _ in 0..3 {
+            assert_eq!(storage.fetch(2).await.unwrap(), Some(signed_checkpoint(2)));
+            assert_eq!(storage.fetch(5).await.unwrap(), None);
+            assert_eq!(storage.latest_index().await.unwrap(), Some(3));
+        }
+        assert_eq!(inner.requests.load(Ordering::Relaxed), 3);
+        assert_eq!(storage.metrics.hits.with_label_values(&["checkpoint"]).get(), 2);
+        assert_eq!(storage.metrics.hits.with_label_values(&["not_signed"]).get(), 2);
+        assert_eq!(storage.metrics.hits.with_label_values(&["latest_index"]).get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cached_storage_expires_negative_entries() {
+        let inner = Arc::new(MockStorage { latest_index: 3, ..Default::default() });
+        let config = FetchCacheConfig { not_signed_ttl: Duration::ZERO, latest_index_ttl: Duration::ZERO };
+        let storage = cached_storage(inner.clone(), config);
+
+        for _ in 0..3 {
+            assert_eq!(storage.fetch(2).await.unwrap(), Some(signed_checkpoint(2)));
+            assert_eq!(storage.fetch(5).await.unwrap(), None);
+            assert_eq!(storage.latest_index().await.unwrap(), Some(3));
+        }
+        // Only the signed checkpoint is served from the cache
+        assert_eq!(inner.requests.load(Ordering::Relaxed), 7);
+    }
+
+    #[tokio::test]
+    async fn test_cached_storage_evicts_expired_negative_entries() {
+        let inner = Arc::new(MockStorage { latest_index: 3, ..Default::default() });
+        let config = FetchCacheConfig { not_signed_ttl: Duration::ZERO, latest_index_ttl: Duration::ZERO };
+        let storage = cached_storage(inner, config);
+
+        for nonce in 4..10 {
+            assert_eq!(storage.fetch(nonce).await.unwrap(), None);
+        }
+        // Only the entry just inserted is left
+        assert_eq!(storage.not_signed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cached_storage_prune_cache_below() {
+        let inner = Arc::new(MockStorage { latest_index: 3, ..Default::default() });
+        let config =
+            FetchCacheConfig { not_signed_ttl: Duration::from_secs(3600), latest_index_ttl: Duration::from_secs(3600) };
+        let storage = cached_storage(inner.clone(), config);
+
+        for nonce in 0..6 {
+            storage.fetch(nonce).await.unwrap();
+        }
+        assert_eq!(storage.prune_cache_below(2), 2);
+        assert_eq!(storage.checkpoints.len(), 2);
+        assert_eq!(storage.prune_cache_below(5), 2);
+        assert!(storage.checkpoints.is_empty());
+        assert_eq!(storage.not_signed.len(), 1);
+
+        // Pruned nonces are fetched from the storage again
+        assert_eq!(storage.fetch(1).await.unwrap(), Some(signed_checkpoint(1)));
+        assert_eq!(inner.requests.load(Ordering::Relaxed), 7);
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use crate::types::hyperlane::{FetchFromStorage, SignedCheckpointWithMessageId};
+
+/// Fetches the checkpoints of a validator from a list of storages by priority:
+/// the next storage (mirror) is only queried when the previous ones failed or don't have the checkpoint.
+/// The latest index is the highest one among all the storages, since a mirror can be ahead of the primary.
+#[derive(Debug)]
+pub struct FallbackStorage {
+    storages: Vec<Arc<dyn FetchFromStorage + Send + Sync>>,
+}
+
+impl FallbackStorage {
+    /// Creates a new FallbackStorage, the first storage being the primary one.
+    pub fn new(storages: Vec<Arc<dyn FetchFromStorage + Send + Sync>>) -> Self {
+        Self { storages }
+    }
+}
+
+#[async_trait]
+impl FetchFromStorage for FallbackStorage {
+    /// Returns the checkpoint from the first storage that has it. The checkpoint is only considered
+    /// missing if at least one storage answered without it, otherwise the last error is returned.
+    async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let mut has_answered = false;
+        let mut last_error = None;
+        for storage in &self.storages {
+            match storage.fetch(index).await {
+                Ok(Some(checkpoint)) => return Ok(Some(checkpoint)),
+                Ok(None) => has_answered = true,
+                Err(e) => {
+                    tracing::warn!(
+                        "⚠️ Could not fetch checkpoint {} from {}: {:?}",
+                        index,
+                        storage.announcement_location(),
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        if has_answered {
+            return Ok(None);
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No storage to fetch the checkpoint {} from", index)))
+    }
+
+    /// Queries every storage & returns the highest latest index, failing only if all the storages failed.
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let results = futures::future::join_all(self.storages.iter().map(|storage| storage.latest_index())).await;
+
+        let mut has_answered = false;
+        let mut latest_index = None;
+        let mut last_error = None;
+        for (storage, result) in self.storages.iter().zip(results) {
+            match result {
+                Ok(index) => {
+                    has_answered = true;
+                    latest_index = latest_index.max(index);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "⚠️ Could not fetch the latest index from {}: {:?}",
+                        storage.announcement_location(),
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        if has_answered {
+            return Ok(latest_index);
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No storage to fetch the latest index from")))
+    }
+
+    fn announcement_location(&self) -> String {
+        self.storages.first().map(|storage| storage.announcement_location()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use super::*;
+    use crate::test_utils::signed_checkpoint;
+
+    /// Storage where the checkpoints up to its latest index are signed, or failing if it has none.
+    #[derive(Debug)]
+    struct MockStorage(Option<u32>);
+
+    #[async_trait]
+    impl FetchFromStorage for MockStorage {
+        async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+            match self.0 {
+                Some(latest_index) => Ok((index <= latest_index).then(|| signed_checkpoint(index))),
+                None => bail!("storage unavailable"),
+            }
+        }
+
+        async fn latest_index(&self) -> Result<Option<u32>> {
+            match self.0 {
+                Some(index) => Ok(Some(index)),
+                None => bail!("storage unavailable"),
+            }
+        }
+
+        fn announcement_location(&self) -> String {
+            format!("mock://{:?}", self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_storage_latest_index_is_the_highest() {
+        let storage = FallbackStorage::new(vec![
+            Arc::new(MockStorage(None)),
+            Arc::new(MockStorage(Some(7))),
+            Arc::new(MockStorage(Some(4))),
+        ]);
+        assert_eq!(storage.latest_index().await.unwrap(), Some(7));
+        assert_eq!(storage.announcement_location(), "mock://None");
+
+        let storage = FallbackStorage::new(vec![Arc::new(MockStorage(None)), Arc::new(MockStorage(None))]);
+        assert!(storage.latest_index().await.is_err());
+
+        let storage = FallbackStorage::new(vec![]);
+        assert!(storage.latest_index().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_storage_fetches_from_mirror_when_primary_misses_checkpoint() {
+        let storage = FallbackStorage::new(vec![Arc::new(MockStorage(Some(2))), Arc::new(MockStorage(Some(5)))]);
+        assert_eq!(storage.fetch(1).await.unwrap(), Some(signed_checkpoint(1)));
+        assert_eq!(storage.fetch(4).await.unwrap(), Some(signed_checkpoint(4)));
+        assert_eq!(storage.fetch(6).await.unwrap(), None);
+
+        // A storage answering without the checkpoint is not an error
+        let storage = FallbackStorage::new(vec![Arc::new(MockStorage(Some(2))), Arc::new(MockStorage(None))]);
+        assert_eq!(storage.fetch(4).await.unwrap(), None);
+
+        let storage = FallbackStorage::new(vec![Arc::new(MockStorage(None)), Arc::new(MockStorage(None))]);
+        assert!(storage.fetch(1).await.is_err());
+    }
+}
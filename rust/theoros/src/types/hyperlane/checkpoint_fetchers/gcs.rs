@@ -1,56 +1,92 @@
 // Source:
 // https://github.com/hyperlane-xyz/hyperlane-monorepo/blob/3e90734310fb1ca9a607ce3d334015fa7aaa9208/rust/hyperlane-base/src/types/gcs_storage.rs#L63
-use std::fmt;
+use std::{fmt, time::Duration};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use async_trait::async_trait;
-use ya_gcp::{storage::StorageClient, AuthFlow, ClientBuilder, ClientBuilderConfig};
+use reqwest::StatusCode as HttpStatusCode;
+use tokio::time::timeout;
+use url::Url;
+use ya_gcp::{
+    storage::{
+        api::{error::HttpStatusError, http::StatusCode, Error},
+        ObjectError, StorageClient,
+    },
+    AuthFlow, ClientBuilder, ClientBuilderConfig,
+};
 
 use crate::types::hyperlane::{FetchFromStorage, SignedCheckpointWithMessageId};
 
-const ANNOUNCEMENT_KEY: &str = "gcsAnnouncementKey";
-
 /// Path to GCS users_secret file
 pub const GCS_USER_SECRET: &str = "GCS_USER_SECRET";
 /// Path to GCS Service account key
 pub const GCS_SERVICE_ACCOUNT_KEY: &str = "GCS_SERVICE_ACCOUNT_KEY";
+/// Endpoint of a GCS compatible server (e.g a fake-gcs emulator) replacing Google's.
+/// Same variable as the one used by the official Google Cloud SDKs.
+pub const GCS_EMULATOR_HOST: &str = "STORAGE_EMULATOR_HOST";
+
+/// The timeout for GCS requests.
+const GCS_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[derive(Debug)]
 pub struct GcsStorageClientBuilder {
     auth: AuthFlow,
+    endpoint: Option<Url>,
 }
 
 impl GcsStorageClientBuilder {
     /// Creates a new [GcsStorageClientBuilder].
     pub fn new(auth: AuthFlow) -> Self {
-        GcsStorageClientBuilder { auth }
+        GcsStorageClientBuilder { auth, endpoint: None }
+    }
+
+    /// Sends the requests to a custom GCS compatible endpoint instead of Google's.
+    /// Requests to custom endpoints are not authenticated.
+    pub fn endpoint(mut self, endpoint: Option<Url>) -> Self {
+        self.endpoint = endpoint;
+        self
     }
 
     /// Builds a [GcsStorageClient].
     pub async fn build(self, bucket_name: impl Into<String>, folder: Option<String>) -> Result<GcsStorageClient> {
-        let inner = ClientBuilder::new(ClientBuilderConfig::new().auth_flow(self.auth)).await?.build_storage_client();
-        let bucket = if let Some(folder) = folder {
-            format! {"{}/{}", bucket_name.into(), folder}
-        } else {
-            bucket_name.into()
+        let inner = match self.endpoint {
+            Some(endpoint) => {
+                if !matches!(self.auth, AuthFlow::NoAuth) {
+                    tracing::warn!("⚠️ GCS credentials are ignored for the custom endpoint {}", endpoint);
+                }
+                let client = reqwest::Client::builder().timeout(GCS_REQUEST_TIMEOUT).build()?;
+                GcsBackend::Endpoint { client, endpoint }
+            }
+            None => GcsBackend::Google(
+                ClientBuilder::new(ClientBuilderConfig::new().auth_flow(self.auth)).await?.build_storage_client(),
+            ),
         };
 
-        Ok(GcsStorageClient { inner, bucket })
+        Ok(GcsStorageClient { inner, bucket: bucket_name.into(), folder })
     }
 }
 
+/// Where the GCS requests are sent.
+enum GcsBackend {
+    /// Google Cloud Storage, through the authenticated `ya-gcp` client.
+    Google(StorageClient),
+    /// A GCS compatible JSON API, e.g a fake-gcs server, accessed anonymously.
+    Endpoint { client: reqwest::Client, endpoint: Url },
+}
+
 /// Google Cloud Storage client
 /// Enables use of any of service account key OR user secrets to authenticate
 /// For anonymous access to public data provide `(None, None)` to Builder
 pub struct GcsStorageClient {
     // GCS storage client
     // # Details: <https://docs.rs/ya-gcp/latest/ya_gcp/storage/struct.StorageClient.html>
-    inner: StorageClient,
+    inner: GcsBackend,
     // bucket name of this client's storage
     bucket: String,
+    // A specific folder inside the above bucket - defaults to the root of the bucket
+    folder: Option<String>,
 }
 
-#[allow(unused)]
 impl GcsStorageClient {
     fn get_checkpoint_key(index: u32) -> String {
         format!("checkpoint_{index}_with_id.json")
@@ -59,22 +95,116 @@ impl GcsStorageClient {
     fn get_latest_checkpoint_key() -> String {
         "checkpoint_latest_index.json".to_string()
     }
+
+    /// Reads an object of the bucket, returning `None` if it does not exist.
+    async fn read_object(&self, key: String) -> Result<Option<Vec<u8>>> {
+        let object_key = composite_key(self.folder.as_deref(), key);
+        match &self.inner {
+            GcsBackend::Google(client) => {
+                let get_object_result =
+                    timeout(GCS_REQUEST_TIMEOUT, client.get_object(&self.bucket, object_key)).await?;
+                match get_object_result {
+                    Ok(data) => Ok(Some(data.to_vec())),
+                    Err(ObjectError::Failure(Error::HttpStatus(HttpStatusError(StatusCode::NOT_FOUND)))) => Ok(None),
+                    Err(e) => bail!(e),
+                }
+            }
+            GcsBackend::Endpoint { client, endpoint } => {
+                let url = object_media_url(endpoint, &self.bucket, &object_key)?;
+                let response = client.get(url).send().await?;
+                if response.status() == HttpStatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+                Ok(Some(response.error_for_status()?.bytes().await?.to_vec()))
+            }
+        }
+    }
 }
 
 #[async_trait]
 impl FetchFromStorage for GcsStorageClient {
     async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
-        let res = self.inner.get_object(&self.bucket, GcsStorageClient::get_checkpoint_key(index)).await?;
-        Ok(Some(serde_json::from_slice(res.as_ref())?))
+        self.read_object(GcsStorageClient::get_checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        self.read_object(GcsStorageClient::get_latest_checkpoint_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
     }
 
     fn announcement_location(&self) -> String {
-        format!("gs://{}/{}", &self.bucket, ANNOUNCEMENT_KEY)
+        match self.folder.as_deref() {
+            None | Some("") => format!("gs://{}", self.bucket),
+            Some(folder) => format!("gs://{}/{}", self.bucket, folder),
+        }
     }
 }
 
 impl fmt::Debug for GcsStorageClient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("S3Storage").field("bucket", &self.bucket).finish()
+        let endpoint = match &self.inner {
+            GcsBackend::Google(_) => None,
+            GcsBackend::Endpoint { endpoint, .. } => Some(endpoint),
+        };
+        f.debug_struct("GcsStorageClient")
+            .field("bucket", &self.bucket)
+            .field("folder", &self.folder)
+            .field("endpoint", &endpoint)
+            .finish()
+    }
+}
+
+/// Prefixes the key with the folder, if any.
+fn composite_key(folder: Option<&str>, key: String) -> String {
+    match folder.map(|folder| folder.trim_matches('/')) {
+        None | Some("") => key,
+        Some(folder) => format!("{}/{}", folder, key),
+    }
+}
+
+/// URL downloading the content of an object through the GCS JSON API, i.e:
+/// `{endpoint}/storage/v1/b/{bucket}/o/{object}?alt=media`.
+fn object_media_url(endpoint: &Url, bucket: &str, object_key: &str) -> Result<Url> {
+    let mut url = endpoint.clone();
+    url.path_segments_mut()
+        .map_err(|_| anyhow!("Invalid GCS endpoint {}", endpoint))?
+        .pop_if_empty()
+        // Object names are a single path segment: their slashes are percent-encoded
+        .extend(["storage", "v1", "b", bucket, "o", object_key]);
+    url.query_pairs_mut().append_pair("alt", "media");
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composite_key() {
+        assert_eq!(composite_key(None, "index.json".into()), "index.json");
+        assert_eq!(composite_key(Some(""), "index.json".into()), "index.json");
+        assert_eq!(composite_key(Some("validator"), "index.json".into()), "validator/index.json");
+        assert_eq!(composite_key(Some("/a/b/"), "index.json".into()), "a/b/index.json");
+    }
+
+    #[test]
+    fn test_object_media_url() {
+        let endpoint = Url::parse("http://localhost:4443").unwrap();
+        let url = object_media_url(&endpoint, "bucket", "validator/checkpoint_1_with_id.json").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:4443/storage/v1/b/bucket/o/validator%2Fcheckpoint_1_with_id.json?alt=media"
+        );
+
+        let endpoint = Url::parse("http://localhost:4443/prefix/").unwrap();
+        let url = object_media_url(&endpoint, "bucket", "index.json").unwrap();
+        assert_eq!(url.as_str(), "http://localhost:4443/prefix/storage/v1/b/bucket/o/index.json?alt=media");
     }
 }
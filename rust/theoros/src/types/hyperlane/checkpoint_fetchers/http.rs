@@ -0,0 +1,228 @@
+use std::{sync::Mutex, time::Duration};
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use reqwest::{
+    header::{ETAG, IF_NONE_MATCH},
+    StatusCode,
+};
+use url::Url;
+
+use crate::types::hyperlane::{FetchFromStorage, SignedCheckpointWithMessageId};
+
+/// The timeout for HTTP requests.
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Latest index of the validator, with the ETag returned by the server.
+#[derive(Debug, Clone)]
+struct CachedLatestIndex {
+    etag: String,
+    index: u32,
+}
+
+/// Result of a GET request on a checkpoint object.
+enum HttpObject {
+    Found { data: Vec<u8>, etag: Option<String> },
+    NotModified,
+    NotFound,
+}
+
+/// Type for reading checkpoints published behind a plain HTTP(S) server or CDN.
+/// Objects follow the same key layout as the S3 buckets, relative to the base URL.
+#[derive(Debug)]
+pub struct HttpStorage {
+    client: reqwest::Client,
+    /// URL of the folder containing the checkpoints.
+    base_url: Url,
+    /// The latest index is polled continuously, so it is only downloaded again
+    /// when its ETag changes. Checkpoints are immutable & fetched once.
+    latest_index: Mutex<Option<CachedLatestIndex>>,
+}
+
+impl HttpStorage {
+    /// Creates a new HttpStorage.
+    pub fn new(base_url: Url) -> Result<Self> {
+        let client = reqwest::Client::builder().timeout(HTTP_REQUEST_TIMEOUT).build()?;
+        Ok(HttpStorage { client, base_url, latest_index: Default::default() })
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn latest_index_key() -> String {
+        "checkpoint_latest_index.json".to_owned()
+    }
+
+    /// Sends a GET request for the object, conditioned on the provided ETag if any.
+    async fn read_object(&self, key: &str, etag: Option<&str>) -> Result<HttpObject> {
+        let mut request = self.client.get(object_url(&self.base_url, key)?);
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(HttpObject::NotFound),
+            StatusCode::NOT_MODIFIED => Ok(HttpObject::NotModified),
+            _ => {
+                let response = response.error_for_status()?;
+                let etag = response.headers().get(ETAG).and_then(|etag| etag.to_str().ok()).map(String::from);
+                let data = response.bytes().await?.to_vec();
+                Ok(HttpObject::Found { data, etag })
+            }
+        }
+    }
+
+    fn cached_latest_index(&self) -> Option<CachedLatestIndex> {
+        self.latest_index.lock().expect("poisoned lock").clone()
+    }
+
+    fn set_cached_latest_index(&self, cached: Option<CachedLatestIndex>) {
+        *self.latest_index.lock().expect("poisoned lock") = cached;
+    }
+}
+
+#[async_trait]
+impl FetchFromStorage for HttpStorage {
+    async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        match self.read_object(&HttpStorage::checkpoint_key(index), None).await? {
+            HttpObject::Found { data, .. } => Ok(Some(serde_json::from_slice(&data)?)),
+            HttpObject::NotFound => Ok(None),
+            HttpObject::NotModified => bail!("Unexpected \"304 Not Modified\" for checkpoint {index}"),
+        }
+    }
+
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let cached = self.cached_latest_index();
+        let etag = cached.as_ref().map(|cached| cached.etag.as_str());
+
+        match self.read_object(&HttpStorage::latest_index_key(), etag).await? {
+            HttpObject::Found { data, etag } => {
+                let index = serde_json::from_slice(&data)?;
+                self.set_cached_latest_index(etag.map(|etag| CachedLatestIndex { etag, index }));
+                Ok(Some(index))
+            }
+            HttpObject::NotModified => {
+                let cached = cached.ok_or_else(|| anyhow!("Unexpected \"304 Not Modified\" for the latest index"))?;
+                Ok(Some(cached.index))
+            }
+            HttpObject::NotFound => {
+                self.set_cached_latest_index(None);
+                Ok(None)
+            }
+        }
+    }
+
+    fn announcement_location(&self) -> String {
+        self.base_url.to_string()
+    }
+}
+
+/// URL of an object, relative to the base URL of the storage.
+fn object_url(base_url: &Url, key: &str) -> Result<Url> {
+    let mut url = base_url.clone();
+    url.path_segments_mut().map_err(|_| anyhow!("Invalid storage URL {}", base_url))?.pop_if_empty().push(key);
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use axum::{
+        extract::State,
+        http::HeaderMap,
+        response::{IntoResponse, Response},
+        routing::get,
+        Json, Router,
+    };
+
+    use super::*;
+    use crate::test_utils::signed_checkpoint;
+
+    /// Validator storage served over HTTP, where only the checkpoint #1 is published.
+    #[derive(Clone, Default)]
+    struct MockServer {
+        latest_index: Arc<Mutex<Option<u32>>>,
+        not_modified_responses: Arc<AtomicU32>,
+    }
+
+    async fn serve_latest_index(State(server): State<MockServer>, headers: HeaderMap) -> Response {
+        let Some(index) = *server.latest_index.lock().unwrap() else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+        let etag = format!("\"{index}\"");
+        if headers.get(IF_NONE_MATCH).is_some_and(|value| value == etag.as_str()) {
+            server.not_modified_responses.fetch_add(1, Ordering::Relaxed);
+            return StatusCode::NOT_MODIFIED.into_response();
+        }
+        ([(ETAG, etag)], index.to_string()).into_response()
+    }
+
+    /// Starts the server on a random port & returns the URL of the validator storage.
+    async fn start_mock_server(server: MockServer) -> Url {
+        let app = Router::new()
+            .route("/validator/checkpoint_latest_index.json", get(serve_latest_index))
+            .route("/validator/checkpoint_1_with_id.json", get(|| async { Json(signed_checkpoint(1)) }))
+            .with_state(server);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Url::parse(&format!("http://{address}/validator")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_http_storage_against_mock_server() {
+        let server = MockServer::default();
+        let storage = HttpStorage::new(start_mock_server(server.clone()).await).unwrap();
+
+        // 404: nothing signed yet
+        assert_eq!(storage.latest_index().await.unwrap(), None);
+        assert!(storage.fetch(0).await.unwrap().is_none());
+
+        // 200: the latest index is downloaded with its ETag
+        *server.latest_index.lock().unwrap() = Some(1);
+        assert_eq!(storage.latest_index().await.unwrap(), Some(1));
+        assert_eq!(server.not_modified_responses.load(Ordering::Relaxed), 0);
+        assert_eq!(storage.fetch(1).await.unwrap(), Some(signed_checkpoint(1)));
+
+        // 304: the ETag didn't change, the cached index is returned
+        assert_eq!(storage.latest_index().await.unwrap(), Some(1));
+        assert_eq!(server.not_modified_responses.load(Ordering::Relaxed), 1);
+
+        // 200: a new index is published with a new ETag
+        *server.latest_index.lock().unwrap() = Some(2);
+        assert_eq!(storage.latest_index().await.unwrap(), Some(2));
+        assert_eq!(server.not_modified_responses.load(Ordering::Relaxed), 1);
+        assert!(storage.fetch(2).await.unwrap().is_none());
+
+        // 404: the cached index is forgotten once the object is removed
+        *server.latest_index.lock().unwrap() = None;
+        assert_eq!(storage.latest_index().await.unwrap(), None);
+        assert!(storage.cached_latest_index().is_none());
+    }
+
+    #[test]
+    fn test_object_url() {
+        let key = HttpStorage::checkpoint_key(42);
+
+        let base_url = Url::parse("https://cdn.example.com").unwrap();
+        assert_eq!(object_url(&base_url, &key).unwrap().as_str(), "https://cdn.example.com/checkpoint_42_with_id.json");
+
+        let base_url = Url::parse("https://cdn.example.com/validator/").unwrap();
+        assert_eq!(
+            object_url(&base_url, &key).unwrap().as_str(),
+            "https://cdn.example.com/validator/checkpoint_42_with_id.json"
+        );
+
+        let base_url = Url::parse("http://localhost:8080/a/b").unwrap();
+        assert_eq!(
+            object_url(&base_url, &HttpStorage::latest_index_key()).unwrap().as_str(),
+            "http://localhost:8080/a/b/checkpoint_latest_index.json"
+        );
+    }
+}
@@ -1,9 +1,13 @@
 // Source:
 // https://github.com/hyperlane-xyz/hyperlane-monorepo/blob/3e90734310fb1ca9a607ce3d334015fa7aaa9208/rust/hyperlane-base/src/types/local_storage.rs#L51
-use std::path::PathBuf;
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 
 use crate::types::hyperlane::{FetchFromStorage, SignedCheckpointWithMessageId};
 
@@ -26,19 +30,60 @@ impl LocalStorage {
     fn checkpoint_file_path(&self, index: u32) -> PathBuf {
         self.path.join(format!("{}_with_id.json", index))
     }
+
+    fn latest_index_file_path(&self) -> PathBuf {
+        self.path.join("index.json")
+    }
+
+    /// Reads & deserializes the file, returning `None` if it does not exist.
+    async fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+        let data = match tokio::fs::read(path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", path)),
+        };
+        let value = serde_json::from_slice(&data).with_context(|| format!("Failed to parse {:?}", path))?;
+        Ok(Some(value))
+    }
 }
 
 #[async_trait]
 impl FetchFromStorage for LocalStorage {
     async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
-        let Ok(data) = tokio::fs::read(self.checkpoint_file_path(index)).await else {
-            return Ok(None);
-        };
-        let checkpoint = serde_json::from_slice(&data)?;
-        Ok(Some(checkpoint))
+        LocalStorage::read_json(&self.checkpoint_file_path(index)).await
+    }
+
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        LocalStorage::read_json(&self.latest_index_file_path()).await
     }
 
     fn announcement_location(&self) -> String {
         format!("file://{}", self.path.to_str().unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_storage_latest_index() {
+        let path = std::env::temp_dir().join(format!("theoros-local-storage-{}", std::process::id()));
+        let storage = LocalStorage::new(path.clone()).unwrap();
+
+        // Missing files are not errors
+        assert_eq!(storage.latest_index().await.unwrap(), None);
+        assert!(storage.fetch(0).await.unwrap().is_none());
+
+        std::fs::write(storage.latest_index_file_path(), "12").unwrap();
+        assert_eq!(storage.latest_index().await.unwrap(), Some(12));
+
+        // Malformed files are
+        std::fs::write(storage.latest_index_file_path(), "not an index").unwrap();
+        assert!(storage.latest_index().await.is_err());
+        std::fs::write(storage.checkpoint_file_path(0), "{}").unwrap();
+        assert!(storage.fetch(0).await.is_err());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -1,4 +1,7 @@
+pub mod cached;
+pub mod fallback;
 pub mod gcs;
+pub mod http;
 pub mod local;
 pub mod s3;
 
@@ -13,12 +16,14 @@ use anyhow::{anyhow, bail, Context, Error, Result};
 use async_trait::async_trait;
 use core::str::FromStr;
 use rusoto_core::Region;
+use url::Url;
 use ya_gcp::{AuthFlow, ServiceAccountAuth};
 
 use crate::types::hyperlane::{
-    gcs::{GcsStorageClientBuilder, GCS_SERVICE_ACCOUNT_KEY, GCS_USER_SECRET},
+    gcs::{GcsStorageClientBuilder, GCS_EMULATOR_HOST, GCS_SERVICE_ACCOUNT_KEY, GCS_USER_SECRET},
+    http::HttpStorage,
     local::LocalStorage,
-    s3::S3Storage,
+    s3::{S3Credentials, S3Storage, S3_CREDENTIALS, S3_ENDPOINT},
 };
 
 use super::SignedCheckpointWithMessageId;
@@ -29,9 +34,21 @@ pub trait FetchFromStorage: Debug + Send + Sync {
     /// Attempt to fetch the signed (checkpoint, messageId) tuple at this index
     #[allow(unused)]
     async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>>;
+    /// Fetch the index of the latest checkpoint signed by the validator, if any
+    async fn latest_index(&self) -> Result<Option<u32>>;
     /// Return the announcement storage location for this syncer
     #[allow(unused)]
     fn announcement_location(&self) -> String;
+    /// Removes the cached checkpoints below this index, for the storages caching them.
+    /// Returns the number of checkpoints removed.
+    fn prune_cache_below(&self, _index: u32) -> usize {
+        0
+    }
+    /// Returns the number of entries cached & their approximate memory usage in bytes,
+    /// for the storages caching them.
+    fn cache_size(&self) -> (usize, usize) {
+        (0, 0)
+    }
 }
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
@@ -47,8 +64,10 @@ pub enum CheckpointStorage {
         bucket: String,
         /// Folder name inside bucket - defaults to the root of the bucket
         folder: Option<String>,
-        /// S3 Region
+        /// S3 Region, custom for S3 compatible stores
         region: Region,
+        /// How the requests are signed - anonymous by default
+        credentials: S3Credentials,
     },
     /// A checkpoint storage on Google Cloud
     Gcs {
@@ -61,6 +80,13 @@ pub enum CheckpointStorage {
         /// Path to oauth user secrets, like those created by
         /// `gcloud auth application-default login`
         user_secrets: Option<String>,
+        /// Endpoint of a GCS compatible server used instead of Google's, e.g a fake-gcs emulator.
+        endpoint: Option<Url>,
+    },
+    /// A checkpoint storage behind a plain HTTP(S) server, following the S3 key layout
+    Http {
+        /// URL of the folder containing the checkpoints
+        url: Url,
     },
 }
 
@@ -69,15 +95,16 @@ impl FromStr for CheckpointStorage {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let [prefix, suffix]: [&str; 2] = s
-            .split("://")
-            .collect::<Vec<_>>()
-            .try_into()
-            .map_err(|_| anyhow!("Error parsing storage location; could not split prefix and suffix ({s})"))?;
+        let (prefix, suffix) = s
+            .split_once("://")
+            .ok_or_else(|| anyhow!("Error parsing storage location; could not split prefix and suffix ({s})"))?;
 
         match prefix {
             "s3" => {
-                let url_components = suffix.split('/').collect::<Vec<&str>>();
+                // A custom endpoint can be provided for S3 compatible stores, e.g
+                // s3://bucket/region/folder?endpoint=http://localhost:9000
+                let (path, query) = suffix.split_once('?').unwrap_or((suffix, ""));
+                let url_components = path.split('/').collect::<Vec<&str>>();
                 let (bucket, region, folder): (&str, &str, Option<String>) = match url_components.len() {
                     2 => Ok((url_components[0], url_components[1], None)),
                     3.. => Ok((url_components[0], url_components[1], Some(url_components[2..].join("/")))),
@@ -85,11 +112,19 @@ impl FromStr for CheckpointStorage {
                         "Error parsing storage location; could not split bucket, region and folder ({suffix})"
                     )),
                 }?;
-                Ok(CheckpointStorage::S3 {
-                    bucket: bucket.into(),
-                    folder,
-                    region: region.parse().context("Invalid region when parsing storage location")?,
-                })
+                let endpoint = url::form_urlencoded::parse(query.as_bytes())
+                    .find(|(key, _)| key == "endpoint")
+                    .map(|(_, endpoint)| endpoint.into_owned())
+                    .or_else(|| env::var(S3_ENDPOINT).ok());
+                let region = match endpoint {
+                    Some(endpoint) => Region::Custom { name: region.into(), endpoint },
+                    None => region.parse().context("Invalid region when parsing storage location")?,
+                };
+                let credentials = match env::var(S3_CREDENTIALS) {
+                    Ok(credentials) => credentials.parse().context("Invalid S3 credentials provider")?,
+                    Err(_) => S3Credentials::default(),
+                };
+                Ok(CheckpointStorage::S3 { bucket: bucket.into(), folder, region, credentials })
             }
             "file" => Ok(CheckpointStorage::LocalStorage { path: suffix.into() }),
             // for google cloud both options (with or without folder) from str are for anonymous access only
@@ -97,18 +132,25 @@ impl FromStr for CheckpointStorage {
             "gs" => {
                 let service_account_key = env::var(GCS_SERVICE_ACCOUNT_KEY).ok();
                 let user_secrets = env::var(GCS_USER_SECRET).ok();
-                if let Some(ind) = suffix.find('/') {
-                    let (bucket, folder) = suffix.split_at(ind);
-                    Ok(Self::Gcs {
-                        bucket: bucket.into(),
-                        folder: Some(folder.into()),
-                        service_account_key,
-                        user_secrets,
-                    })
-                } else {
-                    Ok(Self::Gcs { bucket: suffix.into(), folder: None, service_account_key, user_secrets })
+                let endpoint = env::var(GCS_EMULATOR_HOST).ok().map(|host| parse_endpoint(&host)).transpose()?;
+                let (bucket, folder) = match suffix.split_once('/') {
+                    Some((bucket, folder)) => (bucket, Some(folder.trim_matches('/'))),
+                    None => (suffix, None),
+                };
+                if bucket.is_empty() {
+                    bail!("Error parsing storage location; missing bucket name ({s})");
                 }
+                Ok(Self::Gcs {
+                    bucket: bucket.into(),
+                    folder: folder.filter(|folder| !folder.is_empty()).map(Into::into),
+                    service_account_key,
+                    user_secrets,
+                    endpoint,
+                })
             }
+            "https" | "http" => Ok(CheckpointStorage::Http {
+                url: Url::parse(s).with_context(|| format!("Invalid URL when parsing storage location ({s})"))?,
+            }),
             _ => bail!("Unknown storage location prefix `{prefix}`"),
         }
     }
@@ -119,10 +161,10 @@ impl CheckpointStorage {
     pub async fn build(&self) -> Result<Arc<dyn FetchFromStorage + Send + Sync>> {
         Ok(match self {
             CheckpointStorage::LocalStorage { path } => Arc::new(LocalStorage::new(path.clone())?),
-            CheckpointStorage::S3 { bucket, folder, region } => {
-                Arc::new(S3Storage::new(bucket.clone(), folder.clone(), region.clone()))
+            CheckpointStorage::S3 { bucket, folder, region, credentials } => {
+                Arc::new(S3Storage::new(bucket.clone(), folder.clone(), region.clone(), *credentials)?)
             }
-            CheckpointStorage::Gcs { bucket, folder, service_account_key, user_secrets } => {
+            CheckpointStorage::Gcs { bucket, folder, service_account_key, user_secrets, endpoint } => {
                 let auth = if let Some(path) = service_account_key {
                     AuthFlow::ServiceAccount(ServiceAccountAuth::Path(path.into()))
                 } else if let Some(path) = user_secrets {
@@ -132,8 +174,96 @@ impl CheckpointStorage {
                     AuthFlow::NoAuth
                 };
 
-                Arc::new(GcsStorageClientBuilder::new(auth).build(bucket, folder.to_owned()).await?)
+                Arc::new(
+                    GcsStorageClientBuilder::new(auth)
+                        .endpoint(endpoint.clone())
+                        .build(bucket, folder.to_owned())
+                        .await?,
+                )
             }
+            CheckpointStorage::Http { url } => Arc::new(HttpStorage::new(url.clone())?),
         })
     }
 }
+
+/// Parses an emulator endpoint, which is often provided without scheme (e.g "localhost:4443").
+fn parse_endpoint(endpoint: &str) -> Result<Url> {
+    let endpoint = if endpoint.contains("://") { endpoint.to_owned() } else { format!("http://{endpoint}") };
+    Url::parse(&endpoint).with_context(|| format!("Invalid storage endpoint `{endpoint}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gcs(bucket: &str, folder: Option<&str>) -> CheckpointStorage {
+        CheckpointStorage::Gcs {
+            bucket: bucket.into(),
+            folder: folder.map(Into::into),
+            service_account_key: None,
+            user_secrets: None,
+            endpoint: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_s3_location() {
+        assert_eq!(
+            CheckpointStorage::from_str("s3://bucket/us-east-1/a/b").unwrap(),
+            CheckpointStorage::S3 {
+                bucket: "bucket".into(),
+                folder: Some("a/b".into()),
+                region: Region::UsEast1,
+                credentials: S3Credentials::Anonymous,
+            }
+        );
+        assert_eq!(
+            CheckpointStorage::from_str("s3://bucket/minio?endpoint=http://localhost:9000").unwrap(),
+            CheckpointStorage::S3 {
+                bucket: "bucket".into(),
+                folder: None,
+                region: Region::Custom { name: "minio".into(), endpoint: "http://localhost:9000".into() },
+                credentials: S3Credentials::Anonymous,
+            }
+        );
+        assert!(CheckpointStorage::from_str("s3://bucket").is_err());
+        assert!(CheckpointStorage::from_str("s3://bucket/not-a-region").is_err());
+    }
+
+    #[test]
+    fn test_parse_s3_credentials() {
+        assert_eq!("anonymous".parse::<S3Credentials>().unwrap(), S3Credentials::Anonymous);
+        assert_eq!("env".parse::<S3Credentials>().unwrap(), S3Credentials::Env);
+        assert_eq!("profile".parse::<S3Credentials>().unwrap(), S3Credentials::Profile);
+        assert_eq!("web-identity".parse::<S3Credentials>().unwrap(), S3Credentials::WebIdentity);
+        assert!("iam".parse::<S3Credentials>().is_err());
+    }
+
+    #[test]
+    fn test_parse_gcs_location() {
+        assert_eq!(CheckpointStorage::from_str("gs://bucket").unwrap(), gcs("bucket", None));
+        assert_eq!(CheckpointStorage::from_str("gs://bucket/").unwrap(), gcs("bucket", None));
+        assert_eq!(CheckpointStorage::from_str("gs://bucket/folder").unwrap(), gcs("bucket", Some("folder")));
+        assert_eq!(CheckpointStorage::from_str("gs://bucket/a/b/").unwrap(), gcs("bucket", Some("a/b")));
+        assert!(CheckpointStorage::from_str("gs:///folder").is_err());
+    }
+
+    #[test]
+    fn test_parse_http_location() {
+        assert_eq!(
+            CheckpointStorage::from_str("https://cdn.example.com/validator").unwrap(),
+            CheckpointStorage::Http { url: Url::parse("https://cdn.example.com/validator").unwrap() }
+        );
+        assert!(matches!(
+            CheckpointStorage::from_str("http://localhost:8080").unwrap(),
+            CheckpointStorage::Http { .. }
+        ));
+        assert!(CheckpointStorage::from_str("https://").is_err());
+    }
+
+    #[test]
+    fn test_parse_endpoint() {
+        assert_eq!(parse_endpoint("localhost:4443").unwrap().as_str(), "http://localhost:4443/");
+        assert_eq!(parse_endpoint("https://gcs.example.com").unwrap().as_str(), "https://gcs.example.com/");
+    }
+}
@@ -1,15 +1,18 @@
 // Source:
 // https://github.com/hyperlane-xyz/hyperlane-monorepo/blob/3e90734310fb1ca9a607ce3d334015fa7aaa9208/rust/hyperlane-base/src/types/s3_storage.rs#L29
-use std::{fmt, sync::OnceLock, time::Duration};
+use std::{fmt, time::Duration};
 
 use anyhow::{bail, Result};
 use async_trait::async_trait;
 use futures_util::TryStreamExt;
 use rusoto_core::{
-    credential::{Anonymous, AwsCredentials, StaticProvider},
+    credential::{
+        Anonymous, AutoRefreshingProvider, AwsCredentials, EnvironmentProvider, ProfileProvider, StaticProvider,
+    },
     Region, RusotoError,
 };
 use rusoto_s3::{GetObjectError, GetObjectRequest, S3Client, S3};
+use rusoto_sts::WebIdentityProvider;
 use tokio::time::timeout;
 
 use pragma_utils::http::http_client_with_timeout;
@@ -21,6 +24,26 @@ use crate::types::hyperlane::{FetchFromStorage, SignedCheckpointWithMessageId};
 /// See https://github.com/rusoto/rusoto/issues/1795.
 const S3_REQUEST_TIMEOUT_SECONDS: u64 = 30;
 
+/// Endpoint of an S3 compatible store (e.g MinIO) used instead of AWS.
+pub const S3_ENDPOINT: &str = "S3_ENDPOINT";
+/// Credentials provider used to sign the S3 requests, see [S3Credentials].
+pub const S3_CREDENTIALS: &str = "S3_CREDENTIALS";
+
+/// How the S3 requests are signed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum S3Credentials {
+    /// Unsigned requests, for the public validators buckets.
+    #[default]
+    Anonymous,
+    /// Credentials from the `AWS_ACCESS_KEY_ID` & `AWS_SECRET_ACCESS_KEY` environment variables.
+    Env,
+    /// Credentials from the AWS profile, selected through `AWS_PROFILE`.
+    Profile,
+    /// Credentials of a Kubernetes service account, through `AWS_WEB_IDENTITY_TOKEN_FILE` & `AWS_ROLE_ARN`.
+    WebIdentity,
+}
+
 #[derive(Clone)]
 /// Type for reading/writing to S3
 pub struct S3Storage {
@@ -28,10 +51,12 @@ pub struct S3Storage {
     bucket: String,
     /// A specific folder inside the above repo - set to empty string to use the root of the bucket
     folder: Option<String>,
-    /// The region of the bucket.
+    /// The region of the bucket, with a custom endpoint for S3 compatible stores.
     region: Region,
-    /// A client without credentials for anonymous requests.
-    anonymous_client: OnceLock<S3Client>,
+    /// How the requests are signed.
+    credentials: S3Credentials,
+    /// The client used for all the requests.
+    client: S3Client,
 }
 
 impl fmt::Debug for S3Storage {
@@ -40,22 +65,23 @@ impl fmt::Debug for S3Storage {
             .field("bucket", &self.bucket)
             .field("folder", &self.folder)
             .field("region", &self.region)
+            .field("credentials", &self.credentials)
             .finish()
     }
 }
 
 impl S3Storage {
     /// Creates a new S3Storage.
-    pub fn new(bucket: String, folder: Option<String>, region: Region) -> Self {
-        S3Storage { bucket, folder, region, anonymous_client: Default::default() }
+    pub fn new(bucket: String, folder: Option<String>, region: Region, credentials: S3Credentials) -> Result<Self> {
+        let client = S3Storage::build_client(region.clone(), credentials)?;
+        Ok(S3Storage { bucket, folder, region, credentials, client })
     }
 
-    /// Uses an anonymous client. This should only be used for publicly accessible buckets.
-    async fn anonymously_read_from_bucket(&self, key: String) -> Result<Option<Vec<u8>>> {
+    async fn read_from_bucket(&self, key: String) -> Result<Option<Vec<u8>>> {
         let req =
             GetObjectRequest { key: self.get_composite_key(key), bucket: self.bucket.clone(), ..Default::default() };
         let get_object_result =
-            timeout(Duration::from_secs(S3_REQUEST_TIMEOUT_SECONDS), self.anonymous_client().get_object(req)).await?;
+            timeout(Duration::from_secs(S3_REQUEST_TIMEOUT_SECONDS), self.client.get_object(req)).await?;
 
         match get_object_result {
             Ok(res) => match res.body {
@@ -67,25 +93,29 @@ impl S3Storage {
         }
     }
 
-    /// Gets an anonymous S3Client, creating it if it doesn't already exist.
-    /// An anonymous client doesn't have AWS credentials and will not sign S3
-    /// requests with any credentials.
-    /// We've experienced an inability to make GetObjectRequests to public
-    /// S3 buckets when signing with credentials from an AWS account not from the
-    /// S3 bucket's AWS account.
-    fn anonymous_client(&self) -> &S3Client {
-        self.anonymous_client.get_or_init(|| {
-            // By default, these credentials are anonymous, see:
-            // https://docs.rs/rusoto_credential/latest/rusoto_credential/struct.AwsCredentials.html#anonymous-example
-            let credentials = AwsCredentials::default();
-            assert!(credentials.is_anonymous(), "AWS credentials not anonymous");
-
-            S3Client::new_with(
-                http_client_with_timeout().unwrap(),
-                StaticProvider::from(credentials),
-                self.region.clone(),
-            )
-        })
+    /// Builds the S3Client signing the requests with the provided credentials.
+    /// Anonymous clients should be used for publicly accessible buckets: we've experienced
+    /// an inability to make GetObjectRequests to public S3 buckets when signing with
+    /// credentials from an AWS account not from the S3 bucket's AWS account.
+    fn build_client(region: Region, credentials: S3Credentials) -> Result<S3Client> {
+        let http_client = http_client_with_timeout()?;
+        let client = match credentials {
+            S3Credentials::Anonymous => {
+                // By default, these credentials are anonymous, see:
+                // https://docs.rs/rusoto_credential/latest/rusoto_credential/struct.AwsCredentials.html#anonymous-example
+                let credentials = AwsCredentials::default();
+                assert!(credentials.is_anonymous(), "AWS credentials not anonymous");
+                S3Client::new_with(http_client, StaticProvider::from(credentials), region)
+            }
+            S3Credentials::Env => S3Client::new_with(http_client, EnvironmentProvider::default(), region),
+            S3Credentials::Profile => S3Client::new_with(http_client, ProfileProvider::new()?, region),
+            S3Credentials::WebIdentity => S3Client::new_with(
+                http_client,
+                AutoRefreshingProvider::new(WebIdentityProvider::from_k8s_env())?,
+                region,
+            ),
+        };
+        Ok(client)
     }
 
     fn get_composite_key(&self, key: String) -> String {
@@ -98,12 +128,24 @@ impl S3Storage {
     fn checkpoint_key(index: u32) -> String {
         format!("checkpoint_{index}_with_id.json")
     }
+
+    fn latest_index_key() -> String {
+        "checkpoint_latest_index.json".to_owned()
+    }
 }
 
 #[async_trait]
 impl FetchFromStorage for S3Storage {
     async fn fetch(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
-        self.anonymously_read_from_bucket(S3Storage::checkpoint_key(index))
+        self.read_from_bucket(S3Storage::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        self.read_from_bucket(S3Storage::latest_index_key())
             .await?
             .map(|data| serde_json::from_slice(&data))
             .transpose()
@@ -111,11 +153,15 @@ impl FetchFromStorage for S3Storage {
     }
 
     fn announcement_location(&self) -> String {
-        match self.folder.as_deref() {
+        let location = match self.folder.as_deref() {
             None | Some("") => format!("s3://{}/{}", self.bucket, self.region.name()),
             Some(folder_str) => {
                 format!("s3://{}/{}/{}", self.bucket, self.region.name(), folder_str)
             }
+        };
+        match &self.region {
+            Region::Custom { endpoint, .. } => format!("{location}?endpoint={endpoint}"),
+            _ => location,
         }
     }
 }
@@ -1,26 +1,13 @@
-use alloy::primitives::hex;
-use anyhow::{Context, Result};
-use pragma_feeds::FeedType;
+use alloy::primitives::{keccak256, Bytes, B256};
+use pragma_feeds::{feed_id::FEED_ID_SIZE, FeedId, FeedType};
+use pragma_utils::event_data::FeltReader;
 use starknet::core::types::{Felt, U256};
 
-use pragma_utils::conversions::apibara::FromFieldBytes;
+use super::{reader::ByteReader, FromStarknetEventData};
+use crate::errors::DispatchEventError;
 
-use super::FromStarknetEventData;
-
-const MESSAGE_HEADER_FELT_SIZE: usize = 10;
 const SPOT_MEDIAN_UPDATE_SIZE: usize = 107;
 
-#[derive(Debug, Clone)]
-pub struct DispatchEvent {
-    #[allow(unused)]
-    pub sender: U256,
-    #[allow(unused)]
-    pub destination_domain: u32,
-    #[allow(unused)]
-    pub recipient_address: U256,
-    pub message: DispatchMessage,
-}
-
 // Creates a Dispatch from a Dispatch starknet event data, which is:
 // 0. sender address
 // 1. destination chain id
@@ -35,128 +22,160 @@ pub struct DispatchEvent {
 //        - destination,
 //        - recipient_low,
 //        - recipient_high,
-//    b. body:
-//        - nbr data_feeds updated
+//    b. body, as a Cairo `Bytes` (size in bytes, number of words & u128 words):
+//        - nbr data_feeds updated (u16, or u8 for the dispatchers built from older sources)
 //        - update (per data_feed) =>
-//            - asset_class
-//            - data_type (given it, we know update_size)
-//            [depending on the asset_class <=> data_type tuple, update below...]
+//            - feed_id (asset_class, feed_type & pair_id, see [FeedId])
+//            [depending on the feed_type, update below...]
 //            [for example for SpotMedian below]
-//            - pair_id
-//            - price
-//            - volume
-//            - decimals
 //            - timestamp
 //            - sources_aggregated
-impl FromStarknetEventData for DispatchEvent {
-    fn from_starknet_event_data(data: Vec<Felt>) -> Result<Self> {
-        let mut data = data.iter();
-
-        let sender = U256::from_words(
-            u128::from_field_bytes(data.next().context("Missing sender part 1")?.to_bytes_be()),
-            u128::from_field_bytes(data.next().context("Missing sender part 2")?.to_bytes_be()),
-        );
-
-        let destination_domain = u32::from_field_bytes(data.next().context("Missing destination")?.to_bytes_be());
-
-        let recipient_address = U256::from_words(
-            u128::from_field_bytes(data.next().context("Missing recipient part 1")?.to_bytes_be()),
-            u128::from_field_bytes(data.next().context("Missing recipient part 2")?.to_bytes_be()),
-        );
-
-        let header = DispatchMessageHeader::from_starknet_event_data(data.clone().cloned().collect())?;
-        let body_data: Vec<Felt> = data.skip(MESSAGE_HEADER_FELT_SIZE).cloned().collect();
-        let body = DispatchMessageBody::from_starknet_event_data(body_data)?;
+//            - decimals
+//            - price
+//            - volume
+#[derive(Debug, Clone, FromStarknetEventData)]
+#[event_data(error = DispatchEventError)]
+pub struct DispatchEvent {
+    #[allow(unused)]
+    #[event_data(u256)]
+    pub sender: U256,
+    #[allow(unused)]
+    pub destination_domain: u32,
+    #[allow(unused)]
+    #[event_data(u256)]
+    pub recipient_address: U256,
+    #[event_data(nested)]
+    pub message: DispatchMessage,
+}
 
-        let message = DispatchMessage { header, body };
+impl DispatchEvent {
+    /// Decodes the event data, failing on a truncated or malformed event.
+    pub fn decode(data: &[Felt]) -> Result<Self, DispatchEventError> {
+        Self::read_event_data(&mut FeltReader::new(data))
+    }
+}
 
-        let dispatch = Self { sender, destination_domain, recipient_address, message };
-        Ok(dispatch)
+/// Concatenates the u128 words of a Cairo `Bytes` into its `size` bytes.
+/// When the size is not a multiple of 16, the last word only holds the remaining bytes.
+/// Fails if the number of words does not match the size.
+fn bytes_from_words(words: &[Felt], size: usize) -> Result<Vec<u8>, DispatchEventError> {
+    if words.len() != size.div_ceil(16) {
+        return Err(DispatchEventError::BodySizeMismatch { size, words: words.len() });
     }
+    let mut bytes: Vec<u8> = words.iter().flat_map(|word| word.to_bytes_be()[16..].to_vec()).collect();
+    let remainder = size % 16;
+    if remainder != 0 {
+        // Removes the padding of the last word
+        let last_word_start = bytes.len() - 16;
+        bytes.drain(last_word_start..last_word_start + 16 - remainder);
+    }
+    Ok(bytes)
 }
 
 #[derive(Debug, Clone)]
 pub struct DispatchMessage {
     pub header: DispatchMessageHeader,
     pub body: DispatchMessageBody,
+    /// Body as dispatched, i.e the encoded updates.
+    pub raw_body: Vec<u8>,
 }
 
-#[derive(Debug, Clone)]
+// The body is a Cairo `Bytes`, i.e its size in bytes & the span of its u128 words.
+impl FromStarknetEventData for DispatchMessage {
+    type Error = DispatchEventError;
+
+    fn read_event_data(reader: &mut FeltReader<'_>) -> Result<Self, Self::Error> {
+        let header = DispatchMessageHeader::read_event_data(reader)?;
+        let body_size: u32 = reader.read("body size")?;
+        let words: Vec<Felt> = reader.read_span("body words")?;
+        let raw_body = bytes_from_words(&words, body_size as usize)?;
+        let body = DispatchMessageBody::from_bytes(&raw_body)?;
+
+        Ok(Self { header, body, raw_body })
+    }
+}
+
+impl DispatchMessage {
+    /// Encodes the message like the Hyperlane `Message` library:
+    /// version (1) | nonce (4) | origin (4) | sender (32) | destination (4) | recipient (32) | body
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = &self.header;
+        let mut bytes = Vec::with_capacity(77 + self.raw_body.len());
+        bytes.push(header.version);
+        bytes.extend_from_slice(&header.nonce.to_be_bytes());
+        bytes.extend_from_slice(&header.origin.to_be_bytes());
+        bytes.extend_from_slice(&u256_to_be_bytes(&header.sender));
+        bytes.extend_from_slice(&header.destination.to_be_bytes());
+        bytes.extend_from_slice(&u256_to_be_bytes(&header.recipient));
+        bytes.extend_from_slice(&self.raw_body);
+        bytes
+    }
+
+    /// Id of the message, i.e the keccak256 hash of its encoding.
+    pub fn id(&self) -> B256 {
+        keccak256(self.to_bytes())
+    }
+}
+
+fn u256_to_be_bytes(value: &U256) -> [u8; 32] {
+    let mut bytes = [0_u8; 32];
+    bytes[..16].copy_from_slice(&value.high().to_be_bytes());
+    bytes[16..].copy_from_slice(&value.low().to_be_bytes());
+    bytes
+}
+
+#[derive(Debug, Clone, FromStarknetEventData)]
 pub struct DispatchMessageHeader {
-    #[allow(unused)]
     pub version: u8,
     pub nonce: u32,
     pub origin: u32,
+    #[event_data(u256)]
     pub sender: U256,
-    #[allow(unused)]
     pub destination: u32,
-    #[allow(unused)]
+    #[event_data(u256)]
     pub recipient: U256,
 }
 
-impl FromStarknetEventData for DispatchMessageHeader {
-    fn from_starknet_event_data(data: Vec<Felt>) -> Result<Self> {
-        let mut data = data.iter();
-        Ok(Self {
-            version: u8::from_field_bytes(data.next().context("Missing version")?.to_bytes_be()),
-            nonce: u32::from_field_bytes(data.next().context("Missing nonce")?.to_bytes_be()),
-            origin: u32::from_field_bytes(data.next().context("Missing origin")?.to_bytes_be()),
-            sender: U256::from_words(
-                u128::from_field_bytes(data.next().context("Missing sender part 1")?.to_bytes_be()),
-                u128::from_field_bytes(data.next().context("Missing sender part 2")?.to_bytes_be()),
-            ),
-            destination: u32::from_field_bytes(data.next().context("Missing destination")?.to_bytes_be()),
-            recipient: U256::from_words(
-                u128::from_field_bytes(data.next().context("Missing recipient part 1")?.to_bytes_be()),
-                u128::from_field_bytes(data.next().context("Missing recipient part 2")?.to_bytes_be()),
-            ),
-        })
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct DispatchMessageBody {
-    #[allow(unused)]
-    pub nb_updated: u8,
+    pub nb_updated: u16,
     pub updates: Vec<DispatchUpdate>,
 }
 
-impl FromStarknetEventData for DispatchMessageBody {
-    fn from_starknet_event_data(data: Vec<Felt>) -> Result<Self> {
-        // Flatten the Felt data by removing the first 32 bytes and concatenating the rest
-        let mut data: Vec<u8> = data
-            .iter()
-            .flat_map(|fe| {
-                let bytes = fe.to_bytes_be();
-                // Skip the first 32 bytes, if applicable
-                bytes.split_at(16).1.to_vec()
-            })
-            .collect();
-
-        let nb_updated = u8::from_be_bytes(data.drain(..1).collect::<Vec<u8>>().try_into().unwrap());
-        let mut updates = Vec::with_capacity(nb_updated as usize);
-
-        for _ in 0..nb_updated {
-            let update = DispatchUpdate::from_starknet_event_data(data.clone()).context("Failed to parse update")?;
-            match update {
-                DispatchUpdate::SpotMedian { update: _, feed_id: _ } => {
-                    data.drain(..SPOT_MEDIAN_UPDATE_SIZE);
-                }
-            }
-            updates.push(update);
+impl DispatchMessageBody {
+    /// Decodes the updates of the body. The bytes after the declared updates are ignored.
+    /// The number of updates is a u16 in the messages of the deployed dispatchers, but a u8 in the
+    /// ones of the dispatchers built from older sources, so both encodings are accepted.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DispatchEventError> {
+        // A u8 count can't be mistaken for a u16 one: as the first byte of a feed id is always empty,
+        // it would be read as at least 256 updates, more than the body holds.
+        let error = match Self::decode(bytes, true) {
+            Ok((body, _)) => return Ok(body),
+            Err(e) => e,
+        };
+        // So that a malformed body is not decoded as a u8 one, the updates must fill the whole body.
+        // A u8 body without updates can't be told from a truncated u16 count, but has nothing to store.
+        match Self::decode(bytes, false) {
+            Ok((body, 0)) if body.nb_updated > 0 => Ok(body),
+            _ => Err(error),
         }
+    }
 
-        // Check that the number of parsed updates matches the declared number
-        if updates.len() != nb_updated as usize {
-            anyhow::bail!(
-                "Mismatch between declared number of updates ({}) and actual updates parsed ({})",
-                nb_updated,
-                updates.len()
-            );
+    /// Decodes the body with a u16 or a u8 number of updates, returning the number of bytes left.
+    fn decode(bytes: &[u8], u16_count: bool) -> Result<(Self, usize), DispatchEventError> {
+        let mut reader = ByteReader::new(bytes);
+
+        let nb_updated = match u16_count {
+            true => reader.read_u16("number of updates")?,
+            false => reader.read_u8("number of updates")?.into(),
+        };
+        // The declared number of updates can't be trusted to allocate
+        let mut updates = Vec::with_capacity((nb_updated as usize).min(reader.remaining() / SPOT_MEDIAN_UPDATE_SIZE));
+        for _ in 0..nb_updated {
+            updates.push(DispatchUpdate::read(&mut reader)?);
         }
 
-        // Return the result
-        Ok(Self { nb_updated, updates })
+        Ok((Self { nb_updated, updates }, reader.remaining()))
     }
 }
 
@@ -166,6 +185,8 @@ pub struct DispatchUpdateInfos {
     pub emitter_chain_id: u32,
     pub emitter_address: Felt,
     pub update: DispatchUpdate,
+    /// Message of the update, in the Hyperlane encoding
+    pub message: Bytes,
 }
 
 impl DispatchUpdateInfos {
@@ -173,62 +194,59 @@ impl DispatchUpdateInfos {
         DispatchUpdateInfos {
             nonce: event.message.header.nonce,
             emitter_chain_id: event.message.header.origin,
-            emitter_address: Felt::from_dec_str(&event.message.header.sender.to_string()).unwrap(),
+            emitter_address: Felt::from_bytes_be(&u256_to_be_bytes(&event.message.header.sender)),
             update: update.clone(),
+            message: event.message.to_bytes().into(),
         }
     }
+
+    /// Checks if the update is more recent than another update of the same feed:
+    /// it must be published later, or at the same time but by a later nonce.
+    pub fn is_newer_than(&self, other: &DispatchUpdateInfos) -> bool {
+        (self.update.publish_time(), self.nonce) > (other.update.publish_time(), other.nonce)
+    }
 }
 
 // TODO: Should be a trait?
 #[derive(Debug, Clone)]
 pub enum DispatchUpdate {
-    SpotMedian { update: SpotMedianUpdate, feed_id: String },
+    SpotMedian { update: SpotMedianUpdate, feed_id: FeedId },
 }
 
 impl DispatchUpdate {
-    pub fn feed_id(&self) -> String {
+    pub fn feed_id(&self) -> &FeedId {
         match self {
-            DispatchUpdate::SpotMedian { feed_id, update: _ } => feed_id.clone(),
+            DispatchUpdate::SpotMedian { feed_id, update: _ } => feed_id,
         }
     }
 
-    fn from_starknet_event_data(mut data: Vec<u8>) -> Result<Self> {
-        let raw_asset_class = u16::from_be_bytes(data.drain(..2).collect::<Vec<u8>>().try_into().unwrap());
-
-        let raw_feed_type = u16::from_be_bytes(data.drain(..2).collect::<Vec<u8>>().try_into().unwrap());
-        let feed_type = FeedType::try_from(raw_feed_type)?;
+    /// Timestamp at which the update was published.
+    pub fn publish_time(&self) -> u64 {
+        match self {
+            DispatchUpdate::SpotMedian { update, .. } => update.metadata.timestamp,
+        }
+    }
 
-        let pair_id_high = u128::from_be_bytes(data.drain(..16).collect::<Vec<u8>>().try_into().unwrap());
-        let mut padded_data = [0u8; 16];
-        let extracted_data = data.drain(..12).collect::<Vec<u8>>();
-        padded_data[4..].copy_from_slice(&extracted_data);
-        let pair_id_low = u128::from_be_bytes(padded_data);
-        let pair_id = U256::from_words(pair_id_low, pair_id_high);
+    fn read(reader: &mut ByteReader<'_>) -> Result<Self, DispatchEventError> {
+        let raw_feed_id: [u8; FEED_ID_SIZE] = reader.read_bytes("feed id")?;
+        let feed_id = FeedId::decode(&raw_feed_id).map_err(|e| DispatchEventError::InvalidFeedId(e.to_string()))?;
 
-        let feed_id = build_feed_id(raw_asset_class, raw_feed_type, pair_id_high, pair_id_low);
+        let pair_id_bytes = feed_id.pair_id_bytes();
+        let pair_id = ByteReader::new(&pair_id_bytes).read_u256("pair id")?;
 
-        let update = match feed_type {
+        let update = match feed_id.feed_type {
             FeedType::UniqueSpotMedian => {
-                let mut res = SpotMedianUpdate::from_starknet_event_data(data)?;
+                let mut res = SpotMedianUpdate::read(reader)?;
                 res.pair_id = pair_id;
                 DispatchUpdate::SpotMedian { update: res, feed_id }
             }
+            feed_type => return Err(DispatchEventError::UnsupportedFeedType(feed_type)),
         };
 
         Ok(update)
     }
 }
 
-fn build_feed_id(raw_asset_class: u16, raw_feed_type: u16, pair_id_high: u128, pair_id_low: u128) -> String {
-    let mut bytes: Vec<u8> = Vec::new();
-    bytes.extend_from_slice(&raw_asset_class.to_be_bytes());
-    bytes.extend_from_slice(&raw_feed_type.to_be_bytes());
-    bytes.extend_from_slice(&pair_id_high.to_be_bytes());
-    bytes.extend_from_slice(&pair_id_low.to_be_bytes());
-    let feed_id = format!("0x{}", hex::encode(&bytes));
-    feed_id
-}
-
 #[derive(Debug, Clone)]
 pub struct MetadataUpdate {
     pub timestamp: u64,
@@ -245,16 +263,12 @@ pub struct SpotMedianUpdate {
 }
 
 impl SpotMedianUpdate {
-    fn from_starknet_event_data(mut data: Vec<u8>) -> Result<Self> {
-        let timestamp = u64::from_be_bytes(data.drain(..8).collect::<Vec<u8>>().try_into().unwrap());
-        let num_sources_aggregated = u16::from_be_bytes(data.drain(..2).collect::<Vec<u8>>().try_into().unwrap());
-        let decimals = u8::from_be_bytes(data.drain(..1).collect::<Vec<u8>>().try_into().unwrap());
-        let price_high = u128::from_be_bytes(data.drain(..16).collect::<Vec<u8>>().try_into().unwrap()); // U256
-        let price_low = u128::from_be_bytes(data.drain(..16).collect::<Vec<u8>>().try_into().unwrap());
-        let price = U256::from_words(price_low, price_high);
-        let volume_high = u128::from_be_bytes(data.drain(..16).collect::<Vec<u8>>().try_into().unwrap()); // U256
-        let volume_low = u128::from_be_bytes(data.drain(..16).collect::<Vec<u8>>().try_into().unwrap());
-        let volume = U256::from_words(volume_low, volume_high);
+    fn read(reader: &mut ByteReader<'_>) -> Result<Self, DispatchEventError> {
+        let timestamp = reader.read_u64("timestamp")?;
+        let num_sources_aggregated = reader.read_u16("number of sources aggregated")?;
+        let decimals = reader.read_u8("decimals")?;
+        let price = reader.read_u256("price")?;
+        let volume = reader.read_u256("volume")?;
 
         Ok(Self {
             pair_id: U256::from(0_u8), // This will get populated later
@@ -285,16 +299,121 @@ impl SpotMedianUpdate {
 
 #[cfg(test)]
 mod tests {
+    use alloy::{hex, primitives::b256};
+    use pragma_feeds::AssetClass;
+
     use super::*;
 
     fn create_event_data(raw_data: Vec<&str>) -> Vec<Felt> {
         raw_data.iter().map(|hex_str| Felt::from_hex(hex_str).unwrap()).collect()
     }
 
-    // TODO: Fix this test!
+    fn spot_median_update_bytes(feed_id: &FeedId, price: u128) -> Vec<u8> {
+        let mut bytes = feed_id.encode().to_vec();
+        bytes.extend_from_slice(&1728663780_u64.to_be_bytes());
+        bytes.extend_from_slice(&3_u16.to_be_bytes());
+        bytes.push(8);
+        bytes.extend_from_slice(&[0_u8; 16]);
+        bytes.extend_from_slice(&price.to_be_bytes());
+        bytes.extend_from_slice(&[0_u8; 32]);
+        bytes
+    }
+
     #[test]
-    fn test_dispatch_event_from_event_data() {
+    fn test_dispatch_update_from_event_data_all_asset_classes() {
+        let feeds = [
+            (AssetClass::Crypto, "BTC/USD"),
+            (AssetClass::Forex, "EUR/USD"),
+            (AssetClass::Equities, "US0378331005/USD"),
+            (AssetClass::Commodities, "XAU/USD"),
+        ];
+        for (asset_class, pair_id) in feeds {
+            let feed_id = FeedId::new(asset_class, FeedType::UniqueSpotMedian, pair_id).unwrap();
+            let bytes = spot_median_update_bytes(&feed_id, 108_000_000);
+            assert_eq!(bytes.len(), SPOT_MEDIAN_UPDATE_SIZE);
+
+            let update = DispatchUpdate::read(&mut ByteReader::new(&bytes)).unwrap();
+
+            assert_eq!(update.feed_id(), &feed_id);
+            let DispatchUpdate::SpotMedian { update, .. } = update;
+            assert_eq!(update.price, U256::from_words(108_000_000, 0));
+            assert_eq!(update.metadata.timestamp, 1728663780);
+            assert_eq!(update.metadata.num_sources_aggregated, 3);
+            assert_eq!(update.metadata.decimals, 8);
+            assert_eq!(
+                update.pair_id,
+                U256::from_words(u128::from_be_bytes(feed_id.pair_id_bytes()[16..].try_into().unwrap()), 0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_dispatch_update_from_event_data_unconventional_pair_id() {
+        let mut feed_id = FeedId::new(AssetClass::Equities, FeedType::UniqueSpotMedian, "US0378331005/USD").unwrap();
+        // Same pair id but published as a forex feed, which does not follow the forex conventions.
+        // It is still decoded: pair ids are only validated when provided by users.
+        feed_id.asset_class = AssetClass::Forex;
+        let bytes = spot_median_update_bytes(&feed_id, 1);
+
+        let update = DispatchUpdate::read(&mut ByteReader::new(&bytes)).unwrap();
+        assert_eq!(update.feed_id(), &feed_id);
+        assert!(feed_id.validate().is_err());
+    }
+
+    #[test]
+    fn test_dispatch_update_from_event_data_invalid_feed_id() {
+        let mut bytes = spot_median_update_bytes(&"0x4254432f555344".parse::<FeedId>().unwrap(), 1);
+        // Unknown asset class
+        bytes[1..3].copy_from_slice(&u16::MAX.to_be_bytes());
+
+        assert!(matches!(
+            DispatchUpdate::read(&mut ByteReader::new(&bytes)),
+            Err(DispatchEventError::InvalidFeedId(_))
+        ));
+    }
+
+    #[test]
+    fn test_dispatch_message_to_bytes() {
         let event_data = create_event_data(vec![
+            "0x0",
+            "0x0",
+            "0x1",
+            "0x0",
+            "0x0",
+            // Header
+            "0x3",
+            "0x7",
+            "0x611a3d",
+            "0xe12de834144d9e90044ac03f6024267e",
+            "0x04d997c57f63d509f483927ce74135a4",
+            "0x1",
+            "0x1111",
+            "0x2222",
+            // Body of 20 bytes in 2 words, without any update
+            "0x14",
+            "0x2",
+            "0x00002233445566778899aabbccddeeff",
+            "0xdeadbeef",
+        ]);
+
+        let message = DispatchEvent::from_starknet_event_data(event_data).unwrap().message;
+        assert_eq!(message.raw_body, hex::decode("00002233445566778899aabbccddeeffdeadbeef").unwrap());
+
+        let expected = hex::decode(concat!(
+            "03",
+            "00000007",
+            "00611a3d",
+            "04d997c57f63d509f483927ce74135a4e12de834144d9e90044ac03f6024267e",
+            "00000001",
+            "0000000000000000000000000000222200000000000000000000000000001111",
+            "00002233445566778899aabbccddeeffdeadbeef",
+        ))
+        .unwrap();
+        assert_eq!(message.to_bytes(), expected);
+    }
+
+    fn btc_eth_dispatch_event_data() -> Vec<Felt> {
+        create_event_data(vec![
             "0x00000000000000000000000000000000e12de834144d9e90044ac03f6024267e",
             "0x0000000000000000000000000000000004d997c57f63d509f483927ce74135a4",
             "0x0000000000000000000000000000000000000000000000000000000000000000",
@@ -324,35 +443,124 @@ mod tests {
             "0x0000000000000000000000000000000000000038f1e274c20000000000000000",
             "0x0000000000000000000000000000000000000000000000000000000000000000",
             "0x0000000000000000000000000000000000000000000000000000000000000000",
-        ]);
+        ])
+    }
 
-        let dispatch_event = DispatchEvent::from_starknet_event_data(event_data).unwrap();
+    #[test]
+    fn test_dispatch_event_from_event_data() {
+        let dispatch_event = DispatchEvent::from_starknet_event_data(btc_eth_dispatch_event_data()).unwrap();
+
+        let sender = U256::from_words(0xe12de834144d9e90044ac03f6024267e, 0x04d997c57f63d509f483927ce74135a4);
+        assert_eq!(dispatch_event.sender, sender);
+        assert_eq!(dispatch_event.destination_domain, 0);
+        assert_eq!(dispatch_event.recipient_address, U256::from(0_u32));
+
+        let header = &dispatch_event.message.header;
+        assert_eq!(header.version, 3);
+        assert_eq!(header.nonce, 0);
+        assert_eq!(header.origin, 0x611a3d);
+        assert_eq!(header.sender, sender);
+        assert_eq!(header.destination, 0);
+        assert_eq!(header.recipient, U256::from(0_u32));
+
+        assert_eq!(dispatch_event.message.raw_body.len(), 216);
+        // Id of the message in the Hyperlane encoding, computed outside of Theoros
+        assert_eq!(
+            dispatch_event.message.id(),
+            b256!("71f44a6a9949f019fb1d2d1677aca051537223043e264d4d463dfdf176a35796")
+        );
+        let body = &dispatch_event.message.body;
+        assert_eq!(body.nb_updated, 2);
+        assert_eq!(body.updates.len(), 2);
 
-        // assert_eq!(dispatch_event.sender, U256::from_words(299314662055416172851006310266400155262, 0)); // ça
-        // assert_eq!(dispatch_event.destination_domain, 0);
-        // assert_eq!(dispatch_event.recipient_address, U256::from(0_u32));
+        let expected = [("BTC/USD", 0x5a9d39c70a7_u128), ("ETH/USD", 0x38f1e274c2_u128)];
+        for (update, (pair_id, price)) in body.updates.iter().zip(expected) {
+            assert_eq!(
+                update.feed_id(),
+                &FeedId::new(AssetClass::Crypto, FeedType::UniqueSpotMedian, pair_id).unwrap()
+            );
+            let DispatchUpdate::SpotMedian { update, .. } = update;
+            assert_eq!(update.price, U256::from_words(price, 0));
+            assert_eq!(update.volume, U256::from(0_u32));
+            assert_eq!(update.metadata.timestamp, 1728662756);
+            assert_eq!(update.metadata.num_sources_aggregated, 1);
+            assert_eq!(update.metadata.decimals, 8);
+        }
+    }
 
-        // let header = &dispatch_event.message.header;
-        // assert_eq!(header.version, 1);
-        // assert_eq!(header.nonce, 4);
-        // assert_eq!(header.origin, 5);
-        // assert_eq!(header.sender, U256::from_words(299314662055416172851006310266400155262, 0));
-        // assert_eq!(header.destination, 7);
-        // assert_eq!(header.recipient, U256::from(0_u32));
+    #[test]
+    fn test_bytes_from_words() {
+        let words = [Felt::from(u128::from_be_bytes([1; 16])), Felt::from(0x0203_u128)];
+
+        let mut expected = vec![1; 16];
+        expected.extend_from_slice(&[2, 3]);
+        assert_eq!(bytes_from_words(&words, 18).unwrap(), expected);
+
+        let mut expected = vec![1; 16];
+        expected.extend_from_slice(&[0; 14]);
+        expected.extend_from_slice(&[2, 3]);
+        assert_eq!(bytes_from_words(&words, 32).unwrap(), expected);
+
+        assert!(bytes_from_words(&[], 0).unwrap().is_empty());
+        for size in [0, 16, 33] {
+            assert!(
+                matches!(bytes_from_words(&words, size), Err(DispatchEventError::BodySizeMismatch { words: 2, .. })),
+                "{size} bytes decoded"
+            );
+        }
+    }
 
-        let body = &dispatch_event.message.body;
+    #[test]
+    fn test_dispatch_event_truncated() {
+        let event_data = btc_eth_dispatch_event_data();
+        for len in 0..event_data.len() {
+            assert!(DispatchEvent::decode(&event_data[..len]).is_err(), "{len} felts decoded");
+        }
+    }
+
+    #[test]
+    fn test_dispatch_message_body_u8_number_of_updates() {
+        let event_data = btc_eth_dispatch_event_data();
+        let body = DispatchEvent::decode(&event_data).unwrap().message.raw_body;
+        assert_eq!(body[..2], [0, 2]);
+
+        // Same updates, with the number of updates encoded as a u8
+        let body_u8 = body[1..].to_vec();
+        let body = DispatchMessageBody::from_bytes(&body_u8).unwrap();
         assert_eq!(body.nb_updated, 2);
-        assert_eq!(body.updates.len(), 2);
+        let pair_ids: Vec<&str> = body.updates.iter().map(|update| update.feed_id().pair_id.as_str()).collect();
+        assert_eq!(pair_ids, ["BTC/USD", "ETH/USD"]);
+
+        // The updates of a u8 body must fill it, to not decode a malformed u16 body
+        let mut trailing_bytes = body_u8.clone();
+        trailing_bytes.push(0);
+        assert!(DispatchMessageBody::from_bytes(&trailing_bytes).is_err());
+        assert!(DispatchMessageBody::from_bytes(&[0]).is_err());
+    }
+
+    #[test]
+    fn test_dispatch_message_body_malformed() {
+        let event_data = btc_eth_dispatch_event_data();
+        let body = DispatchEvent::decode(&event_data).unwrap().message.raw_body;
+
+        for len in 0..body.len() {
+            assert!(DispatchMessageBody::from_bytes(&body[..len]).is_err(), "{len} bytes decoded");
+        }
 
-        // match &body.updates[0] {
-        //     DispatchUpdate::SpotMedian { feed_id: _, update } => {
-        //         assert_eq!(update.pair_id, U256::from(9_u32));
-        //         assert_eq!(update.price, BigDecimal::from(10)); // 1000 / 10^2
-        //         assert_eq!(update.volume, 0_u128);
-        //         assert_eq!(update.metadata.decimals, 2);
-        //         assert_eq!(update.metadata.timestamp, 1234567890);
-        //         assert_eq!(update.metadata.num_sources_aggregated, 5);
-        //     }
-        // }
+        // More updates declared than sent
+        let mut too_many_updates = body.clone();
+        too_many_updates[..2].copy_from_slice(&u16::MAX.to_be_bytes());
+        assert!(matches!(
+            DispatchMessageBody::from_bytes(&too_many_updates),
+            Err(DispatchEventError::UnexpectedEnd { field: "feed id", .. })
+        ));
+
+        // Unknown feed type
+        let mut unknown_feed_type = body;
+        unknown_feed_type[2 + 3..2 + 5].copy_from_slice(&u16::MAX.to_be_bytes());
+        assert!(matches!(
+            DispatchMessageBody::from_bytes(&unknown_feed_type),
+            Err(DispatchEventError::InvalidFeedId(_))
+        ));
     }
 }
@@ -0,0 +1,66 @@
+use alloy::primitives::B256;
+use starknet::core::types::Felt;
+
+use pragma_utils::event_data::{EventDataError, FeltReader};
+
+use super::FromStarknetEventData;
+
+// Creates an InsertedIntoTree from a InsertedIntoTree starknet event data, which is:
+// 0. message id low
+// 1. message id high
+// 2. index
+/// Event emitted by the Hyperlane `MerkleTreeHook` when the id of a dispatched message
+/// is inserted in the merkle tree.
+#[derive(Debug, Clone, FromStarknetEventData)]
+pub struct InsertedIntoTreeEvent {
+    #[event_data(with = read_message_id)]
+    pub message_id: B256,
+    pub index: u32,
+}
+
+/// Reads the message id serialized as a Cairo u256, failing if one of its words does not fit in a u128.
+fn read_message_id(reader: &mut FeltReader<'_>) -> Result<B256, EventDataError> {
+    let message_id = reader.read_u256("message id")?;
+    let mut bytes = [0_u8; 32];
+    bytes[..16].copy_from_slice(&message_id.high().to_be_bytes());
+    bytes[16..].copy_from_slice(&message_id.low().to_be_bytes());
+    Ok(B256::from(bytes))
+}
+
+/// Converts the (low, high) felts of a Cairo u256 into its big endian bytes.
+pub fn b256_from_words(low: &Felt, high: &Felt) -> B256 {
+    let mut bytes = [0_u8; 32];
+    bytes[..16].copy_from_slice(&high.to_bytes_be()[16..]);
+    bytes[16..].copy_from_slice(&low.to_bytes_be()[16..]);
+    B256::from(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::b256;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_inserted_into_tree_event() {
+        let data = vec![
+            Felt::from_hex("0x3c4d9d6a5f036c2abc0d1fca93b24f13").unwrap(),
+            Felt::from_hex("0x0ccf4d9d6a5f036c2abc0d1fca93b24f").unwrap(),
+            Felt::from(1211),
+        ];
+        let event = InsertedIntoTreeEvent::from_starknet_event_data(data).unwrap();
+        assert_eq!(event.message_id, b256!("0ccf4d9d6a5f036c2abc0d1fca93b24f3c4d9d6a5f036c2abc0d1fca93b24f13"));
+        assert_eq!(event.index, 1211);
+
+        assert!(InsertedIntoTreeEvent::from_starknet_event_data(vec![Felt::ONE, Felt::TWO]).is_err());
+    }
+
+    #[test]
+    fn test_parse_inserted_into_tree_event_oversized_message_id_word() {
+        let oversized_word = Felt::from(u128::MAX) + Felt::ONE;
+        for (low, high) in [(oversized_word, Felt::ONE), (Felt::ONE, oversized_word)] {
+            let data = vec![low, high, Felt::from(1211)];
+            assert!(InsertedIntoTreeEvent::from_starknet_event_data(data).is_err());
+        }
+    }
+}
@@ -1,13 +1,10 @@
 pub mod dispatch_event;
+pub mod inserted_into_tree_event;
+pub mod reader;
 pub mod validator_announcement_event;
 
 pub use dispatch_event::*;
+pub use inserted_into_tree_event::*;
 pub use validator_announcement_event::*;
 
-use starknet::core::types::Felt;
-
-// TODO: Use VecDequeue for optimized pop_first (instead of Vec<Felt>)
-// https://doc.rust-lang.org/std/collections/struct.VecDeque.html
-pub trait FromStarknetEventData: Sized {
-    fn from_starknet_event_data(data: Vec<Felt>) -> anyhow::Result<Self>;
-}
+pub use pragma_utils::event_data::FromStarknetEventData;
@@ -0,0 +1,79 @@
+use starknet::core::types::U256;
+
+use crate::errors::DispatchEventError;
+
+/// Reads big-endian values from bytes one after the other, failing instead of panicking
+/// when there are not enough bytes left.
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Number of bytes not read yet.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    pub fn read_bytes<const N: usize>(&mut self, field: &'static str) -> Result<[u8; N], DispatchEventError> {
+        let remaining = self.remaining();
+        let bytes = self.bytes.get(self.position..self.position + N).ok_or(DispatchEventError::UnexpectedEnd {
+            field,
+            needed: N,
+            remaining,
+        })?;
+        self.position += N;
+        // The slice is exactly N bytes long
+        let mut array = [0_u8; N];
+        array.copy_from_slice(bytes);
+        Ok(array)
+    }
+
+    pub fn read_u8(&mut self, field: &'static str) -> Result<u8, DispatchEventError> {
+        Ok(u8::from_be_bytes(self.read_bytes(field)?))
+    }
+
+    pub fn read_u16(&mut self, field: &'static str) -> Result<u16, DispatchEventError> {
+        Ok(u16::from_be_bytes(self.read_bytes(field)?))
+    }
+
+    pub fn read_u64(&mut self, field: &'static str) -> Result<u64, DispatchEventError> {
+        Ok(u64::from_be_bytes(self.read_bytes(field)?))
+    }
+
+    pub fn read_u128(&mut self, field: &'static str) -> Result<u128, DispatchEventError> {
+        Ok(u128::from_be_bytes(self.read_bytes(field)?))
+    }
+
+    /// Reads a u256 encoded on 32 bytes, i.e its high part first & then its low part.
+    pub fn read_u256(&mut self, field: &'static str) -> Result<U256, DispatchEventError> {
+        let high = self.read_u128(field)?;
+        let low = self.read_u128(field)?;
+        Ok(U256::from_words(low, high))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_reader() {
+        let bytes: Vec<u8> = (1..=13).collect();
+        let mut reader = ByteReader::new(&bytes);
+        assert_eq!(reader.read_u8("a").unwrap(), 1);
+        assert_eq!(reader.read_u16("b").unwrap(), 0x0203);
+        assert_eq!(reader.read_u64("c").unwrap(), 0x0405060708090a0b);
+        assert_eq!(reader.remaining(), 2);
+
+        let error = reader.read_u128("d").unwrap_err();
+        assert!(matches!(error, DispatchEventError::UnexpectedEnd { field: "d", needed: 16, remaining: 2 }));
+        // Nothing is consumed on error
+        assert_eq!(reader.read_u16("e").unwrap(), 0x0c0d);
+        assert_eq!(reader.remaining(), 0);
+    }
+}
@@ -1,25 +1,54 @@
-use anyhow::Context;
 use starknet::core::types::{EthAddress, Felt};
 
-use pragma_utils::conversions::starknet::FeltVecToString;
+use pragma_utils::{
+    conversions::starknet::FeltVecToString,
+    event_data::{EventDataError, FeltReader},
+};
 
 use super::FromStarknetEventData;
 
-#[derive(Debug, Clone)]
+// Creates a ValidatorAnnouncement from a ValidatorAnnouncement starknet event data, which is:
+// 0. validator address
+// 1. storage location, as a span of felts
+#[derive(Debug, Clone, FromStarknetEventData)]
 pub struct ValidatorAnnouncementEvent {
+    #[event_data(with = read_validator)]
     pub validator: EthAddress,
+    #[event_data(with = read_storage_location)]
     pub storage_location: String,
 }
 
-impl FromStarknetEventData for ValidatorAnnouncementEvent {
-    fn from_starknet_event_data(data: Vec<Felt>) -> anyhow::Result<Self> {
-        let mut data = data.iter();
-        let validator = &data.next().context("Missing validator")?;
-        let validator = EthAddress::from_felt(validator).context("Invalid validator ETH address")?;
+fn read_validator(reader: &mut FeltReader<'_>) -> Result<EthAddress, EventDataError> {
+    let validator = reader.read_felt("validator")?;
+    EthAddress::from_felt(&validator).map_err(|e| EventDataError::Invalid { field: "validator", reason: e.to_string() })
+}
+
+fn read_storage_location(reader: &mut FeltReader<'_>) -> Result<String, EventDataError> {
+    Ok(reader.read_span::<Felt>("storage location")?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt_dec;
+
+    use super::*;
 
-        let storage_location: String = data.cloned().collect::<Vec<Felt>>().to_string();
+    #[test]
+    fn test_parse_validator_announcement_event() {
+        let data = vec![
+            Felt::from_hex("0x15d34aaf54267db7d7c367839aaf71a00a2c6a65").unwrap(),
+            Felt::THREE,
+            felt_dec!("180946006308525359965345158532346553211983108462325076142963585023296502126"),
+            felt_dec!("90954189295124463684969781689350429239725285131197301894846683156275291225"),
+            felt_dec!("276191619276790668637754154763775604"),
+        ];
+        let event = ValidatorAnnouncementEvent::from_starknet_event_data(data.clone()).unwrap();
+        assert_eq!(Felt::from(event.validator), data[0]);
+        assert_eq!(
+            event.storage_location,
+            "file:///var/folders/kr/z3l_6qyn3znb6gbnddtvgsn40000gn/T/.tmpdY51LU/checkpoint"
+        );
 
-        let new_event = Self { validator, storage_location };
-        Ok(new_event)
+        assert!(ValidatorAnnouncementEvent::from_starknet_event_data(data[..4].to_vec()).is_err());
     }
 }
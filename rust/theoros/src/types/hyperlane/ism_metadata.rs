@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use alloy::{
+    primitives::{B256, U256},
+    signers::Signature,
+};
+
+use crate::errors::MerkleTreeError;
+
+use super::{CheckpointWithMessageId, MerkleProof, TREE_DEPTH};
+
+/// Metadata expected by the Hyperlane `MessageIdMultisigIsm` to verify a message, i.e the
+/// checkpoint of the message signed by the validators.
+///
+/// Format:
+/// [   0:  32] Merkle tree hook address
+/// [  32:  64] Root of the signed checkpoint
+/// [  64:  68] Index of the signed checkpoint
+/// [  68:????] Validator signatures, 65 bytes each
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageIdMultisigMetadata {
+    pub merkle_tree_hook_address: U256,
+    pub root: B256,
+    pub index: u32,
+    /// Signatures of the checkpoint, in the order of the validators set of the ISM
+    pub signatures: Vec<Signature>,
+}
+
+impl MessageIdMultisigMetadata {
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(68 + self.signatures.len() * 65);
+        bytes.extend_from_slice(&self.merkle_tree_hook_address.to_be_bytes::<32>());
+        bytes.extend_from_slice(self.root.as_slice());
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        for signature in &self.signatures {
+            bytes.extend_from_slice(&signature.as_bytes());
+        }
+        bytes
+    }
+}
+
+/// Metadata expected by the Hyperlane `MerkleRootMultisigIsm` to verify a message, i.e the proof
+/// that the message is included in the merkle tree of a checkpoint signed by the validators.
+///
+/// Format:
+/// [   0:  32] Merkle tree hook address
+/// [  32:  36] Index of the message in the merkle tree
+/// [  36:  68] Message id of the signed checkpoint
+/// [  68:1092] Merkle proof of the message
+/// [1092:1096] Index of the signed checkpoint
+/// [1096:????] Validator signatures, 65 bytes each
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleRootMultisigMetadata {
+    pub merkle_tree_hook_address: U256,
+    pub proof: MerkleProof,
+    pub signed_message_id: U256,
+    pub signed_checkpoint_index: u32,
+    /// Signatures of the checkpoint, in the order of the validators set of the ISM
+    pub signatures: Vec<Signature>,
+}
+
+impl MerkleRootMultisigMetadata {
+    /// Builds the metadata of a message from its proof & the signatures of a checkpoint.
+    /// Fails if the proof does not lead to the root of the signed checkpoint.
+    pub fn new(
+        proof: MerkleProof,
+        checkpoint: &CheckpointWithMessageId,
+        signatures: Vec<Signature>,
+    ) -> Result<Self, MerkleTreeError> {
+        let index = checkpoint.checkpoint.index;
+        let root = &checkpoint.checkpoint.root;
+        let root = U256::from_str(root).map(B256::from).map_err(|_| MerkleTreeError::InvalidRoot(root.clone()))?;
+        let proof_root = proof.root();
+        if proof.index > index || proof_root != root {
+            return Err(MerkleTreeError::RootMismatch { index, expected: root, found: proof_root });
+        }
+
+        Ok(Self {
+            merkle_tree_hook_address: checkpoint.checkpoint.merkle_tree_hook_address,
+            proof,
+            signed_message_id: checkpoint.message_id,
+            signed_checkpoint_index: index,
+            signatures,
+        })
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(68 + TREE_DEPTH * 32 + 4 + self.signatures.len() * 65);
+        bytes.extend_from_slice(&self.merkle_tree_hook_address.to_be_bytes::<32>());
+        bytes.extend_from_slice(&self.proof.index.to_be_bytes());
+        bytes.extend_from_slice(&self.signed_message_id.to_be_bytes::<32>());
+        for sibling in &self.proof.path {
+            bytes.extend_from_slice(sibling.as_slice());
+        }
+        bytes.extend_from_slice(&self.signed_checkpoint_index.to_be_bytes());
+        for signature in &self.signatures {
+            bytes.extend_from_slice(&signature.as_bytes());
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::keccak256;
+
+    use super::*;
+    use crate::types::hyperlane::{Checkpoint, IncrementalMerkleTree};
+
+    #[test]
+    fn test_message_id_multisig_metadata() {
+        let metadata = MessageIdMultisigMetadata {
+            merkle_tree_hook_address: U256::from(42),
+            root: keccak256("root"),
+            index: 1211,
+            signatures: vec![Signature::test_signature(); 3],
+        };
+        let bytes = metadata.as_bytes();
+        assert_eq!(bytes.len(), 68 + 3 * 65);
+        assert_eq!(U256::from_be_slice(&bytes[0..32]), U256::from(42));
+        assert_eq!(&bytes[32..64], keccak256("root").as_slice());
+        assert_eq!(&bytes[64..68], 1211_u32.to_be_bytes().as_slice());
+        assert_eq!(&bytes[198..263], Signature::test_signature().as_bytes().as_slice());
+    }
+
+    #[test]
+    fn test_merkle_root_multisig_metadata() {
+        let mut tree = IncrementalMerkleTree::default();
+        for index in 0..5_u32 {
+            tree.insert(keccak256(index.to_be_bytes())).unwrap();
+        }
+        let checkpoint = CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: U256::from(42),
+                mailbox_domain: 1,
+                root: tree.root().to_string(),
+                index: 4,
+            },
+            message_id: keccak256(4_u32.to_be_bytes()).into(),
+        };
+        let signatures = vec![Signature::test_signature(); 2];
+
+        let metadata = MerkleRootMultisigMetadata::new(tree.proof(2, 5).unwrap(), &checkpoint, signatures).unwrap();
+        let bytes = metadata.as_bytes();
+        assert_eq!(bytes.len(), 1096 + 2 * 65);
+        assert_eq!(U256::from_be_slice(&bytes[0..32]), U256::from(42));
+        assert_eq!(&bytes[32..36], 2_u32.to_be_bytes().as_slice());
+        assert_eq!(&bytes[36..68], keccak256(4_u32.to_be_bytes()).as_slice());
+        // The first sibling of the message is the next message
+        assert_eq!(&bytes[68..100], tree.leaf(3).unwrap().as_slice());
+        assert_eq!(&bytes[1092..1096], 4_u32.to_be_bytes().as_slice());
+        assert_eq!(&bytes[1096..1161], Signature::test_signature().as_bytes().as_slice());
+
+        // The proof must lead to the checkpoint root
+        assert!(matches!(
+            MerkleRootMultisigMetadata::new(tree.proof(2, 4).unwrap(), &checkpoint, vec![]),
+            Err(MerkleTreeError::RootMismatch { index: 4, .. })
+        ));
+    }
+}
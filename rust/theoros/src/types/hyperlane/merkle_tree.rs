@@ -0,0 +1,296 @@
+use alloy::primitives::{keccak256, B256};
+
+use crate::errors::MerkleTreeError;
+
+/// Depth of the Hyperlane merkle tree.
+pub const TREE_DEPTH: usize = 32;
+
+lazy_static::lazy_static! {
+    /// Root of an empty subtree, for each height.
+    static ref ZERO_HASHES: [B256; TREE_DEPTH + 1] = {
+        let mut hashes = [B256::ZERO; TREE_DEPTH + 1];
+        for height in 1..=TREE_DEPTH {
+            hashes[height] = hash_pair(&hashes[height - 1], &hashes[height - 1]);
+        }
+        hashes
+    };
+}
+
+fn hash_pair(left: &B256, right: &B256) -> B256 {
+    keccak256([left.as_slice(), right.as_slice()].concat())
+}
+
+/// Incremental merkle tree of the message ids, mirroring the Hyperlane `MerkleTreeHook` contract.
+///
+/// Besides the branch used by the contract to compute the root, the tree keeps the nodes built
+/// from the leaves inserted locally so it can produce inclusion proofs.
+/// A tree created from an on-chain snapshot can only prove the leaves inserted after the snapshot.
+#[derive(Debug, Clone)]
+pub struct IncrementalMerkleTree {
+    /// Branch of the tree, as stored by the contract.
+    branch: [B256; TREE_DEPTH],
+    /// Number of leaves in the tree.
+    count: u32,
+    /// Branch of the snapshot the tree was created from.
+    snapshot_branch: [B256; TREE_DEPTH],
+    /// Number of leaves in the snapshot the tree was created from.
+    snapshot_count: u32,
+    /// Nodes built from the leaves inserted locally, per height (the leaves first, the root last).
+    /// The nodes of a height start at the position `snapshot_count >> height`.
+    nodes: Vec<Vec<B256>>,
+}
+
+impl Default for IncrementalMerkleTree {
+    fn default() -> Self {
+        Self::from_branch([B256::ZERO; TREE_DEPTH], 0)
+    }
+}
+
+impl IncrementalMerkleTree {
+    /// Creates a tree from the branch & the number of leaves of an existing tree,
+    /// i.e the state of the `MerkleTreeHook` contract.
+    pub fn from_branch(branch: [B256; TREE_DEPTH], count: u32) -> Self {
+        Self { branch, count, snapshot_branch: branch, snapshot_count: count, nodes: vec![vec![]; TREE_DEPTH + 1] }
+    }
+
+    /// Branch of the tree, as stored by the contract.
+    pub fn branch(&self) -> [B256; TREE_DEPTH] {
+        self.branch
+    }
+
+    /// Number of leaves in the tree. The index of the latest leaf is `count - 1`.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Inserts a leaf & returns its index.
+    pub fn insert(&mut self, leaf: B256) -> Result<u32, MerkleTreeError> {
+        let index = self.count;
+        let count = index.checked_add(1).ok_or(MerkleTreeError::TreeFull)?;
+
+        // Same as the contract: only the subtree completed by the leaf is stored in the branch
+        let mut node = leaf;
+        let mut size = count;
+        for height in 0..TREE_DEPTH {
+            if size & 1 == 1 {
+                self.branch[height] = node;
+                break;
+            }
+            node = hash_pair(&self.branch[height], &node);
+            size /= 2;
+        }
+
+        // Updates all the nodes from the leaf to the root. As the leaf is the latest one,
+        // the right siblings are always empty subtrees.
+        let mut node = leaf;
+        for height in 0..=TREE_DEPTH {
+            let position = u64::from(index) >> height;
+            self.set_node(height, position, node);
+            if height == TREE_DEPTH {
+                break;
+            }
+            node = if position & 1 == 0 {
+                hash_pair(&node, &ZERO_HASHES[height])
+            } else {
+                let left = self.stored_node(height, position - 1).expect("left sibling of the latest leaf is known");
+                hash_pair(&left, &node)
+            };
+        }
+
+        self.count = count;
+        Ok(index)
+    }
+
+    /// Current root of the tree, computed like the contract does.
+    pub fn root(&self) -> B256 {
+        let mut current = B256::ZERO;
+        for height in 0..TREE_DEPTH {
+            current = if (self.count >> height) & 1 == 1 {
+                hash_pair(&self.branch[height], &current)
+            } else {
+                hash_pair(&current, &ZERO_HASHES[height])
+            };
+        }
+        current
+    }
+
+    /// Root of the tree when it only had its first `count` leaves, i.e the root of the checkpoint
+    /// of index `count - 1`. Returns [None] if the leaves needed are older than the snapshot.
+    pub fn root_at(&self, count: u32) -> Option<B256> {
+        match count {
+            count if count > self.count => None,
+            count if count == self.count => Some(self.root()),
+            count => self.node_at(TREE_DEPTH, 0, count.into()),
+        }
+    }
+
+    /// Leaf of the provided index, if it was inserted locally.
+    pub fn leaf(&self, index: u32) -> Option<B256> {
+        if index >= self.count {
+            return None;
+        }
+        self.stored_node(0, index.into())
+    }
+
+    /// Proof that the leaf of the provided index is included in the tree when it had `count` leaves.
+    /// Returns [None] if the leaf is not part of the tree or if the nodes needed are older than the snapshot.
+    pub fn proof(&self, index: u32, count: u32) -> Option<MerkleProof> {
+        if index >= count || count > self.count {
+            return None;
+        }
+        let mut path = [B256::ZERO; TREE_DEPTH];
+        for (height, sibling) in path.iter_mut().enumerate() {
+            *sibling = self.node_at(height, (u64::from(index) >> height) ^ 1, count.into())?;
+        }
+        Some(MerkleProof { leaf: self.leaf(index)?, index, path })
+    }
+
+    /// Approximate memory used by the nodes built locally.
+    pub fn approximate_size(&self) -> usize {
+        self.nodes.iter().map(Vec::len).sum::<usize>() * size_of::<B256>()
+    }
+
+    /// Node at the provided height & position of the tree when it only had its first `count` leaves.
+    fn node_at(&self, height: usize, position: u64, count: u64) -> Option<B256> {
+        let first_leaf = position << height;
+        let end_leaf = (position + 1) << height;
+        if first_leaf >= count {
+            return Some(ZERO_HASHES[height]);
+        }
+        if end_leaf <= count {
+            // The subtree is complete, so the node did not change since
+            return self.stored_node(height, position);
+        }
+        let left = self.node_at(height - 1, 2 * position, count)?;
+        let right = self.node_at(height - 1, 2 * position + 1, count)?;
+        Some(hash_pair(&left, &right))
+    }
+
+    /// Latest value of the node at the provided height & position, if known.
+    fn stored_node(&self, height: usize, position: u64) -> Option<B256> {
+        let offset = u64::from(self.snapshot_count) >> height;
+        if position >= offset {
+            return self.nodes[height].get((position - offset) as usize).copied();
+        }
+        // The latest complete subtree of each height of the snapshot is part of its branch
+        if height < TREE_DEPTH && position + 1 == offset && offset & 1 == 1 {
+            return Some(self.snapshot_branch[height]);
+        }
+        None
+    }
+
+    fn set_node(&mut self, height: usize, position: u64, node: B256) {
+        let offset = u64::from(self.snapshot_count) >> height;
+        let nodes = &mut self.nodes[height];
+        let index = (position - offset) as usize;
+        if index == nodes.len() {
+            nodes.push(node);
+        } else {
+            nodes[index] = node;
+        }
+    }
+}
+
+/// Merkle proof that a leaf is included in a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub leaf: B256,
+    pub index: u32,
+    /// Siblings of the nodes from the leaf to the root.
+    pub path: [B256; TREE_DEPTH],
+}
+
+impl MerkleProof {
+    /// Root of the tree the leaf is included in, computed like the `MerkleLib.branchRoot` contract function.
+    pub fn root(&self) -> B256 {
+        let mut current = self.leaf;
+        for (height, sibling) in self.path.iter().enumerate() {
+            current = if (self.index >> height) & 1 == 1 {
+                hash_pair(sibling, &current)
+            } else {
+                hash_pair(&current, sibling)
+            };
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::b256;
+
+    use super::*;
+
+    fn leaf(index: u32) -> B256 {
+        keccak256(format!("message-{index}"))
+    }
+
+    /// Computes the root from all the leaves, without any optimization.
+    fn naive_root(leaves: &[B256]) -> B256 {
+        let mut level = leaves.to_vec();
+        for zero_hash in ZERO_HASHES.iter().take(TREE_DEPTH) {
+            if level.len() % 2 == 1 {
+                level.push(*zero_hash);
+            }
+            level = level.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+        }
+        level.first().copied().unwrap_or(ZERO_HASHES[TREE_DEPTH])
+    }
+
+    #[test]
+    fn test_empty_root() {
+        // `MerkleLib.INITIAL_ROOT` of the Hyperlane contracts
+        let initial_root = b256!("27ae5ba08d7291c96c8cbddcc148bf48a6d68c7974b94356f53754ef6171d757");
+        assert_eq!(IncrementalMerkleTree::default().root(), initial_root);
+    }
+
+    #[test]
+    fn test_insert_and_proofs() {
+        let mut tree = IncrementalMerkleTree::default();
+        let leaves: Vec<B256> = (0..33).map(leaf).collect();
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert_eq!(tree.insert(*leaf).unwrap(), index as u32);
+            assert_eq!(tree.root(), naive_root(&leaves[..=index]));
+        }
+
+        for count in 1..=tree.count() {
+            let root = naive_root(&leaves[..count as usize]);
+            assert_eq!(tree.root_at(count), Some(root));
+            for index in 0..count {
+                let proof = tree.proof(index, count).unwrap();
+                assert_eq!(proof.leaf, leaves[index as usize]);
+                assert_eq!(proof.root(), root, "proof of leaf #{index} in a tree of {count} leaves");
+            }
+        }
+        assert_eq!(tree.root_at(34), None);
+        assert_eq!(tree.proof(3, 3), None);
+    }
+
+    #[test]
+    fn test_from_snapshot() {
+        let leaves: Vec<B256> = (0..40).map(leaf).collect();
+        let mut full_tree = IncrementalMerkleTree::default();
+        for leaf in &leaves[..27] {
+            full_tree.insert(*leaf).unwrap();
+        }
+
+        let mut tree = IncrementalMerkleTree::from_branch(full_tree.branch, full_tree.count());
+        assert_eq!(tree.root(), full_tree.root());
+        for leaf in &leaves[27..] {
+            tree.insert(*leaf).unwrap();
+            full_tree.insert(*leaf).unwrap();
+            assert_eq!(tree.root(), full_tree.root());
+        }
+
+        // Only the leaves inserted after the snapshot can be proven
+        for count in 28..=40 {
+            assert_eq!(tree.root_at(count), full_tree.root_at(count));
+            for index in 27..count {
+                assert_eq!(tree.proof(index, count), full_tree.proof(index, count));
+            }
+        }
+        assert_eq!(tree.leaf(20), None);
+        assert_eq!(tree.proof(20, 40), None);
+        assert_eq!(tree.root_at(20), None);
+    }
+}
@@ -1,9 +1,13 @@
 pub mod checkpoint;
 pub mod checkpoint_fetchers;
 pub mod events;
+pub mod ism_metadata;
+pub mod merkle_tree;
 pub mod signing;
 
 pub use checkpoint::*;
 pub use checkpoint_fetchers::*;
 pub use events::*;
+pub use ism_metadata::*;
+pub use merkle_tree::*;
 pub use signing::*;
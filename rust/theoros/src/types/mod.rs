@@ -1,3 +1,4 @@
 pub mod calldata;
+pub mod feeds_metadata;
 pub mod hyperlane;
 pub mod state;
@@ -5,6 +5,7 @@ use prometheus::Registry;
 use crate::{
     rpc::{evm::HyperlaneValidatorsMapping, starknet::StarknetRpc},
     storage::TheorosStorage,
+    types::feeds_metadata::FeedsMetadataRegistry,
 };
 
 #[derive(Clone)]
@@ -12,6 +13,7 @@ pub struct AppState {
     pub starknet_rpc: Arc<StarknetRpc>,
     pub hyperlane_validators_mapping: Arc<HyperlaneValidatorsMapping>,
     pub storage: Arc<TheorosStorage>,
+    pub feeds_metadata: Arc<FeedsMetadataRegistry>,
     #[allow(unused)]
     pub metrics_registry: Registry, // already wrapped into an Arc
     pub ws: Arc<WsState>,
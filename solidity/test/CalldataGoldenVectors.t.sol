@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+import "forge-std/Test.sol";
+import "../src/Pragma.sol";
+import "./../src/Hyperlane.sol";
+import {PragmaHarness} from "./TestUtils.sol";
+import {TransparentUpgradeableProxy} from "@openzeppelin/contracts/proxy/transparent/TransparentUpgradeableProxy.sol";
+
+/// Decodes the calldata golden vectors produced by Theoros (see `test-vectors/calldata.json`).
+contract CalldataGoldenVectorsTest is Test {
+    string private vectors;
+
+    function setUp() public {
+        vectors = vm.readFile(string.concat(vm.projectRoot(), "/../test-vectors/calldata.json"));
+    }
+
+    function testGoldenVectorSpotMedianBtcUsd() public {
+        _testGoldenVector(0);
+    }
+
+    function testGoldenVectorSpotMedianUnorderedDuplicatedSignatures() public {
+        _testGoldenVector(1);
+    }
+
+    function _testGoldenVector(uint256 index) internal {
+        string memory key = string.concat(".vectors[", vm.toString(index), "]");
+        PragmaHarness pragmaHarness = _configurePragma(key);
+
+        bytes memory calldata_ = vm.parseJsonBytes(vectors, string.concat(key, ".calldata"));
+        uint8 numUpdates = pragmaHarness.exposed_updateDataInfoFromUpdate(calldata_);
+        assertEq(numUpdates, 1, "Number of updates should be 1");
+
+        bytes32 feedId = vm.parseJsonBytes32(vectors, string.concat(key, ".feed_id"));
+        SpotMedian memory spotMedian = pragmaHarness.exposed_spotMedianFeeds(feedId);
+
+        assertEq(
+            spotMedian.metadata.timestamp,
+            vm.parseJsonUint(vectors, string.concat(key, ".publish_time")),
+            "Timestamp should match"
+        );
+        assertEq(
+            spotMedian.metadata.numberOfSources,
+            vm.parseJsonUint(vectors, string.concat(key, ".num_sources_aggregated")),
+            "Number of sources should match"
+        );
+        assertEq(
+            spotMedian.metadata.decimals,
+            vm.parseJsonUint(vectors, string.concat(key, ".decimals")),
+            "Decimals should match"
+        );
+        assertEq(spotMedian.price, vm.parseJsonUint(vectors, string.concat(key, ".price")), "Price should match");
+        assertEq(spotMedian.volume, vm.parseJsonUint(vectors, string.concat(key, ".volume")), "Volume should match");
+    }
+
+    /// Deploys Pragma with the validators & the data source of the vector.
+    function _configurePragma(string memory key) internal returns (PragmaHarness) {
+        address[] memory validators = vm.parseJsonAddressArray(vectors, string.concat(key, ".validators"));
+        Hyperlane hyperlane = new Hyperlane(validators);
+
+        uint32[] memory chainIds = new uint32[](1);
+        chainIds[0] = uint32(vm.parseJsonUint(vectors, string.concat(key, ".emitter_chain_id")));
+        bytes32[] memory emitterAddresses = new bytes32[](1);
+        emitterAddresses[0] = vm.parseJsonBytes32(vectors, string.concat(key, ".emitter_address"));
+
+        PragmaHarness pragmaImpl = new PragmaHarness();
+        bytes memory initData = abi.encodeWithSelector(
+            pragmaImpl.initialize.selector,
+            address(hyperlane),
+            address(this),
+            chainIds,
+            emitterAddresses,
+            120,
+            0.1 ether
+        );
+        TransparentUpgradeableProxy proxy =
+            new TransparentUpgradeableProxy(address(pragmaImpl), address(this), initData);
+        return PragmaHarness(address(proxy));
+    }
+}
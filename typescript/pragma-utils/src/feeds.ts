@@ -13,6 +13,9 @@ const MAX_PAIR_ID = BigInt(
 
 export enum AssetClass {
   Crypto = 0,
+  Forex = 1,
+  Equities = 2,
+  Commodities = 3,
 }
 
 export enum UniqueVariant {